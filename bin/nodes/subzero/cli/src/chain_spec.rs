@@ -26,9 +26,11 @@ use node_runtime::{
 	DemocracyConfig,GrandpaConfig, ImOnlineConfig, SessionConfig, SessionKeys, StakerStatus,
 	StakingConfig, ElectionsConfig, IndicesConfig, SocietyConfig, SudoConfig, SystemConfig,
 	TechnicalCommitteeConfig, wasm_binary_unwrap,
+	ZeroSenseConfig, GameDaoControlConfig, GameDaoGovernanceConfig, GameDaoCrowdfundingConfig,
+	GameDaoTangramConfig, GameDaoControl, ControlType, ControlAccessModel, ControlFeeModel,
 };
 use node_runtime::Block;
-use node_runtime::constants::currency::*;
+use node_runtime::constants::{currency::*, time::DAYS};
 use sc_service::ChainType;
 use hex_literal::hex;
 use sc_telemetry::TelemetryEndpoints;
@@ -38,7 +40,7 @@ use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_runtime::{Perbill, traits::{Verify, IdentifyAccount}};
 
-pub use node_primitives::{AccountId, Balance, Signature};
+pub use node_primitives::{AccountId, Balance, Signature, SenseProps};
 pub use node_runtime::GenesisConfig;
 
 type AccountPublic = <Signature as Verify>::Signer;
@@ -219,6 +221,8 @@ pub fn testnet_genesis(
 	endowed_accounts: Option<Vec<AccountId>>,
 	enable_println: bool,
 ) -> GenesisConfig {
+	// dev accounts get a gamedao demo world
+	let demo = endowed_accounts.is_none();
 	let mut endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(|| {
 		vec![
 			get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -246,6 +250,45 @@ pub fn testnet_genesis(
 	const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
 	const STASH: Balance = ENDOWMENT / 1000;
 
+	// gamedao demo world:
+	// a studio dao and a guild with members, each with a realm,
+	// an extra class and items, a guild campaign and sense entities
+
+	const TREASURY: Balance = 1_000 * DOLLARS;
+
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+	let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+	let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+	let eve = get_account_id_from_seed::<sr25519::Public>("Eve");
+	let ferdie = get_account_id_from_seed::<sr25519::Public>("Ferdie");
+
+	let bodies = if demo { vec![
+		(
+			alice.clone(), alice.clone(), b"ZERO Studio".to_vec(), b"".to_vec(),
+			ControlType::Dao, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+			vec![bob.clone(), charlie.clone(), dave.clone()],
+		),
+		(
+			bob.clone(), bob.clone(), b"Builders Guild".to_vec(), b"".to_vec(),
+			ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::Transfer, 10 * DOLLARS, 64,
+			vec![charlie.clone(), eve.clone(), ferdie.clone()],
+		),
+	] } else { vec![] };
+	// keyless body treasuries by body nonce
+	let treasuries: Vec<AccountId> = (0..bodies.len() as u128)
+		.map(GameDaoControl::treasury_account)
+		.collect();
+
+	let entities = if demo { vec![
+		(alice.clone(), b"".to_vec(), 1_000, 500, 100),
+		(bob.clone(), b"".to_vec(), 800, 400, 80),
+		(charlie.clone(), b"".to_vec(), 300, 100, 40),
+		(dave.clone(), b"".to_vec(), 200, 50, 20),
+		(eve.clone(), b"".to_vec(), 100, 20, 10),
+		(ferdie.clone(), b"".to_vec(), 50, 10, 5),
+	] } else { vec![] };
+
 	GenesisConfig {
 		frame_system: Some(SystemConfig {
 			code: wasm_binary_unwrap().to_vec(),
@@ -254,6 +297,7 @@ pub fn testnet_genesis(
 		pallet_balances: Some(BalancesConfig {
 			balances: endowed_accounts.iter().cloned()
 				.map(|x| (x, ENDOWMENT))
+				.chain(treasuries.iter().cloned().map(|x| (x, TREASURY)))
 				.collect()
 		}),
 		pallet_indices: Some(IndicesConfig {
@@ -327,6 +371,33 @@ pub fn testnet_genesis(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		module_sense: Some(ZeroSenseConfig {
+			entities,
+			reporters: if demo { vec![(SenseProps::XP, alice.clone())] } else { vec![] },
+		}),
+		module_control: Some(GameDaoControlConfig {
+			bodies,
+		}),
+		module_signal: Some(GameDaoGovernanceConfig {
+			proposal_time_limit: 30 * DAYS,
+		}),
+		module_flow: Some(GameDaoCrowdfundingConfig {
+			campaigns: if demo { vec![
+				(1, b"Guild Hall".to_vec(), 1_000 * DOLLARS, 10 * DOLLARS, 30 * DAYS, b"".to_vec()),
+			] } else { vec![] },
+		}),
+		module_tangram: Some(GameDaoTangramConfig {
+			realms: vec![],
+			classes: if demo { vec![
+				(0, b"heroes".to_vec(), 100),
+				(1, b"badges".to_vec(), 1_000),
+			] } else { vec![] },
+			items: if demo { vec![
+				(0, 1, b"Founder".to_vec(), b"".to_vec(), alice.clone()),
+				(0, 1, b"Scout".to_vec(), b"".to_vec(), bob.clone()),
+				(1, 1, b"Builder".to_vec(), b"".to_vec(), eve.clone()),
+			] } else { vec![] },
+		}),
 	}
 }
 
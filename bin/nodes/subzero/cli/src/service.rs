@@ -659,7 +659,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = node_runtime::impls::ChargeSponsoredPayment::from(0, None);
 				let extra = (
 					check_spec_version,
 					check_tx_version,
@@ -119,5 +119,10 @@ pub fn config_endowed(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		module_sense: Some(Default::default()),
+		module_control: Some(Default::default()),
+		module_signal: Some(Default::default()),
+		module_flow: Some(Default::default()),
+		module_tangram: Some(Default::default()),
 	}
 }
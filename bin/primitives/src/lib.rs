@@ -132,6 +132,23 @@ pub enum AuthoritysOriginId {
 	SenseNet,
 }
 
+/// Read access to CONTROL bodies for pallets which control builds on
+pub trait BodyProvider<AccountId, Hash> {
+	/// controller of an active body
+	fn controller(body: &Hash) -> Option<AccountId>;
+	/// keyless treasury of a body
+	fn treasury(body: &Hash) -> Option<AccountId>;
+	/// active, direct or inherited, member of a body
+	fn is_member(body: &Hash, who: &AccountId) -> bool;
+}
+
+/// no control connected, there are no bodies
+impl<AccountId, Hash> BodyProvider<AccountId, Hash> for () {
+	fn controller(_: &Hash) -> Option<AccountId> { None }
+	fn treasury(_: &Hash) -> Option<AccountId> { None }
+	fn is_member(_: &Hash, _: &AccountId) -> bool { false }
+}
+
 //
 //	s e n s e
 //
@@ -143,7 +160,102 @@ pub enum SenseProps {
 	REP   = 1,
 	TRUST = 2,
 }
+impl Default for SenseProps { fn default() -> Self { Self::XP } }
+
+/// Read access to SENSE, e.g. to gate actions by reputation
+pub trait SenseScore<AccountId> {
+	/// weighted score across xp, rep and trust
+	fn score(who: &AccountId) -> u64;
+	/// current value of a single property
+	fn value(property: SenseProps, who: &AccountId) -> u64;
+}
+
+/// no sense connected, nothing is gated
+impl<AccountId> SenseScore<AccountId> for () {
+	fn score(_: &AccountId) -> u64 { u64::MAX }
+	fn value(_: SenseProps, _: &AccountId) -> u64 { u64::MAX }
+}
+
+/// GameDAO actions which earn experience and reputation
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum SenseAction {
+	Contribute = 0,
+	Vote       = 1,
+	JoinBody   = 2,
+	CreateItem = 3,
+}
+
+/// Write access to SENSE, called by pallets when an account acts
+pub trait SenseHooks<AccountId> {
+	fn on_action(action: SenseAction, who: &AccountId);
+}
+
+/// no sense connected, nothing is rewarded
+impl<AccountId> SenseHooks<AccountId> for () {
+	fn on_action(_: SenseAction, _: &AccountId) {}
+}
+
 
+//
+//	t a n g r a m
+//
+
+/// Read access to TANGRAM realms, e.g. to scope realm reporters
+pub trait RealmProvider<AccountId> {
+	/// owns an item of realm `realm`
+	fn is_participant(realm: u64, who: &AccountId) -> bool;
+}
+
+/// no tangram connected, there are no realms
+impl<AccountId> RealmProvider<AccountId> for () {
+	fn is_participant(_: u64, _: &AccountId) -> bool { false }
+}
+
+/// Rarity tier of an item, the first byte of its dna
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Rarity {
+	LOW  = 0,
+	HIGH = 1,
+	RARE = 2,
+	EPIC = 3,
+}
+impl Default for Rarity { fn default() -> Self { Self::LOW } }
+
+/// Odds of the rarity tiers of a class in parts per ten thousand,
+/// the remainder is low. Odds halve every `halving` blocks after
+/// the first mint of the class, zero keeps them constant.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct RarityTable {
+	pub epic: u16,
+	pub rare: u16,
+	pub high: u16,
+	pub halving: u32,
+}
+
+impl RarityTable {
+	pub const PARTS: u16 = 10_000;
+
+	/// odds do not exceed the parts
+	pub fn is_valid(&self) -> bool {
+		self.epic as u32 + self.rare as u32 + self.high as u32 <= Self::PARTS as u32
+	}
+
+	/// tier of a roll in `0..PARTS`, `age` blocks after the first mint
+	pub fn tier(&self, roll: u16, age: u32) -> Rarity {
+		let shift = if self.halving == 0 { 0 } else { (age / self.halving).min(16) };
+		let epic = (self.epic as u32) >> shift;
+		let rare = epic + ((self.rare as u32) >> shift);
+		let high = rare + ((self.high as u32) >> shift);
+		let roll = roll as u32;
+		if roll < epic { Rarity::EPIC }
+		else if roll < rare { Rarity::RARE }
+		else if roll < high { Rarity::HIGH }
+		else { Rarity::LOW }
+	}
+}
 
 //
 //	g o v e r n a n c e
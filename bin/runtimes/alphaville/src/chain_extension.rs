@@ -0,0 +1,150 @@
+//! Chain extension exposing GameDAO and tangram to ink! contracts.
+//!
+//! Contracts call `seal_call_chain_extension` with one of the function ids below,
+//! arguments and results are SCALE encoded tuples. Queries are free of side effects,
+//! tangram mint and transfer act with the contract as a realm operator, see
+//! `module_tangram::Call::set_operator`.
+
+use codec::Encode;
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+use node_primitives::{AccountId, Balance, BodyProvider, Hash, SenseProps};
+use module_tangram::{ClassIndex, RealmIndex};
+use crate::{GameDaoControl, GameDaoCrowdfunding, GameDaoTangram, Runtime, ZeroSense};
+
+//
+//	function ids
+//
+
+/// `(body, account) -> bool`, active membership
+pub const CONTROL_IS_MEMBER: u32 = 0x0001_0001;
+/// `(body, account) -> ControlMemberState`
+pub const CONTROL_MEMBER_STATE: u32 = 0x0001_0002;
+/// `body -> Option<AccountId>`, controller of an active body
+pub const CONTROL_CONTROLLER: u32 = 0x0001_0003;
+/// `body -> Option<AccountId>`
+pub const CONTROL_TREASURY: u32 = 0x0001_0004;
+
+/// `campaign -> FlowState`
+pub const FLOW_STATE: u32 = 0x0002_0001;
+/// `(campaign, account) -> Balance`
+pub const FLOW_CONTRIBUTION: u32 = 0x0002_0002;
+/// `campaign -> Balance`
+pub const FLOW_BALANCE: u32 = 0x0002_0003;
+
+/// `item -> Option<AccountId>`
+pub const TANGRAM_OWNER: u32 = 0x0003_0001;
+/// `(realm, class, name, cid, to) -> Hash`, the contract operates the realm
+pub const TANGRAM_MINT: u32 = 0x0003_0002;
+/// `(item, to) -> ()`, item owned by the contract, or by its caller
+/// when the contract operates the realm of the item
+pub const TANGRAM_TRANSFER: u32 = 0x0003_0003;
+
+/// `account -> u64`, weighted score
+pub const SENSE_SCORE: u32 = 0x0004_0001;
+/// `(property, account) -> u64`
+pub const SENSE_VALUE: u32 = 0x0004_0002;
+
+/// Weight of a tangram mint, matching `create_item`
+const MINT_WEIGHT: Weight = 5_000_000;
+/// Weight per input byte of a tangram mint, covering name and cid
+const MINT_WEIGHT_PER_BYTE: Weight = 10_000;
+/// Weight of a tangram transfer, matching `transfer` without the moved tree
+const TRANSFER_WEIGHT: Weight = 1_000_000;
+
+pub struct GameDaoExtension;
+
+impl ChainExtension<Runtime> for GameDaoExtension {
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = Runtime>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let mut env = env.buf_in_buf_out();
+		let db = <Runtime as frame_system::Config>::DbWeight::get();
+
+		let output: Vec<u8> = match func_id {
+
+			CONTROL_IS_MEMBER => {
+				env.charge_weight(db.reads(1))?;
+				let (body, account): (Hash, AccountId) = env.read_as()?;
+				GameDaoControl::is_active_member(&body, &account).encode()
+			},
+			CONTROL_MEMBER_STATE => {
+				env.charge_weight(db.reads(1))?;
+				let (body, account): (Hash, AccountId) = env.read_as()?;
+				GameDaoControl::body_member_state((body, account)).encode()
+			},
+			CONTROL_CONTROLLER => {
+				env.charge_weight(db.reads(2))?;
+				let body: Hash = env.read_as()?;
+				<GameDaoControl as BodyProvider<_, _>>::controller(&body).encode()
+			},
+			CONTROL_TREASURY => {
+				env.charge_weight(db.reads(2))?;
+				let body: Hash = env.read_as()?;
+				<GameDaoControl as BodyProvider<_, _>>::treasury(&body).encode()
+			},
+
+			FLOW_STATE => {
+				env.charge_weight(db.reads(1))?;
+				let campaign: Hash = env.read_as()?;
+				GameDaoCrowdfunding::campaign_state(campaign).encode()
+			},
+			FLOW_CONTRIBUTION => {
+				env.charge_weight(db.reads(1))?;
+				let (campaign, account): (Hash, AccountId) = env.read_as()?;
+				GameDaoCrowdfunding::campaign_contribution((campaign, account)).encode()
+			},
+			FLOW_BALANCE => {
+				env.charge_weight(db.reads(1))?;
+				let campaign: Hash = env.read_as()?;
+				let balance: Balance = GameDaoCrowdfunding::campaign_balance(campaign);
+				balance.encode()
+			},
+
+			TANGRAM_OWNER => {
+				env.charge_weight(db.reads(1))?;
+				let item: Hash = env.read_as()?;
+				GameDaoTangram::owner_of(&item).encode()
+			},
+			TANGRAM_MINT => {
+				// name and cid are unbounded, charge for the input before reading it
+				env.charge_weight(MINT_WEIGHT.saturating_add(
+					MINT_WEIGHT_PER_BYTE.saturating_mul(env.in_len().into())
+				))?;
+				let (realm, class, name, cid, to): (RealmIndex, ClassIndex, Vec<u8>, Vec<u8>, AccountId) = env.read_as()?;
+				let operator = env.ext().address().clone();
+				GameDaoTangram::operator_mint( &operator, realm, class, name, cid, &to )?.encode()
+			},
+			TANGRAM_TRANSFER => {
+				env.charge_weight(TRANSFER_WEIGHT.saturating_add(GameDaoTangram::tree_transfer_weight()))?;
+				let (item, to): (Hash, AccountId) = env.read_as()?;
+				let operator = env.ext().address().clone();
+				let caller = env.ext().caller().clone();
+				GameDaoTangram::operator_transfer( &operator, &caller, &item, &to )?;
+				().encode()
+			},
+
+			SENSE_SCORE => {
+				env.charge_weight(db.reads(4))?;
+				let account: AccountId = env.read_as()?;
+				ZeroSense::score(&account).encode()
+			},
+			SENSE_VALUE => {
+				env.charge_weight(db.reads(2))?;
+				let (property, account): (SenseProps, AccountId) = env.read_as()?;
+				ZeroSense::value(property, &account).encode()
+			},
+
+			_ => return Err(DispatchError::Other("unknown chain extension function")),
+		};
+
+		env.write(&output, false, None)?;
+		Ok(RetVal::Converging(0))
+	}
+}
@@ -18,7 +18,19 @@
 //! Some configurable implementations as associated type for the substrate runtime.
 
 use frame_support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, NegativeImbalance};
+use frame_support::weights::DispatchInfo;
+use codec::{Encode, Decode};
+use sp_runtime::{
+	traits::{SignedExtension, DispatchInfoOf, PostDispatchInfoOf, Zero},
+	transaction_validity::{TransactionValidity, TransactionValidityError, InvalidTransaction},
+	DispatchResult,
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use node_primitives::{AccountId, Balance, Hash};
+use crate::{
+	Balances, Authorship, NegativeImbalance, Call, Runtime, TransactionPayment,
+	GameDaoControl, GameDaoGovernance, GameDaoTangram,
+};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -27,6 +39,133 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// GameDAO calls a body may sponsor for its members: proposals, votes and
+/// gameplay that moves no funds or items out of the member's hands,
+/// each within the body itself or one of its realms.
+pub fn is_sponsored(call: &Call, body: &Hash) -> bool {
+	let realm_body = |item: &Hash| GameDaoTangram::realm_of_item(item)
+		.map(GameDaoTangram::owner_for_realm);
+	match call {
+		Call::GameDaoGovernance(module_signal::Call::general_proposal(context, ..)) |
+		Call::GameDaoGovernance(module_signal::Call::multiple_proposal(context, ..)) =>
+			context == body,
+		Call::GameDaoGovernance(module_signal::Call::simple_vote(proposal, ..)) |
+		Call::GameDaoGovernance(module_signal::Call::choice_vote(proposal, ..)) |
+		Call::GameDaoGovernance(module_signal::Call::reveal_vote(proposal, ..)) =>
+			GameDaoGovernance::proposal_body(proposal).as_ref() == Some(body),
+		Call::GameDaoTangram(module_tangram::Call::equip(item, ..)) |
+		Call::GameDaoTangram(module_tangram::Call::unequip(item)) =>
+			realm_body(item).as_ref() == Some(body),
+		_ => false,
+	}
+}
+
+/// Charge the transaction fee like `ChargeTransactionPayment`, or from the
+/// treasury of the `sponsor` body. A body sponsors allowed GameDAO calls of its
+/// active members within their budget, tips are never sponsored.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeSponsoredPayment {
+	#[codec(compact)]
+	tip: Balance,
+	sponsor: Option<Hash>,
+}
+
+impl ChargeSponsoredPayment {
+	/// utility constructor. Used only in client/factory code.
+	pub fn from(tip: Balance, sponsor: Option<Hash>) -> Self {
+		Self { tip, sponsor }
+	}
+
+	// account paying the fee, the signer or a sponsoring treasury
+	fn payer(
+		&self,
+		who: &AccountId,
+		call: &Call,
+		info: &DispatchInfo,
+		len: usize,
+	) -> Result<AccountId, TransactionValidityError> {
+		let body = match self.sponsor {
+			Some(body) => body,
+			None => return Ok(who.clone()),
+		};
+		if !self.tip.is_zero() || !is_sponsored(call, &body) {
+			return Err(InvalidTransaction::Payment.into())
+		}
+		let fee = TransactionPayment::compute_fee(len as u32, info, Zero::zero());
+		GameDaoControl::sponsor(&body, who, fee).ok_or(InvalidTransaction::Payment.into())
+	}
+}
+
+impl sp_std::fmt::Debug for ChargeSponsoredPayment {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeSponsoredPayment<{:?}, {:?}>", self.tip, self.sponsor)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl SignedExtension for ChargeSponsoredPayment {
+	const IDENTIFIER: &'static str = "ChargeSponsoredPayment";
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		<ChargeTransactionPayment<Runtime> as SignedExtension>::Pre,
+		// sponsor, member and predicted fee
+		Option<(Hash, AccountId, Balance)>,
+	);
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let payer = self.payer(who, call, info, len)?;
+		ChargeTransactionPayment::<Runtime>::from(self.tip).validate(&payer, call, info, len)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let payer = self.payer(who, call, info, len)?;
+		let pre = ChargeTransactionPayment::<Runtime>::from(self.tip).pre_dispatch(&payer, call, info, len)?;
+		let sponsored = match self.sponsor {
+			Some(body) => {
+				let fee = TransactionPayment::compute_fee(len as u32, info, Zero::zero());
+				GameDaoControl::note_sponsored(&body, who, fee);
+				Some((body, who.clone(), fee))
+			},
+			None => None,
+		};
+		Ok((pre, sponsored))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (pre, sponsored) = pre;
+		if let Some((body, who, fee)) = sponsored {
+			let actual_fee = TransactionPayment::compute_actual_fee(len as u32, info, post_info, Zero::zero());
+			GameDaoControl::refund_sponsored(&body, &who, fee.saturating_sub(actual_fee));
+		}
+		ChargeTransactionPayment::<Runtime>::post_dispatch(pre, info, post_info, len, result)
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use sp_runtime::{assert_eq_error_rate, FixedPointNumber, traits::Convert};
@@ -46,7 +46,7 @@ use sp_core::{
 	OpaqueMetadata,
 };
 pub use node_primitives::{AccountId, Signature};
-use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
+use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment, SenseAction};
 use sp_api::impl_runtime_apis;
 use sp_runtime::{
 	Permill, Perbill, Perquintill, Percent, ApplyExtrinsicResult,
@@ -97,11 +97,15 @@ pub use pallet_balances::Call as BalancesCall;
 pub use frame_system::Call as SystemCall;
 #[cfg(any(feature = "std", test))]
 pub use pallet_staking::StakerStatus;
+pub use module_control::{ControlType, ControlAccessModel, ControlFeeModel};
 
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
 use impls::Author;
 
+/// Chain extension exposing GameDAO and tangram to contracts.
+pub mod chain_extension;
+
 /// Constant values used within the runtime.
 pub mod constants;
 use constants::{time::*, currency::*};
@@ -128,10 +132,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 50,
+	spec_version: 51,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// Native version.
@@ -290,6 +294,12 @@ pub enum ProxyType {
 	NonTransfer,
 	Governance,
 	Staking,
+	/// votes on gamedao proposals
+	GameDaoVoting,
+	/// contributions to gamedao campaigns
+	GameDaoCampaign,
+	/// item transfers within a tangram realm
+	TangramGameplay(module_tangram::RealmIndex),
 }
 impl Default for ProxyType { fn default() -> Self { Self::Any } }
 impl InstanceFilter<Call> for ProxyType {
@@ -300,7 +310,27 @@ impl InstanceFilter<Call> for ProxyType {
 				c,
 				Call::Balances(..) |
 				Call::Vesting(pallet_vesting::Call::vested_transfer(..)) |
-				Call::Indices(pallet_indices::Call::transfer(..))
+				Call::Indices(pallet_indices::Call::transfer(..)) |
+				Call::GameDaoControl(module_control::Call::create(..)) |
+				Call::GameDaoControl(module_control::Call::create_child(..)) |
+				Call::GameDaoControl(module_control::Call::add_member(..)) |
+				Call::GameDaoControl(module_control::Call::fund_child(..)) |
+				Call::GameDaoCrowdfunding(module_flow::Call::contribute(..)) |
+				Call::GameDaoTangram(module_tangram::Call::transfer(..)) |
+				Call::GameDaoTangram(module_tangram::Call::burn(..)) |
+				Call::GameDaoTangram(module_tangram::Call::craft(..)) |
+				Call::GameDaoTangram(module_tangram::Call::list(..)) |
+				Call::GameDaoTangram(module_tangram::Call::start_auction(..)) |
+				Call::GameDaoTangram(module_tangram::Call::delist(..)) |
+				Call::GameDaoTangram(module_tangram::Call::buy(..)) |
+				Call::GameDaoTangram(module_tangram::Call::bid(..)) |
+				Call::GameDaoTangram(module_tangram::Call::settle_auction(..)) |
+				Call::GameDaoTangram(module_tangram::Call::make_offer(..)) |
+				Call::GameDaoTangram(module_tangram::Call::accept_offer(..)) |
+				Call::GameDaoTangram(module_tangram::Call::lend(..)) |
+				Call::GameDaoTangram(module_tangram::Call::cancel_lend(..)) |
+				Call::GameDaoTangram(module_tangram::Call::rent(..)) |
+				Call::GameDaoTangram(module_tangram::Call::redeem(..))
 			),
 			ProxyType::Governance => matches!(
 				c,
@@ -309,9 +339,26 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Society(..) |
 				Call::TechnicalCommittee(..) |
 				Call::Elections(..) |
-				Call::Treasury(..)
+				Call::Treasury(..) |
+				Call::GameDaoGovernance(..)
 			),
 			ProxyType::Staking => matches!(c, Call::Staking(..)),
+			ProxyType::GameDaoVoting => matches!(
+				c,
+				Call::GameDaoGovernance(module_signal::Call::simple_vote(..)) |
+				Call::GameDaoGovernance(module_signal::Call::choice_vote(..)) |
+				Call::GameDaoGovernance(module_signal::Call::commit_vote(..)) |
+				Call::GameDaoGovernance(module_signal::Call::reveal_vote(..))
+			),
+			ProxyType::GameDaoCampaign => matches!(
+				c,
+				Call::GameDaoCrowdfunding(module_flow::Call::contribute(..))
+			),
+			ProxyType::TangramGameplay(realm) => match c {
+				Call::GameDaoTangram(module_tangram::Call::transfer(_, item)) =>
+					GameDaoTangram::realm_of_item(item) == Some(*realm),
+				_ => false,
+			},
 		}
 	}
 	fn is_superset(&self, o: &Self) -> bool {
@@ -319,7 +366,10 @@ impl InstanceFilter<Call> for ProxyType {
 			(x, y) if x == y => true,
 			(ProxyType::Any, _) => true,
 			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, ProxyType::GameDaoCampaign) => false,
+			(ProxyType::NonTransfer, ProxyType::TangramGameplay(_)) => false,
 			(ProxyType::NonTransfer, _) => true,
+			(ProxyType::Governance, ProxyType::GameDaoVoting) => true,
 			_ => false,
 		}
 	}
@@ -795,7 +845,7 @@ impl pallet_contracts::Config for Runtime {
 	type MaxValueSize = MaxValueSize;
 	type WeightPrice = pallet_transaction_payment::Module<Self>;
 	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-	type ChainExtension = ();
+	type ChainExtension = chain_extension::GameDaoExtension;
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 }
@@ -841,7 +891,7 @@ impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for R
 			frame_system::CheckEra::<Runtime>::from(era),
 			frame_system::CheckNonce::<Runtime>::from(nonce),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+			impls::ChargeSponsoredPayment::from(tip, None),
 		);
 		let raw_payload = SignedPayload::new(call, extra)
 			.map_err(|e| {
@@ -1052,9 +1102,42 @@ impl pallet_assets::Config for Runtime {
 //	SenseModuleId::get().into_account(),
 //
 
+parameter_types! {
+	pub const SenseMaxBatchSize: u32 = 64;
+	pub const SenseEntityDeposit: Balance = 1 * DOLLARS;
+	pub const SenseReleaseTrust: u64 = 10;
+	pub const SenseJudgementDeposit: Balance = 10 * DOLLARS;
+	pub const SenseMaxOpenJudgements: u32 = 16;
+	pub const SenseMaxRewardsPerBlock: u32 = 4;
+}
+
+// xp and rep earned by gamedao actions
+pub struct SenseRewards;
+impl module_sense::RewardSchedule for SenseRewards {
+	fn reward(action: SenseAction) -> (module_sense::SenseDelta, module_sense::SenseDelta) {
+		match action {
+			SenseAction::Contribute => (10, 2),
+			SenseAction::Vote       => (5, 1),
+			SenseAction::JoinBody   => (5, 0),
+			SenseAction::CreateItem => (2, 0),
+		}
+	}
+}
+
 impl module_sense::Config for Runtime {
 	type Event = Event;
 	type ForceOrigin = EnsureRoot<AccountId>;
+	type MaxBatchSize = SenseMaxBatchSize;
+	type Realms = GameDaoTangram;
+	type Currency = Balances;
+	type EntityDeposit = SenseEntityDeposit;
+	type ReleaseTrust = SenseReleaseTrust;
+	type JudgementDeposit = SenseJudgementDeposit;
+	type MaxOpenJudgements = SenseMaxOpenJudgements;
+	type JudgementOrigin = EnsureRootOrHalfCouncil;
+	type Slash = Treasury;
+	type Rewards = SenseRewards;
+	type MaxRewardsPerBlock = SenseMaxRewardsPerBlock;
 }
 
 //
@@ -1079,6 +1162,9 @@ parameter_types! {
 	pub const MaxBodiesPerAccount: usize = 10;
 	pub const MaxCreationsPerBlock: usize = 3;
 	pub const MaxMembersPerBody: usize = 1024;
+	pub const MaxChildren: usize = 16;
+	pub const MaxBodyDepth: u32 = 4;
+	pub const MinTrustToCreate: u64 = 0;
 }
 
 impl module_control::Config for Runtime {
@@ -1092,6 +1178,12 @@ impl module_control::Config for Runtime {
 	type MaxBodiesPerAccount = MaxBodiesPerAccount;
 	type MaxCreationsPerBlock = MaxCreationsPerBlock;
 	type MaxMembersPerBody = MaxMembersPerBody;
+	type MaxChildren = MaxChildren;
+	type MaxDepth = MaxBodyDepth;
+
+	type Sense = ZeroSense;
+	type MinTrustToCreate = MinTrustToCreate;
+	type SenseHooks = ZeroSense;
 
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
@@ -1122,7 +1214,7 @@ parameter_types! {
 
 	pub const MaxCampaignsPerAddress: usize = 3;
 	pub const MaxCampaignsPerBlock: usize = 3;
-	pub const MaxContributionsPerBlock: usize = 3;
+	pub const MaxContributionsPerBlock: usize = 64;
 
 	pub const MinDuration: BlockNumber = 1 * DAYS;
 	pub const MaxDuration: BlockNumber = 100 * DAYS;
@@ -1133,6 +1225,12 @@ parameter_types! {
 	// TODO: fees
 	pub const CampaignFee: Balance = 25 * CENTS;
 
+	pub const MaxMilestones: usize = 8;
+	pub const MaxMilestonesPerBlock: usize = 16;
+	pub const MaxRewardTiers: usize = 8;
+	pub const MaxRewardsPerBlock: u32 = 64;
+	pub const MaxCleanupPerBlock: u32 = 64;
+
 }
 
 impl module_flow::Config for Runtime {
@@ -1155,6 +1253,7 @@ impl module_flow::Config for Runtime {
 	type MaxCampaignsPerAddress = MaxCampaignsPerAddress;
 	type MaxCampaignsPerBlock = MaxCampaignsPerBlock;
 	type MaxContributionsPerBlock = MaxContributionsPerBlock;
+	type SenseHooks = ZeroSense;
 
 	type MinDuration = MinDuration;
 	type MaxDuration = MaxDuration;
@@ -1163,6 +1262,12 @@ impl module_flow::Config for Runtime {
 
 	type CampaignFee = CampaignFee;
 
+	type MaxMilestones = MaxMilestones;
+	type MaxMilestonesPerBlock = MaxMilestonesPerBlock;
+	type MaxRewardTiers = MaxRewardTiers;
+	type MaxRewardsPerBlock = MaxRewardsPerBlock;
+	type MaxCleanupPerBlock = MaxCleanupPerBlock;
+
 }
 
 //
@@ -1172,6 +1277,11 @@ impl module_flow::Config for Runtime {
 
 parameter_types! {
 	pub const MaxProposalsPerBlock: usize = 3;
+	pub const MinScoreToVote: u64 = 0;
+	pub const MaxOptions: u32 = 16;
+	pub const MaxRunoffBallots: u32 = 256;
+	pub const CommitDeposit: Balance = 1 * DOLLARS;
+	pub const MaxRevealPeriod: BlockNumber = 7 * DAYS;
 }
 
 impl module_signal::Config for Runtime {
@@ -1182,7 +1292,13 @@ impl module_signal::Config for Runtime {
 	type Event = Event;
 	type Nonce = SeedNonce;
 	type Randomness = RandomnessCollectiveFlip;
-	type MaxProposalsPerBlock = MaxContributionsPerBlock;
+	type MaxProposalsPerBlock = MaxProposalsPerBlock;
+	type MinScoreToVote = MinScoreToVote;
+	type MaxOptions = MaxOptions;
+	type MaxRunoffBallots = MaxRunoffBallots;
+	type CommitDeposit = CommitDeposit;
+	type MaxRevealPeriod = MaxRevealPeriod;
+	type SenseHooks = ZeroSense;
 
 }
 
@@ -1234,6 +1350,17 @@ parameter_types! {
     pub const MaxClasses: u64 = 1024;
     pub const MaxToken: u128 = 2^32;
     pub const MaxTotalToken: u128 = 2^64;
+	pub const MaxRedeemables: u32 = 64;
+	pub const MaxRedeemablesPerAccount: u32 = 32;
+	pub const MaxIngredients: u32 = 16;
+	pub const MaxRoyalty: Permill = Permill::from_percent(25);
+	pub const MaxOffers: u32 = 32;
+	pub const MaxItemChildren: u32 = 16;
+	pub const MaxItemDepth: u32 = 4;
+	pub const MaxTreeSize: u32 = 32;
+	pub const MaxRentalDuration: BlockNumber = 90 * DAYS;
+	pub const MaxRentalExpiries: u32 = 64;
+	pub const MaxRentalsPerBlock: u32 = 64;
 }
 impl module_tangram::Config for Runtime {
     type Event = Event;
@@ -1256,6 +1383,20 @@ impl module_tangram::Config for Runtime {
 	type TotalIndex = u128;
 	type BurnedIndex = u128;
 
+	type SenseHooks = ZeroSense;
+	type Control = GameDaoControl;
+	type MaxRedeemables = MaxRedeemables;
+	type MaxRedeemablesPerAccount = MaxRedeemablesPerAccount;
+	type MaxIngredients = MaxIngredients;
+	type MaxRoyalty = MaxRoyalty;
+	type MaxOffers = MaxOffers;
+	type MaxItemChildren = MaxItemChildren;
+	type MaxItemDepth = MaxItemDepth;
+	type MaxTreeSize = MaxTreeSize;
+	type MaxRentalDuration = MaxRentalDuration;
+	type MaxRentalExpiries = MaxRentalExpiries;
+	type MaxRentalsPerBlock = MaxRentalsPerBlock;
+
 // 	type WeightInfo = weights::module_item::WeightInfo<Runtime>;
 }
 
@@ -1313,15 +1454,15 @@ construct_runtime!(
 		//
 		//
 
-		ZeroSense: module_sense::{Module, Call, Storage, Event<T>},
+		ZeroSense: module_sense::{Module, Call, Storage, Event<T>, Config<T>},
 		// ZeroNFT: zero_nft::{Module, Call, Storage, Event<T>},
 		// ZeroCurrencies: module_currencies::{Module, Call, Storage, Event<T>},
 		// ZeroAirdrop: module_airdrop::{Module, Call, Storage, Event<T>},
 
-		GameDaoControl: module_control::{Module, Call, Storage, Event<T>},
-		GameDaoGovernance: module_signal::{Module, Call, Storage, Event<T>},
-		GameDaoCrowdfunding: module_flow::{Module, Call, Storage, Event<T>},
-		GameDaoTangram: module_tangram::{Module, Call, Storage, Event<T>},
+		GameDaoControl: module_control::{Module, Call, Storage, Event<T>, Config<T>},
+		GameDaoGovernance: module_signal::{Module, Call, Storage, Event<T>, Config<T>},
+		GameDaoCrowdfunding: module_flow::{Module, Call, Storage, Event<T>, Config<T>},
+		GameDaoTangram: module_tangram::{Module, Call, Storage, Event<T>, Config<T>},
 //		GameDaoLootDrop: module_loot::{Module, Call, Storage, Event<T>},
 
 	}
@@ -1349,7 +1490,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	impls::ChargeSponsoredPayment,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1591,6 +1732,49 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl module_sense_runtime_api::SenseApi<Block, AccountId> for Runtime {
+		fn score(account: AccountId) -> u64 {
+			ZeroSense::score(&account)
+		}
+
+		fn value(account: AccountId, property: node_primitives::SenseProps) -> u64 {
+			ZeroSense::value(property, &account)
+		}
+	}
+
+	impl module_signal_runtime_api::SignalApi<Block, Hash> for Runtime {
+		fn winner(proposal: Hash) -> Option<u8> {
+			GameDaoGovernance::proposal_winner(proposal)
+		}
+
+		fn choice_votes(proposal: Hash) -> Vec<u64> {
+			GameDaoGovernance::choice_votes(proposal)
+		}
+	}
+
+	impl module_tangram_runtime_api::TangramApi<Block, Hash, AccountId> for Runtime {
+		fn rarity(item: Hash) -> Option<node_primitives::Rarity> {
+			GameDaoTangram::rarity_of(&item)
+		}
+
+		fn rarity_table(realm: u64, class: u64) -> node_primitives::RarityTable {
+			GameDaoTangram::rarity_table(realm, class)
+		}
+
+		fn dna(seed: Hash, realm: u64, class: u64, index: u64, age: u32) -> (node_primitives::Rarity, Hash) {
+			let table = GameDaoTangram::rarity_table(realm, class);
+			GameDaoTangram::generate_dna(&table, seed, realm, class, index, age)
+		}
+
+		fn item_tree(item: Hash) -> Vec<(Hash, u32)> {
+			GameDaoTangram::item_tree(&item)
+		}
+
+		fn item_user(item: Hash) -> Option<AccountId> {
+			GameDaoTangram::user_of(&item)
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -0,0 +1,227 @@
+// This file is part of ZERO Network.
+// Copyright (C) 2010-2020 ZERO Technologies.
+// SPDX-License-Identifier: Apache-2.0
+
+//! GameDAO chain extension called from a contract.
+//!
+//! The fixture contract forwards its input, a function id followed by the
+//! arguments, to the chain extension and returns the output. Every function id
+//! is called through `pallet_contracts` against the runtime.
+
+use codec::{ Decode, Encode };
+use frame_support::{ assert_ok, BasicExternalities };
+use node_primitives::{ AccountId, Balance, Hash, SenseProps };
+use pallet_contracts_primitives::ContractExecResult;
+use sp_runtime::{ traits::{ Dispatchable, Hash as HashT }, DispatchError };
+use module_control::{ ControlAccessModel, ControlFeeModel, ControlMemberState, ControlType };
+use module_flow::FlowState;
+use zero_runtime::{
+	chain_extension::*,
+	constants::{ currency::DOLLARS, time::DAYS },
+	Call, Contracts, GameDaoControl, GameDaoCrowdfunding, GameDaoTangram, Origin, Runtime, System, ZeroSense,
+};
+
+const GAS_LIMIT: u64 = 100_000_000_000;
+
+const ALICE: AccountId = AccountId::new([1u8; 32]);
+const BOB: AccountId = AccountId::new([2u8; 32]);
+const CHARLIE: AccountId = AccountId::new([3u8; 32]);
+
+// body 0 controlled by alice with member bob, realm 0 and class 0,
+// a campaign of body 0 funded with 10 by bob,
+// bob scores in xp and rep and earns more by joining and contributing, the fixture contract deployed by alice
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![
+			(ALICE, 1_000_000 * DOLLARS), (BOB, 1_000 * DOLLARS), (CHARLIE, 1_000 * DOLLARS),
+			(GameDaoControl::treasury_account(0), 1_000 * DOLLARS),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+	module_sense::GenesisConfig::<Runtime> {
+		entities: vec![ (BOB, vec![], 30, 20, 0) ],
+		reporters: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	// bodies and campaigns are random, randomness starts at block 1
+	BasicExternalities::execute_with_storage(&mut t, || System::set_block_number(1));
+	module_control::GenesisConfig::<Runtime> {
+		bodies: vec![(
+			ALICE, ALICE, b"body".to_vec(), vec![], ControlType::Dao,
+			ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0, vec![ BOB ],
+		)],
+	}.assimilate_storage(&mut t).unwrap();
+	module_flow::GenesisConfig::<Runtime> {
+		campaigns: vec![ (0, b"campaign".to_vec(), 100 * DOLLARS, 10 * DOLLARS, 2 * DAYS, vec![]) ],
+	}.assimilate_storage(&mut t).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		assert_ok!(
+			Call::GameDaoCrowdfunding(module_flow::Call::contribute(campaign(), 10 * DOLLARS)).dispatch(Origin::signed(BOB))
+		);
+		let code = wat::parse_file("tests/fixtures/game_dao_extension.wat").unwrap();
+		assert_ok!( Contracts::instantiate_with_code(
+			Origin::signed(ALICE), Contracts::subsistence_threshold() * 100, GAS_LIMIT, code, vec![], vec![],
+		) );
+	});
+	ext
+}
+
+fn contract() -> AccountId {
+	let code = wat::parse_file("tests/fixtures/game_dao_extension.wat").unwrap();
+	Contracts::contract_address( &ALICE, &<Runtime as frame_system::Config>::Hashing::hash(&code), &[] )
+}
+
+fn body() -> Hash {
+	GameDaoControl::body_by_nonce(0)
+}
+
+fn campaign() -> Hash {
+	GameDaoCrowdfunding::campaigns_by_body(body())[0]
+}
+
+// call the extension through the contract with encoded arguments
+fn call_as(caller: AccountId, func_id: u32, input: &[u8]) -> ContractExecResult {
+	let mut data = func_id.encode();
+	data.extend_from_slice(input);
+	Contracts::bare_call( caller, contract(), 0, GAS_LIMIT, data )
+}
+
+fn call(func_id: u32, input: impl Encode) -> ContractExecResult {
+	call_as( ALICE, func_id, &input.encode() )
+}
+
+fn query<T: Decode>(func_id: u32, input: impl Encode) -> T {
+	let output = call(func_id, input).exec_result.expect("the extension succeeds").data;
+	T::decode(&mut &output[..]).expect("the output decodes")
+}
+
+fn error(result: ContractExecResult) -> DispatchError {
+	result.exec_result.expect_err("the extension fails").error
+}
+
+fn mint(to: &AccountId) -> Hash {
+	query( TANGRAM_MINT, (0u64, 0u64, b"item".to_vec(), Vec::<u8>::new(), to.clone()) )
+}
+
+//
+//	queries
+//
+
+#[test]
+fn control_queries_read_bodies_and_members() {
+	new_test_ext().execute_with(|| {
+		assert!( query::<bool>( CONTROL_IS_MEMBER, (body(), BOB) ) );
+		assert!( !query::<bool>( CONTROL_IS_MEMBER, (body(), CHARLIE) ) );
+		assert!(
+			query::<ControlMemberState>( CONTROL_MEMBER_STATE, (body(), BOB) ) ==
+			GameDaoControl::body_member_state((body(), BOB))
+		);
+		assert_eq!( query::<Option<AccountId>>( CONTROL_CONTROLLER, body() ), Some(ALICE) );
+		assert_eq!(
+			query::<Option<AccountId>>( CONTROL_TREASURY, body() ),
+			Some( GameDaoControl::treasury_account(0) )
+		);
+	});
+}
+
+#[test]
+fn flow_queries_read_campaigns() {
+	new_test_ext().execute_with(|| {
+		assert_eq!( query::<FlowState>( FLOW_STATE, campaign() ), FlowState::Active );
+		assert_eq!( query::<Balance>( FLOW_CONTRIBUTION, (campaign(), BOB) ), 10 * DOLLARS );
+		assert_eq!( query::<Balance>( FLOW_CONTRIBUTION, (campaign(), CHARLIE) ), 0 );
+		assert_eq!( query::<Balance>( FLOW_BALANCE, campaign() ), 10 * DOLLARS );
+	});
+}
+
+#[test]
+fn sense_queries_read_score_and_properties() {
+	new_test_ext().execute_with(|| {
+		assert_eq!( query::<u64>( SENSE_VALUE, (SenseProps::XP, BOB) ), ZeroSense::value(SenseProps::XP, &BOB) );
+		assert_eq!( query::<u64>( SENSE_VALUE, (SenseProps::REP, BOB) ), ZeroSense::value(SenseProps::REP, &BOB) );
+		assert!( ZeroSense::value(SenseProps::XP, &BOB) > 30 );
+		assert_eq!( query::<u64>( SENSE_SCORE, BOB ), ZeroSense::score(&BOB) );
+		assert_eq!( query::<u64>( SENSE_SCORE, CHARLIE ), 0 );
+	});
+}
+
+//
+//	tangram
+//
+
+#[test]
+fn mint_requires_the_contract_to_operate_the_realm() {
+	new_test_ext().execute_with(|| {
+		let args = (0u64, 0u64, b"item".to_vec(), Vec::<u8>::new(), BOB);
+		assert_eq!( error(call(TANGRAM_MINT, args.clone())), DispatchError::from(module_tangram::Error::<Runtime>::NotOperator) );
+
+		assert_ok!( GameDaoTangram::set_operator( Origin::signed(ALICE), 0, contract(), true ) );
+		let item = mint(&BOB);
+		assert_eq!( query::<Option<AccountId>>( TANGRAM_OWNER, item ), Some(BOB) );
+		assert_eq!( query::<Option<AccountId>>( TANGRAM_OWNER, Hash::default() ), None );
+	});
+}
+
+#[test]
+fn transfer_moves_items_of_the_contract_or_its_caller() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( GameDaoTangram::set_operator( Origin::signed(ALICE), 0, contract(), true ) );
+		let (owned, held) = ( mint(&contract()), mint(&BOB) );
+
+		// the caller's items move while the contract operates the realm
+		assert_eq!(
+			error( call_as( ALICE, TANGRAM_TRANSFER, &(held, CHARLIE).encode() ) ),
+			DispatchError::from(module_tangram::Error::<Runtime>::Unauthorized)
+		);
+		assert_ok!( call_as( BOB, TANGRAM_TRANSFER, &(held, CHARLIE).encode() ).exec_result );
+		assert_eq!( GameDaoTangram::owner_of(&held), Some(CHARLIE) );
+
+		assert_ok!( GameDaoTangram::set_operator( Origin::signed(ALICE), 0, contract(), false ) );
+		assert_eq!(
+			error( call_as( CHARLIE, TANGRAM_TRANSFER, &(held, BOB).encode() ) ),
+			DispatchError::from(module_tangram::Error::<Runtime>::NotOperator)
+		);
+
+		// its own items move without operating the realm
+		assert_ok!( call( TANGRAM_TRANSFER, (owned, BOB) ).exec_result );
+		assert_eq!( GameDaoTangram::owner_of(&owned), Some(BOB) );
+	});
+}
+
+//
+//	weights and decoding
+//
+
+#[test]
+fn weights_are_charged_per_read_and_input_byte() {
+	new_test_ext().execute_with(|| {
+		let read = <Runtime as frame_system::Config>::DbWeight::get().reads(1);
+		let score = call( SENSE_SCORE, BOB ).gas_consumed;
+		let value = call( SENSE_VALUE, (SenseProps::XP, BOB) ).gas_consumed;
+		assert!( score > value + read );
+
+		assert_ok!( GameDaoTangram::set_operator( Origin::signed(ALICE), 0, contract(), true ) );
+		let mint_with = |cid: Vec<u8>| call( TANGRAM_MINT, (0u64, 0u64, b"item".to_vec(), cid, BOB) ).gas_consumed;
+		let short = mint_with(vec![]);
+		let long = mint_with(vec![0u8; 1_000]);
+		assert!( long >= short + 1_000 * 10_000 );
+
+		// the tree of a transfer is charged up front
+		let item = mint(&BOB);
+		let mut data = TANGRAM_TRANSFER.encode();
+		data.extend( (item, CHARLIE).encode() );
+		let result = Contracts::bare_call( BOB, contract(), 0, GameDaoTangram::tree_transfer_weight(), data );
+		assert_eq!( error(result), DispatchError::from(pallet_contracts::Error::<Runtime>::OutOfGas) );
+		assert_eq!( GameDaoTangram::owner_of(&item), Some(BOB) );
+	});
+}
+
+#[test]
+fn malformed_input_and_unknown_functions_fail() {
+	new_test_ext().execute_with(|| {
+		assert!( call_as( ALICE, CONTROL_IS_MEMBER, &body().encode() ).exec_result.is_err() );
+		assert!( call_as( ALICE, SENSE_VALUE, &[9u8] ).exec_result.is_err() );
+		assert_eq!( error(call( 0x0005_0001, BOB )), DispatchError::Other("unknown chain extension function") );
+	});
+}
@@ -0,0 +1,36 @@
+;; Forward the input to the GameDAO chain extension and return its output.
+;; The input is the little endian function id followed by its arguments.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) len of the input buffer
+	(data (i32.const 0) "\00\20")
+
+	;; [4, 8) len of the output buffer
+	(data (i32.const 4) "\00\20")
+
+	;; [16, 8208) input, function id and arguments
+
+	;; [8208, 16400) output
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 0))
+
+		(drop (call $seal_call_chain_extension
+			(i32.load (i32.const 16))					;; func_id
+			(i32.const 20)								;; input_ptr
+			(i32.sub (i32.load (i32.const 0)) (i32.const 4))	;; input_len
+			(i32.const 8208)							;; output_ptr
+			(i32.const 4)								;; output_len_ptr
+		))
+
+		(call $seal_return (i32.const 0) (i32.const 8208) (i32.load (i32.const 4)))
+	)
+)
@@ -0,0 +1,75 @@
+// This file is part of ZERO Network.
+// Copyright (C) 2010-2020 ZERO Technologies.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime upgrade against a live state snapshot.
+//!
+//! Export the state of a running chain and point `SNAPSHOT` to it:
+//!
+//! 	subzero export-state --chain <chain> > snapshot.json
+//! 	SNAPSHOT=snapshot.json cargo test -p zero-runtime --test snapshot_migrations -- --ignored
+//!
+//! The migrations of the new runtime are applied to the snapshot,
+//! afterwards the storage invariants of the gamedao pallets are checked.
+
+use std::collections::BTreeMap;
+
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_core::{ bytes::from_hex, storage::Storage };
+use zero_runtime::{
+	AllModules,
+	GameDaoControl, GameDaoCrowdfunding, GameDaoGovernance, GameDaoTangram, ZeroSense,
+};
+
+// read the raw top level storage of an `export-state` chain spec
+fn load_snapshot(path: &str) -> Storage {
+	let file = std::fs::File::open(path).expect("snapshot file can be opened");
+	let spec: serde_json::Value = serde_json::from_reader(file).expect("snapshot is valid json");
+	let raw = spec["genesis"]["raw"]["top"].as_object().expect("snapshot contains raw state");
+
+	let top: BTreeMap<Vec<u8>, Vec<u8>> = raw.iter()
+		.map(|(key, value)| (
+			from_hex(key).expect("storage key is hex"),
+			from_hex(value.as_str().expect("storage value is a string")).expect("storage value is hex"),
+		))
+		.collect();
+
+	Storage { top, children_default: Default::default() }
+}
+
+#[test]
+#[ignore]
+fn snapshot_migrations_keep_invariants() {
+	let path = match std::env::var("SNAPSHOT") {
+		Ok(path) => path,
+		Err(_) => return,
+	};
+
+	let mut ext = sp_io::TestExternalities::new(load_snapshot(&path));
+	ext.execute_with(|| {
+		<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		// migrations are applied once
+		let versions = (
+			GameDaoControl::storage_version(),
+			GameDaoCrowdfunding::storage_version(),
+			GameDaoGovernance::storage_version(),
+			GameDaoTangram::storage_version(),
+			ZeroSense::storage_version(),
+		);
+		<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(versions, (
+			GameDaoControl::storage_version(),
+			GameDaoCrowdfunding::storage_version(),
+			GameDaoGovernance::storage_version(),
+			GameDaoTangram::storage_version(),
+			ZeroSense::storage_version(),
+		));
+
+		GameDaoControl::check_invariants().expect("control invariants");
+		GameDaoCrowdfunding::check_invariants().expect("flow invariants");
+		GameDaoGovernance::check_invariants().expect("signal invariants");
+		GameDaoTangram::check_invariants().expect("tangram invariants");
+		ZeroSense::check_invariants().expect("sense invariants");
+	});
+}
@@ -15,6 +15,27 @@
 //! CONTROL
 //!
 //! This pallet invokes the initial control body for various functions.
+//!
+//! Bodies can be updated by their controller or governance. Dissolving a body
+//! refunds reserved membership fees, pays out the free treasury to its members
+//! pro rata to the fees they paid, and archives the body and its tangram realms.
+//!
+//! Bodies can have a parent body, e.g. guilds and teams under a studio DAO.
+//! The parent controller or governance creates, funds and dissolves children
+//! and limits what a child treasury spends per period. Membership of a child
+//! is independent, inherited from or requires membership of the parent.
+//!
+//! A body can sponsor transaction fees of its active members from its
+//! treasury, within a budget per member and period. The runtime charges
+//! sponsored fees through its signed extension.
+//!
+//! Treasuries are keyless sub accounts of `MODULE_ID`, derived from the body
+//! index. Funds only move through pallet logic or signal proposals. Bodies
+//! created with an externally owned treasury are re-pointed on upgrade, the
+//! legacy account or governance moves the membership fees with `migrate_treasury`.
+//!
+//! Storage is versioned by `StorageVersion`. Breaking changes bump the
+//! version and migrate in `on_runtime_upgrade`, the storage prefix is kept.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![feature(derive_default_enum)]
@@ -27,8 +48,12 @@
 
 // TODO:
 // mod default_weight;
-// mod mock;
-// mod tests;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
 
 pub use module::*;
 
@@ -36,21 +61,29 @@ pub mod module {
 
 	use frame_support::{
 		decl_error, decl_event, decl_module, decl_storage,
-		ensure,
-		dispatch::DispatchResult,
-		traits::{ Get, Currency, EnsureOrigin, Randomness, ReservableCurrency, ExistenceRequirement },
+		ensure, transactional,
+		dispatch::{ DispatchError, DispatchResult },
+		traits::{ Get, Currency, EnsureOrigin, Randomness, ReservableCurrency, ExistenceRequirement, BalanceStatus },
+		weights::Weight,
+		storage::IterableStorageMap,
 	};
 	use frame_system::{ self as system, ensure_root, ensure_signed };
 	use codec::{ Encode, Decode };
 	use sp_runtime::{
-		traits::{ Hash, Zero },
+		traits::{ AccountIdConversion, Hash, SaturatedConversion, Saturating, Zero },
+		helpers_128bit::multiply_by_rational,
 		ModuleId
 	};
 	use sp_std::prelude::*;
 	use pallet_balances::{ self as balances };
 	use primitives:: {
 		Balance,
+		BodyProvider,
 		// AccountId,
+		SenseAction,
+		SenseHooks,
+		SenseProps,
+		SenseScore,
 	};
 
 	// use hex_literal;
@@ -73,12 +106,14 @@ pub mod module {
 	#[derive(Encode, Decode, PartialEq, Clone, Eq, Default, PartialOrd, Ord, TypeInfo)]
 	#[repr(u8)]
 	#[derive(Debug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub enum ControlType {
 	#[default]
 		Individual = 0, // individual
 		Company = 1,    // offchain body
 		Dao = 2,        // dao
 		Hybrid = 3,
+		Guild = 4,      // guild or team under a parent body
 	}
 
 	#[derive(Encode, Decode, PartialEq, Clone, Eq, PartialOrd, Ord, TypeInfo)]
@@ -87,6 +122,7 @@ pub mod module {
 		Inactive = 0,
 		Active = 1,
 		Locked = 2,
+		Archived = 3,
 	}
 
 	#[derive(Encode, Decode, PartialEq, Clone, Eq, PartialOrd, Ord, TypeInfo)]
@@ -103,6 +139,7 @@ pub mod module {
 	#[derive(Encode, Decode, Clone, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
 	#[repr(u8)]
 	#[derive(Debug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub enum ControlFeeModel {
 	#[default]
 		NoFees = 0,		// feeless
@@ -113,6 +150,7 @@ pub mod module {
 	#[derive(Encode, Decode, Clone, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
 	#[repr(u8)]
 	#[derive(Debug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub enum ControlAccessModel {
 	#[default]
 		Open = 0,		// anybody can join
@@ -120,6 +158,17 @@ pub mod module {
 		Controller = 2,	// controller invites
 	}
 
+	#[derive(Encode, Decode, Clone, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
+	#[repr(u8)]
+	#[derive(Debug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ControlMembershipModel {
+	#[default]
+		Independent = 0,	// own members only
+		Inherited = 1,		// active members of the parent are members
+		Required = 2,		// joining requires membership of the parent
+	}
+
 	//
 	//
 	//
@@ -127,6 +176,20 @@ pub mod module {
 	pub const MODULE_ID: ModuleId = ModuleId(*b"dao/ctrl");
 	pub const MODULE_VERSION: &str = "1.0";
 
+	/// Storage version
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo)]
+	#[derive(Debug)]
+	pub enum Releases {
+		/// unversioned, externally owned treasuries
+		V0,
+		/// keyless treasuries
+		V1,
+	}
+
+	impl Default for Releases {
+		fn default() -> Self { Releases::V0 }
+	}
+
 	//
 	//
 	//
@@ -149,6 +212,15 @@ pub mod module {
 		type MaxBodiesPerAccount: Get<usize>;
 		type MaxMembersPerBody: Get<usize>;
 		type MaxCreationsPerBlock: Get<usize>;
+		type MaxChildren: Get<usize>;
+		type MaxDepth: Get<u32>;
+
+		// sense
+		// reputation gate for creating bodies
+		type Sense: SenseScore<Self::AccountId>;
+		type MinTrustToCreate: Get<u64>;
+		// experience for joining bodies
+		type SenseHooks: SenseHooks<Self::AccountId>;
 
 		//
 		type Event: From<Event<Self>> + Into<<Self as system::Config>::Event>;
@@ -216,6 +288,10 @@ pub mod module {
 			BodyMemberState get(fn body_member_state): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => ControlMemberState = ControlMemberState::Inactive;
 			/// Memberships by AccountId
 			Memberships get(fn memberships): map hasher(blake2_128_concat) T::AccountId => Vec<T::Hash>;
+			/// Membership fee reserved by a member
+			ReservedFees get(fn reserved_fee): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => T::Balance;
+			/// Membership fee transferred to the treasury by a member
+			PaidFees get(fn paid_fee): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => T::Balance;
 
 			// significant accounts
 
@@ -225,6 +301,8 @@ pub mod module {
 			BodyController get(fn body_controller): map hasher(blake2_128_concat) T::Hash => T::AccountId;
 			/// Treasury of a body
 			BodyTreasury get(fn body_treasury): map hasher(blake2_128_concat) T::Hash => T::AccountId;
+			/// Externally owned treasury of a body before keyless treasuries
+			LegacyTreasury get(fn legacy_treasury): map hasher(blake2_128_concat) T::Hash => Option<T::AccountId>;
 
 			//
 
@@ -238,24 +316,64 @@ pub mod module {
 			/// Number of DAOs controlled by account
 			ControlledBodiesCount get(fn by_controller_count): map hasher(blake2_128_concat) T::AccountId => u64 = 0;
 
+			// hierarchy
+
+			/// Parent of a body
+			BodyParent get(fn body_parent): map hasher(blake2_128_concat) T::Hash => Option<T::Hash>;
+			/// Children of a body
+			BodyChildren get(fn body_children): map hasher(blake2_128_concat) T::Hash => Vec<T::Hash>;
+			/// Membership of a body in relation to its parent
+			BodyMembership get(fn body_membership): map hasher(blake2_128_concat) T::Hash => ControlMembershipModel;
+			/// Treasury spend limit set by the parent, (amount, period)
+			BodySpendLimit get(fn spend_limit): map hasher(blake2_128_concat) T::Hash => Option<(T::Balance, T::BlockNumber)>;
+			/// Treasury spent in the current period, (period start, amount)
+			BodySpent get(fn spent): map hasher(blake2_128_concat) T::Hash => (T::BlockNumber, T::Balance);
+
+			// sponsoring
+
+			/// Fees sponsored per member and period, (budget, period)
+			BodySponsorship get(fn sponsorship): map hasher(blake2_128_concat) T::Hash => Option<(T::Balance, T::BlockNumber)>;
+			/// Fees sponsored for a member in the current period, (period start, amount)
+			SponsoredFees get(fn sponsored_fees): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => (T::BlockNumber, T::Balance);
+
 			/// the goode olde nonce
 			Nonce: u128;
 
+			/// Storage version, new chains start at the latest
+			StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V1): Releases;
+
 		}
 
-		// add_extra_genesis {
-		// 	config(balances): Vec<(T::AccountId, Vec<T::CommodityInfo>)>;
-		// 	build(|config: &GenesisConfig<T, I>| {
-		// 		for (who, assets) in config.balances.iter() {
-		// 			for asset in assets {
-		// 			match <Module::<T, I> as UniqueAssets::<T::AccountId>>::mint(who, asset.clone()) {
-		// 				Ok(_) => {}
-		// 				Err(err) => { panic!(err) },
-		// 			}
-		// 			}
-		// 		}
-		// 	});
-		// }
+		add_extra_genesis {
+			/// bodies created at genesis:
+			/// (creator, controller, name, cid, body, access, fee model, fee, member limit, members)
+			config(bodies): Vec<(
+				T::AccountId, T::AccountId, Vec<u8>, Vec<u8>, ControlType,
+				ControlAccessModel, ControlFeeModel, T::Balance, u64, Vec<T::AccountId>
+			)>;
+			build(|config: &GenesisConfig<T>| {
+				for (creator, controller, name, cid, body, access, fee_model, fee, member_limit, members) in config.bodies.iter() {
+					let hash = Module::<T>::do_create(
+						system::RawOrigin::Signed(creator.clone()).into(),
+						creator.clone(),
+						controller.clone(),
+						name.clone(),
+						cid.clone(),
+						body.clone(),
+						access.clone(),
+						fee_model.clone(),
+						fee.clone(),
+						0,
+						0,
+						*member_limit,
+					).expect("genesis body is valid");
+					for member in members.iter().filter(|member| *member != controller) {
+						Module::<T>::add( hash.clone(), member.clone() )
+							.expect("genesis member is valid");
+					}
+				}
+			});
+		}
 	}
 
 	//
@@ -268,19 +386,27 @@ pub mod module {
 			fn deposit_event() = default;
 			type Error = Error<T>;
 
+			fn on_runtime_upgrade() -> Weight {
+				match Self::storage_version() {
+					Releases::V0 => Self::migrate_to_v1(),
+					_ => 0,
+				}
+			}
+
 			// Enable DAO
 			// currently root, layer supervisor
 			// enables an org to be used
 			// hash: an organisations hash
 			#[weight = 1_000_000]
-			fn enable(
+			pub fn enable(
 				origin,
 				hash: T::Hash,
 			) -> DispatchResult {
 				ensure_root(origin)?;
+				ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
 				<BodyState<T>>::insert( hash.clone(), ControlState::Active );
 				let now = <system::Module<T>>::block_number();
-				Self::deposit_event( RawEvent::BodyDisabled( hash ) );
+				Self::deposit_event( RawEvent::BodyEnabled( hash ) );
 				Ok(())
 			}
 
@@ -289,11 +415,12 @@ pub mod module {
 			// disables an org to be used
 			// hash: an organisations hash
 			#[weight = 1_000_000]
-			fn disable(
+			pub fn disable(
 				origin,
 				hash: T::Hash,
 			) -> DispatchResult {
 				ensure_root(origin)?;
+				ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
 				<BodyState<T>>::insert( hash.clone(), ControlState::Inactive );
 				let now = <system::Module<T>>::block_number();
 				Self::deposit_event( RawEvent::BodyDisabled( hash ) );
@@ -318,10 +445,10 @@ pub mod module {
 			// // burn: T::Balance,		// cost to burn
 			// // strategy: u16,
 			#[weight = 5_000_000]
-			fn create(
+			#[transactional]
+			pub fn create(
 				origin,
 				controller: T::AccountId,
-				treasury: T::AccountId,
 				name: Vec<u8>,
 				cid: Vec<u8>,
 				body: ControlType,
@@ -338,192 +465,358 @@ pub mod module {
 
 				let sender = ensure_signed(origin.clone())?;
 
-				// set up fee
-				let creation_fee = T::CreationFee::get();
-
-				// TODO: fix balance check
-				// it seems like the balances coming from polkadotjs app work
-				// the ones coming from gamedao beta dont
-				// controller and treasury need enough balance
-				let free_balance = balances::Module::<T>::free_balance(&sender);
-				// ensure!( free_balance > creation_fee, Error::<T>::BalanceTooLow );
-				let free_balance_treasury = balances::Module::<T>::free_balance(&treasury);
-				// ensure!( free_balance_treasury > creation_fee, Error::<T>::BalanceTooLow );
-
-				// controller and treasury must not be equal
-				ensure!(&controller != &treasury, Error::<T>::DuplicateAddress );
-
-				let now   = <system::Module<T>>::block_number();
-				let hash = <T as Config>::Randomness::random(&name);
-				let index = Nonce::get();
-				let state = ControlState::Active; // live
-				// TODO: create enums for bonding strategies
-				let strategy = 0;
-
-				let body_data = Body {
-					id:       hash.clone(),
-					index:    index.clone(),
-					creator:  sender.clone(),
-					name:     name.clone(),
-					cid:      cid,
-					body:     body.clone(),
-					created:  now.clone(),
-					mutated:  now.clone(),
-				};
-				Bodies::<T>::insert( hash.clone(), body_data );
-
-				// membership fees
-				let mut _fee = T::Balance::zero();
-				match &fee_model {
-					ControlFeeModel::Reserve => { _fee = fee },
-					ControlFeeModel::Transfer => { _fee = fee },
-					_ => { }
-				};
+				// creator needs sufficient trust
+				ensure!(
+					T::Sense::value(SenseProps::TRUST, &sender) >= T::MinTrustToCreate::get(),
+					Error::<T>::TrustTooLow
+				);
 
-				let config_data = BConfig {
-					fee_model: fee_model.clone(),
-					fee: _fee.clone(),
-					gov_asset: gov_asset.clone(),
-					pay_asset: pay_asset.clone(),
-					member_limit: member_limit.clone(),
-					access: access.clone()
-				};
-				BodyConfig::<T>::insert( hash.clone(), config_data );
-
-				//
-
-				BodyByNonce::<T>::insert( index.clone(), hash.clone() );
-				BodyState::<T>::insert( hash.clone(), state );
-				BodyAccess::<T>::insert( hash.clone(), access.clone() );
-				BodyCreator::<T>::insert( hash.clone(), sender.clone() );
-				BodyController::<T>::insert( hash.clone(), controller.clone() );
-				BodyTreasury::<T>::insert( hash.clone(), treasury.clone() );
-
-				let mut controlled = Self::by_controller(&controller);
-				controlled.push(hash.clone());
-				ControlledBodies::<T>::mutate(
-					&controller,
-					|controlled| controlled.push(hash.clone())
+				Self::do_create(
+					origin,
+					sender,
+					controller,
+					name,
+					cid,
+					body,
+					access,
+					fee_model,
+					fee,
+					gov_asset,
+					pay_asset,
+					member_limit,
+				)?;
+				Ok(())
+
+			}
+
+			// Create Child
+			// parent controller or governance
+			// creates a body under a parent, paid by the parent treasury
+			// funding: transferred from the parent treasury
+			#[weight = 5_000_000]
+			#[transactional]
+			pub fn create_child(
+				origin,
+				parent: T::Hash,
+				controller: T::AccountId,
+				name: Vec<u8>,
+				cid: Vec<u8>,
+				body: ControlType,
+				access: ControlAccessModel,
+				fee_model: ControlFeeModel,
+				fee: T::Balance,
+				member_limit: u64,
+				membership: ControlMembershipModel,
+				funding: T::Balance,
+			) -> DispatchResult {
+
+				Self::ensure_controller_or_governance(origin, &parent)?;
+				ensure!( Self::body_state(&parent) == ControlState::Active, Error::<T>::BodyInactive );
+
+				// bounds
+				let depth = Self::ancestors(&parent).len() as u32 + 1;
+				ensure!( depth <= T::MaxDepth::get(), Error::<T>::MaxDepthReached );
+				let children = Self::body_children(&parent);
+				ensure!( children.len() < T::MaxChildren::get(), Error::<T>::TooManyChildren );
+
+				// the parent treasury creates the child,
+				// the creation fee counts against its spend limit
+				Self::spend( &parent, T::CreationFee::get() )?;
+				let treasury = Self::body_treasury(&parent);
+				let config = Self::body_config(&parent);
+				let child = Self::do_create(
+					system::RawOrigin::Signed(treasury.clone()).into(),
+					treasury.clone(),
+					controller,
+					name,
+					cid,
+					body,
+					access,
+					fee_model,
+					fee,
+					config.gov_asset,
+					config.pay_asset,
+					member_limit,
+				)?;
+
+				BodyParent::<T>::insert( &child, parent.clone() );
+				BodyChildren::<T>::mutate( &parent, |children| children.push(child.clone()) );
+				BodyMembership::<T>::insert( &child, membership );
+
+				if !funding.is_zero() {
+					Self::fund( &parent, &child, funding )?;
+				}
+
+				Self::deposit_event(
+					RawEvent::ChildCreated(parent, child)
 				);
+				Ok(())
+
+			}
+
+			// Fund Child
+			// parent controller or governance
+			// transfers from the parent treasury to the child treasury
+			#[weight = 1_000_000]
+			pub fn fund_child(
+				origin,
+				child: T::Hash,
+				amount: T::Balance,
+			) -> DispatchResult {
+
+				let parent = Self::body_parent(&child).ok_or(Error::<T>::NoParent)?;
+				Self::ensure_controller_or_governance(origin, &parent)?;
+				ensure!( Self::body_state(&child) != ControlState::Archived, Error::<T>::BodyArchived );
+				Self::fund( &parent, &child, amount )
+
+			}
+
+			// Set Spend Limit
+			// parent controller or governance
+			// limits what a child treasury spends per period,
+			// a zero period removes the limit
+			#[weight = 1_000_000]
+			pub fn set_spend_limit(
+				origin,
+				child: T::Hash,
+				amount: T::Balance,
+				period: T::BlockNumber,
+			) -> DispatchResult {
+
+				let parent = Self::body_parent(&child).ok_or(Error::<T>::NoParent)?;
+				Self::ensure_controller_or_governance(origin, &parent)?;
+
+				if period.is_zero() {
+					BodySpendLimit::<T>::remove(&child);
+				} else {
+					BodySpendLimit::<T>::insert( &child, (amount, period) );
+				}
+				BodySpent::<T>::remove(&child);
 
-				// TODO: this needs a separate add / removal function
-				// whenever the controller of an organisation changes!
-				ControlledBodiesCount::<T>::mutate(
-					&controller,
-					|controlled_count| *controlled_count += 1
+				Self::deposit_event(
+					RawEvent::SpendLimitSet(child, amount, period)
 				);
+				Ok(())
+
+			}
 
+			// Set Sponsorship
+			// controller or governance
+			// treasury pays transaction fees of active members
+			// up to budget per member and period,
+			// a zero period stops sponsoring
+			#[weight = 1_000_000]
+			pub fn set_sponsorship(
+				origin,
+				hash: T::Hash,
+				budget: T::Balance,
+				period: T::BlockNumber,
+			) -> DispatchResult {
+
+				Self::ensure_controller_or_governance(origin, &hash)?;
+				ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
 
-				let mut created = Self::by_creator(sender.clone());
-				created.push(hash.clone());
-				ControlledBodies::<T>::mutate(
-					&sender,
-					|created| created.push(hash.clone())
+				if period.is_zero() {
+					BodySponsorship::<T>::remove(&hash);
+				} else {
+					BodySponsorship::<T>::insert( &hash, (budget, period) );
+				}
+
+				Self::deposit_event(
+					RawEvent::SponsorshipSet(hash, budget, period)
 				);
+				Ok(())
 
-				// initiate member registry -> consumes fees
-				// creator and controller can be equal
-				// controller and treasury cannot be equal
-				// match Self::add( &hash, creator.clone() ) {
-				// 		Ok(_) => {},
-				// 		Err(err) => { panic!("{err}") }
-				// };
-				match Self::add( hash.clone(), controller.clone() ) {
-						Ok(_) => {},
-						Err(err) => { panic!("{err}") }
-				};
-				// match Self::add( hash.clone(), treasury.clone() ) {
-				// 		Ok(_) => {},
-				// 		Err(err) => { panic!("{err}") }
-				// };
+			}
 
-				// generate nft realm
+			// Update Body
+			// controller or governance
+			// changes name, cid, access, fees and member limit
+			// fees already paid or reserved by members are kept
+			#[weight = 1_000_000]
+			pub fn update_body(
+				origin,
+				hash: T::Hash,
+				name: Vec<u8>,
+				cid: Vec<u8>,
+				access: ControlAccessModel,
+				fee_model: ControlFeeModel,
+				fee: T::Balance,
+				member_limit: u64,
+			) -> DispatchResult {
 
-				// get the current realm index
-				let current_realm_index = tangram::NextRealmIndex::get();
+				let who = Self::ensure_controller_or_governance(origin, &hash)?;
+				ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
 
-				// every org receives a token realm by default
-				let realm = tangram::Module::<T>::create_realm(origin.clone(), hash.clone());
-				let realm = match realm {
-						Ok(_) => {},
-						Err(err) => { return Err(err) }
+				// 0 == no limit
+				let count = Self::body_member_count(&hash);
+				ensure!( member_limit == 0 || member_limit >= count, Error::<T>::MemberLimitTooLow );
+
+				let now = <system::Module<T>>::block_number();
+				Bodies::<T>::mutate( &hash, |body| {
+					body.name = name;
+					body.cid = cid;
+					body.mutated = now.clone();
+				});
+
+				let fee = match &fee_model {
+					ControlFeeModel::NoFees => T::Balance::zero(),
+					_ => fee,
 				};
+				BodyConfig::<T>::mutate( &hash, |config| {
+					config.access = access.clone();
+					config.fee_model = fee_model;
+					config.fee = fee;
+					config.member_limit = member_limit;
+				});
+				BodyAccess::<T>::insert( &hash, access );
 
-				// get current class index
-				let current_class_index = tangram::NextClassIndex::get(current_realm_index);
+				Self::deposit_event(
+					RawEvent::BodyUpdated(who, hash, now)
+				);
+				Ok(())
 
-				// generate a class name
-				let name:Vec<u8> = b"game".to_vec();
-				// every org receives a token class for collectables by default
-				let max = 1000; // TODO: externalise max
-				let class = tangram::Module::<T>::create_class(
-					origin.clone(),
-					current_realm_index.clone(),
-					name,
-					max,
-					// mint,
-					// burn,
-					strategy
+			}
+
+			// Dissolve Body
+			// controller or governance
+			// refunds reserved membership fees, pays out the free treasury
+			// pro rata to paid fees, evenly without fees,
+			// or back to the parent treasury, within the spend limit,
+			// archives the body and its realms
+			#[weight = 1_000_000 + 1_000_000 * T::MaxMembersPerBody::get() as Weight]
+			#[transactional]
+			pub fn dissolve(
+				origin,
+				hash: T::Hash,
+			) -> DispatchResult {
+
+				Self::ensure_controller_or_governance(origin, &hash)?;
+				ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
+				ensure!(
+					Self::body_children(&hash).iter().all(|child| Self::body_state(child) == ControlState::Archived),
+					Error::<T>::BodyHasChildren
 				);
-				let class = match class {
-						Ok(_) => {},
-						Err(err) => { return Err(err) }
-				};
 
-				// get the next realm index...
-				let next_realm_index = tangram::NextRealmIndex::get();
-
-				// mint an item for creator
-				// let item_name:Vec<u8> = b"creator".to_vec();
-				// let item_cid:Vec<u8> = b"0".to_vec();
-
-				// let item = tangram::Module::<T>::create_item(
-				// 	origin.clone(),
-				// 	current_realm_index,
-				// 	current_class_index,
-				// 	item_name,
-				// 	item_cid,
-				// 	creator.clone()
-				// );
-				// let item = match item {
-				// 		Ok(_) => {},
-				// 		Err(err) => { return Err(err) }
-				// };
-
-				// mint an item for controller
-				// let ctrl_item_name:Vec<u8> = b"controller".to_vec();
-				// let ctrl_item_cid:Vec<u8> = b"1".to_vec();
-
-				// let ctrl_item = tangram::Module::<T>::create_item(
-				// 	origin.clone(),
-				// 	current_realm_index,
-				// 	current_class_index,
-				// 	ctrl_item_name,
-				// 	ctrl_item_cid,
-				// 	controller.clone()
-				// );
-				// let ctrl_item = match ctrl_item {
-				// 		Ok(_) => {},
-				// 		Err(err) => { return Err(err) }
-				// };
-
-				// pay tribute
-				// let balance = <balances::Module<T>>::free_balance(&sender);
-				// let dao_fee = _fee.checked_mul(0.25);
-
-				let transfer = <balances::Module<T> as Currency<_>>::transfer(
-					&sender,
-					&T::GameDAOTreasury::get(),
-					creation_fee,
-					ExistenceRequirement::AllowDeath
+				let members = Self::body_members(&hash);
+				let treasury = Self::body_treasury(&hash);
+
+				// 1. refund reserved fees
+
+				let mut paid = T::Balance::zero();
+				let mut fees = Vec::with_capacity(members.len());
+				for member in &members {
+					let reserved = ReservedFees::<T>::take((&hash, member));
+					<balances::Module<T>>::unreserve( member, reserved );
+					let fee = PaidFees::<T>::take((&hash, member));
+					paid = paid.saturating_add(fee);
+					fees.push(fee);
+				}
+
+				// 2. pay out treasury,
+				// children return their treasury to an active parent,
+				// the payout is not limited by the spend limit
+
+				let available = <balances::Module<T>>::free_balance(&treasury);
+				let mut payout = T::Balance::zero();
+				let parent = Self::body_parent(&hash)
+					.filter(|parent| Self::body_state(parent) != ControlState::Archived);
+				if let Some(parent) = parent {
+					if !available.is_zero() {
+						<balances::Module<T> as Currency<_>>::transfer(
+							&treasury,
+							&Self::body_treasury(&parent),
+							available,
+							ExistenceRequirement::AllowDeath
+						)?;
+						payout = available;
+					}
+				} else if !members.is_empty() && !available.is_zero() {
+					let total = available.saturated_into::<u128>();
+					for (member, fee) in members.iter().zip(fees.iter()) {
+						let share = if paid.is_zero() {
+							total / members.len() as u128
+						} else {
+							multiply_by_rational(
+								total,
+								(*fee).saturated_into::<u128>(),
+								paid.saturated_into::<u128>()
+							).unwrap_or(0)
+						};
+						let share = share.saturated_into::<T::Balance>();
+						if share.is_zero() || member == &treasury { continue }
+						let transfer = <balances::Module<T> as Currency<_>>::transfer(
+							&treasury,
+							member,
+							share,
+							ExistenceRequirement::AllowDeath
+						);
+						if transfer.is_ok() { payout = payout.saturating_add(share); }
+					}
+				}
+
+				// 3. archive
+
+				for member in &members {
+					Memberships::<T>::mutate( member, |memberships| memberships.retain(|m| m != &hash) );
+					BodyMemberState::<T>::insert(( hash.clone(), member.clone() ), ControlMemberState::Exited);
+				}
+				BodyMembers::<T>::remove(&hash);
+				BodyMemberCount::<T>::insert(&hash, 0);
+				BodyState::<T>::insert(&hash, ControlState::Archived);
+				tangram::Module::<T>::archive_realms(&hash);
+
+				Self::deposit_event(
+					RawEvent::BodyDissolved(hash, payout)
 				);
+				Ok(())
+
+			}
+
+			// Migrate Treasury
+			// legacy treasury account or governance
+			// moves the free and reserved balance of the legacy account,
+			// e.g. paid fees, campaign balances and creator deposits,
+			// to the keyless treasury, which then replaces it
+			#[weight = 1_000_000]
+			#[transactional]
+			pub fn migrate_treasury(
+				origin,
+				hash: T::Hash,
+			) -> DispatchResult {
+
+				let legacy = Self::legacy_treasury(&hash).ok_or(Error::<T>::TreasuryMigrated)?;
+				if T::ForceOrigin::try_origin(origin.clone()).is_err() {
+					let sender = ensure_signed(origin)?;
+					ensure!( sender == legacy, Error::<T>::NotLegacyTreasury );
+				}
 
-				// nonce
-				Nonce::mutate(|n| *n += 1);
+				let treasury = Self::treasury_account(Self::body_by_hash(&hash).index);
+				// free first, reserves are only repatriated to existing accounts
+				let free = <balances::Module<T>>::free_balance(&legacy);
+				if !free.is_zero() {
+					<balances::Module<T> as Currency<_>>::transfer(
+						&legacy,
+						&treasury,
+						free,
+						ExistenceRequirement::AllowDeath
+					)?;
+				}
+				let reserved = <balances::Module<T>>::reserved_balance(&legacy);
+				if !reserved.is_zero() {
+					<balances::Module<T> as ReservableCurrency<_>>::repatriate_reserved(
+						&legacy,
+						&treasury,
+						reserved,
+						BalanceStatus::Reserved
+					)?;
+				}
+				let amount = free.saturating_add(reserved);
+				BodyTreasury::<T>::insert(&hash, treasury.clone());
+				LegacyTreasury::<T>::remove(&hash);
 
-				// dispatch event
 				Self::deposit_event(
-					RawEvent::BodyCreated(sender, hash, now, next_realm_index)
+					RawEvent::TreasuryMoved(hash, legacy, treasury, amount)
 				);
 				Ok(())
 
@@ -531,7 +824,7 @@ pub mod module {
 
 			// Add Member to Body
 			#[weight = 1_000_000]
-			fn add_member(
+			pub fn add_member(
 				origin,
 				hash: T::Hash,
 				account: T::AccountId
@@ -550,7 +843,7 @@ pub mod module {
 
 			// Remove Member from Body
 			#[weight = 1_000_000]
-			fn remove_member(
+			pub fn remove_member(
 				origin,
 				hash: T::Hash,
 				account: T::AccountId,
@@ -582,7 +875,7 @@ pub mod module {
 			// }
 
 			#[weight = 1_000_000]
-			fn check_membership(
+			pub fn check_membership(
 				origin,
 				hash: T::Hash
 			) -> DispatchResult {
@@ -638,6 +931,414 @@ pub mod module {
 
 		// }
 
+		// controller of the body, of one of its ancestors or governance
+		// returns the controller for events
+		fn ensure_controller_or_governance(
+			origin: T::Origin,
+			hash: &T::Hash,
+		) -> Result<T::AccountId, DispatchError> {
+
+			ensure!( <Bodies<T>>::contains_key(hash), Error::<T>::BodyUnknown );
+			let controller = Self::body_controller(hash);
+			match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => Ok(controller),
+				Err(origin) => {
+					let sender = ensure_signed(origin)?;
+					ensure!(
+						sender == controller ||
+						Self::ancestors(hash).iter().any(|ancestor| Self::body_controller(ancestor) == sender),
+						Error::<T>::NotController
+					);
+					Ok(sender)
+				}
+			}
+
+		}
+
+		// create a body on behalf of `sender`,
+		// `origin` creates the tangram realm
+		// the fee is paid first, callers run this in a transaction
+		// so any failure afterwards rolls back the body, its realm and class,
+		// genesis has no transactions and expects success
+		fn do_create(
+			origin: T::Origin,
+			sender: T::AccountId,
+			controller: T::AccountId,
+			name: Vec<u8>,
+			cid: Vec<u8>,
+			body: ControlType,
+			access: ControlAccessModel,
+			fee_model: ControlFeeModel,
+			fee: T::Balance,
+			gov_asset: u8,
+			pay_asset: u8,
+			member_limit: u64,
+		) -> Result<T::Hash, DispatchError> {
+
+			let now   = <system::Module<T>>::block_number();
+			let hash = <T as Config>::Randomness::random(&name);
+			let index = Nonce::get();
+			ensure!( !<Bodies<T>>::contains_key(&hash), Error::<T>::BodyExists );
+
+			// pay tribute before anything is written
+			let creation_fee = T::CreationFee::get();
+			let free_balance = balances::Module::<T>::free_balance(&sender);
+			ensure!( free_balance >= creation_fee, Error::<T>::BalanceTooLow );
+			<balances::Module<T> as Currency<_>>::transfer(
+				&sender,
+				&T::GameDAOTreasury::get(),
+				creation_fee,
+				ExistenceRequirement::AllowDeath
+			)?;
+
+			// keyless treasury
+			let treasury = Self::treasury_account(index);
+			let state = ControlState::Active; // live
+			// TODO: create enums for bonding strategies
+			let strategy = 0;
+
+			let body_data = Body {
+				id:       hash.clone(),
+				index:    index.clone(),
+				creator:  sender.clone(),
+				name:     name.clone(),
+				cid:      cid,
+				body:     body.clone(),
+				created:  now.clone(),
+				mutated:  now.clone(),
+			};
+			Bodies::<T>::insert( hash.clone(), body_data );
+
+			// membership fees
+			let mut _fee = T::Balance::zero();
+			match &fee_model {
+				ControlFeeModel::Reserve => { _fee = fee },
+				ControlFeeModel::Transfer => { _fee = fee },
+				_ => { }
+			};
+
+			let config_data = BConfig {
+				fee_model: fee_model.clone(),
+				fee: _fee.clone(),
+				gov_asset: gov_asset.clone(),
+				pay_asset: pay_asset.clone(),
+				member_limit: member_limit.clone(),
+				access: access.clone()
+			};
+			BodyConfig::<T>::insert( hash.clone(), config_data );
+
+			//
+
+			BodyByNonce::<T>::insert( index.clone(), hash.clone() );
+			BodyState::<T>::insert( hash.clone(), state );
+			BodyAccess::<T>::insert( hash.clone(), access.clone() );
+			BodyCreator::<T>::insert( hash.clone(), sender.clone() );
+			BodyController::<T>::insert( hash.clone(), controller.clone() );
+			BodyTreasury::<T>::insert( hash.clone(), treasury.clone() );
+
+			let mut controlled = Self::by_controller(&controller);
+			controlled.push(hash.clone());
+			ControlledBodies::<T>::mutate(
+				&controller,
+				|controlled| controlled.push(hash.clone())
+			);
+
+			// TODO: this needs a separate add / removal function
+			// whenever the controller of an organisation changes!
+			ControlledBodiesCount::<T>::mutate(
+				&controller,
+				|controlled_count| *controlled_count += 1
+			);
+
+
+			let mut created = Self::by_creator(sender.clone());
+			created.push(hash.clone());
+			ControlledBodies::<T>::mutate(
+				&sender,
+				|created| created.push(hash.clone())
+			);
+
+			// initiate member registry -> consumes fees
+			// creator and controller can be equal
+			// controller and treasury cannot be equal
+			// match Self::add( &hash, creator.clone() ) {
+			// 		Ok(_) => {},
+			// 		Err(err) => { panic!("{err}") }
+			// };
+			Self::add( hash.clone(), controller.clone() )?;
+			// match Self::add( hash.clone(), treasury.clone() ) {
+			// 		Ok(_) => {},
+			// 		Err(err) => { panic!("{err}") }
+			// };
+
+			// generate nft realm
+
+			// get the current realm index
+			let current_realm_index = tangram::NextRealmIndex::get();
+
+			// every org receives a token realm by default
+			tangram::Module::<T>::create_realm(origin.clone(), hash.clone())?;
+
+			// get current class index
+			let current_class_index = tangram::NextClassIndex::get(current_realm_index);
+
+			// generate a class name
+			let name:Vec<u8> = b"game".to_vec();
+			// every org receives a token class for collectables by default
+			let max = 1000; // TODO: externalise max
+			tangram::Module::<T>::create_class(
+				origin.clone(),
+				current_realm_index.clone(),
+				name,
+				max,
+				// mint,
+				// burn,
+				strategy
+			)?;
+
+			// get the next realm index...
+			let next_realm_index = tangram::NextRealmIndex::get();
+
+			// mint an item for creator
+			// let item_name:Vec<u8> = b"creator".to_vec();
+			// let item_cid:Vec<u8> = b"0".to_vec();
+
+			// let item = tangram::Module::<T>::create_item(
+			// 	origin.clone(),
+			// 	current_realm_index,
+			// 	current_class_index,
+			// 	item_name,
+			// 	item_cid,
+			// 	creator.clone()
+			// );
+			// let item = match item {
+			// 		Ok(_) => {},
+			// 		Err(err) => { return Err(err) }
+			// };
+
+			// mint an item for controller
+			// let ctrl_item_name:Vec<u8> = b"controller".to_vec();
+			// let ctrl_item_cid:Vec<u8> = b"1".to_vec();
+
+			// let ctrl_item = tangram::Module::<T>::create_item(
+			// 	origin.clone(),
+			// 	current_realm_index,
+			// 	current_class_index,
+			// 	ctrl_item_name,
+			// 	ctrl_item_cid,
+			// 	controller.clone()
+			// );
+			// let ctrl_item = match ctrl_item {
+			// 		Ok(_) => {},
+			// 		Err(err) => { return Err(err) }
+			// };
+
+			// nonce
+			Nonce::mutate(|n| *n += 1);
+
+			// dispatch event
+			Self::deposit_event(
+				RawEvent::BodyCreated(sender, hash, now, next_realm_index)
+			);
+			Ok(hash)
+
+		}
+
+		// record externally owned treasuries, they stay in use until
+		// `migrate_treasury` moves their funds to the keyless account
+		fn migrate_to_v1() -> Weight {
+			let count = Nonce::get();
+			for index in 0..count {
+				let hash = Self::body_by_nonce(index);
+				if !<Bodies<T>>::contains_key(&hash) { continue }
+				let treasury = Self::treasury_account(index);
+				let legacy = Self::body_treasury(&hash);
+				if legacy == treasury { continue }
+				LegacyTreasury::<T>::insert(&hash, legacy);
+			}
+			StorageVersion::put(Releases::V1);
+			T::DbWeight::get().reads_writes(
+				(count as Weight).saturating_mul(3).saturating_add(2),
+				(count as Weight).saturating_add(1)
+			)
+		}
+
+		/// storage invariants, checked against state snapshots after migrations
+		#[cfg(feature = "std")]
+		pub fn check_invariants() -> Result<(), &'static str> {
+			for (hash, _) in <Bodies<T>>::iter() {
+				let members = Self::body_members(&hash);
+				if Self::body_member_count(&hash) != members.len() as u64 {
+					return Err("member count does not match member list")
+				}
+				for member in &members {
+					if !Self::memberships(member).contains(&hash) {
+						return Err("member without membership")
+					}
+				}
+				let index = Self::body_by_hash(&hash).index;
+				if Self::body_by_nonce(index) != hash {
+					return Err("body index does not resolve to body")
+				}
+				if Self::body_treasury(&hash) != Self::treasury_account(index) && Self::legacy_treasury(&hash).is_none() {
+					return Err("treasury is not keyless")
+				}
+			}
+			if Self::storage_version() != Releases::V1 {
+				return Err("storage version not migrated")
+			}
+			Ok(())
+		}
+
+		/// keyless treasury of the body with `index`
+		pub fn treasury_account( index: u128 ) -> T::AccountId {
+			MODULE_ID.into_sub_account(index)
+		}
+
+		/// parents of a body, nearest first
+		pub fn ancestors( hash: &T::Hash ) -> Vec<T::Hash> {
+			let mut ancestors = Vec::new();
+			let mut current = Self::body_parent(hash);
+			while let Some(parent) = current {
+				if ancestors.len() as u32 >= T::MaxDepth::get() { break }
+				current = Self::body_parent(&parent);
+				ancestors.push(parent);
+			}
+			ancestors
+		}
+
+		/// children of a body and their children, breadth first
+		pub fn descendants( hash: &T::Hash ) -> Vec<T::Hash> {
+			let mut descendants = Self::body_children(hash);
+			let mut index = 0;
+			while index < descendants.len() {
+				let children = Self::body_children(&descendants[index]);
+				descendants.extend(children);
+				index += 1;
+			}
+			descendants
+		}
+
+		/// top level body of a tree
+		pub fn root_of( hash: &T::Hash ) -> T::Hash {
+			Self::ancestors(hash).pop().unwrap_or(*hash)
+		}
+
+		/// active member of the body or,
+		/// when inherited, of its parent
+		pub fn is_active_member( hash: &T::Hash, account: &T::AccountId ) -> bool {
+			if Self::body_member_state(( hash, account )) == ControlMemberState::Active {
+				return true
+			}
+			match ( Self::body_membership(hash), Self::body_parent(hash) ) {
+				( ControlMembershipModel::Inherited, Some(parent) ) => Self::is_active_member(&parent, account),
+				_ => false,
+			}
+		}
+
+		/// account `amount` against the spend limit of a body,
+		/// every outflow of the treasury is accounted here
+		/// before the funds leave or are unreserved
+		pub fn spend( hash: &T::Hash, amount: T::Balance ) -> DispatchResult {
+			if let Some(spent) = Self::spent_with(hash, amount)? {
+				BodySpent::<T>::insert( hash, spent );
+			}
+			Ok(())
+		}
+
+		// period start and spent amount including `amount`,
+		// none without a spend limit
+		fn spent_with(
+			hash: &T::Hash,
+			amount: T::Balance
+		) -> Result<Option<(T::BlockNumber, T::Balance)>, DispatchError> {
+			let (limit, period) = match Self::spend_limit(hash) {
+				Some(limit) => limit,
+				None => return Ok(None),
+			};
+			let now = <system::Module<T>>::block_number();
+			let (start, spent) = Self::spent(hash);
+			let (start, spent) = if now >= start.saturating_add(period) {
+				(now, T::Balance::zero())
+			} else {
+				(start, spent)
+			};
+			let spent = spent.saturating_add(amount);
+			ensure!( spent <= limit, Error::<T>::SpendLimitExceeded );
+			Ok(Some((start, spent)))
+		}
+
+		/// treasury paying `fee` for `account`,
+		/// when the body is active, sponsors, the member has budget left
+		/// and the fee is within the spend limit of the body
+		pub fn sponsor( hash: &T::Hash, account: &T::AccountId, fee: T::Balance ) -> Option<T::AccountId> {
+			if Self::body_state(hash) != ControlState::Active { return None }
+			if !Self::is_active_member(hash, account) { return None }
+			let (budget, period) = Self::sponsorship(hash)?;
+			let (_, spent) = Self::sponsored_in_period(hash, account, period);
+			if spent.saturating_add(fee) > budget { return None }
+			Self::spent_with(hash, fee).ok()?;
+			Some(Self::body_treasury(hash))
+		}
+
+		/// account a sponsored `fee` against the budget of `account`
+		/// and the spend limit of the body, checked by `sponsor`
+		pub fn note_sponsored( hash: &T::Hash, account: &T::AccountId, fee: T::Balance ) {
+			let period = match Self::sponsorship(hash) {
+				Some((_, period)) => period,
+				None => return,
+			};
+			let (start, spent) = Self::sponsored_in_period(hash, account, period);
+			SponsoredFees::<T>::insert(( hash, account ), (start, spent.saturating_add(fee)));
+			let _ = Self::spend( hash, fee );
+		}
+
+		/// return an overestimated sponsored fee to the budget of `account`
+		/// and the spend limit of the body
+		pub fn refund_sponsored( hash: &T::Hash, account: &T::AccountId, refund: T::Balance ) {
+			if refund.is_zero() { return }
+			SponsoredFees::<T>::mutate(( hash, account ), |(_, spent)| *spent = spent.saturating_sub(refund));
+			if Self::spend_limit(hash).is_some() {
+				BodySpent::<T>::mutate( hash, |(_, spent)| *spent = spent.saturating_sub(refund) );
+			}
+		}
+
+		// current period start and sponsored fees of a member
+		fn sponsored_in_period(
+			hash: &T::Hash,
+			account: &T::AccountId,
+			period: T::BlockNumber
+		) -> (T::BlockNumber, T::Balance) {
+			let now = <system::Module<T>>::block_number();
+			let (start, spent) = Self::sponsored_fees(( hash, account ));
+			if now >= start.saturating_add(period) {
+				(now, T::Balance::zero())
+			} else {
+				(start, spent)
+			}
+		}
+
+		// transfer from the parent to the child treasury
+		fn fund(
+			parent: &T::Hash,
+			child: &T::Hash,
+			amount: T::Balance
+		) -> DispatchResult {
+
+			Self::spend( parent, amount )?;
+			<balances::Module<T> as Currency<_>>::transfer(
+				&Self::body_treasury(parent),
+				&Self::body_treasury(child),
+				amount,
+				ExistenceRequirement::KeepAlive
+			)?;
+			Self::deposit_event(
+				RawEvent::ChildFunded(parent.clone(), child.clone(), amount)
+			);
+			Ok(())
+
+		}
+
 		fn set_member_state(
 			hash: T::Hash,
 			account: T::AccountId,
@@ -664,16 +1365,22 @@ pub mod module {
 			// 1. body exists
 
 			ensure!( <Bodies<T>>::contains_key(&hash), Error::<T>::BodyUnknown );
+			ensure!( Self::body_state(&hash) != ControlState::Archived, Error::<T>::BodyArchived );
+			if Self::body_membership(&hash) == ControlMembershipModel::Required {
+				let parent = Self::body_parent(&hash).ok_or(Error::<T>::NoParent)?;
+				ensure!( Self::is_active_member(&parent, &account), Error::<T>::ParentMembershipRequired );
+			}
 
-			// 2. member limit
+			// 2. member limit, body limit 0 == no limit
 
 			let mut members = BodyMembers::<T>::get(hash);
 			let max_members = T::MaxMembersPerBody::get();
 			ensure!(members.len() < max_members, Error::<T>::MembershipLimitReached);
+			let config = Self::body_config(hash);
+			ensure!(config.member_limit == 0 || (members.len() as u64) < config.member_limit, Error::<T>::MembershipLimitReached);
 
 			// 3. initial state
 
-			let config = Self::body_config(hash);
 			let state = match config.access {
 				ControlAccessModel::Open => ControlMemberState::Active, // active
 				_ => ControlMemberState::Pending, // pending
@@ -692,16 +1399,18 @@ pub mod module {
  				// reserve
  				ControlFeeModel::Reserve => {
 					<balances::Module<T>>::reserve(&account, config.fee)?;
+					ReservedFees::<T>::insert(( hash.clone(), account.clone() ), config.fee);
  				},
  				// transfer to treasury
  				ControlFeeModel::Transfer => {
 					let treasury = BodyTreasury::<T>::get(hash);
-					let transfer = <balances::Module<T> as Currency<_>>::transfer(
+					<balances::Module<T> as Currency<_>>::transfer(
 						&account,
 						&treasury,
 						config.fee,
 						ExistenceRequirement::AllowDeath
-					);
+					)?;
+					PaidFees::<T>::insert(( hash.clone(), account.clone() ), config.fee);
  				}
  				_ => {}
  			}
@@ -731,6 +1440,8 @@ pub mod module {
 					// state
 					BodyMemberState::<T>::insert(( hash.clone(), account.clone() ), state);
 
+					<T as Config>::SenseHooks::on_action( SenseAction::JoinBody, &account );
+
 					let now = <system::Module<T>>::block_number();
 					Self::deposit_event(
 						RawEvent::AddMember(hash,account,now)
@@ -769,11 +1480,11 @@ pub mod module {
 						Err(_) => {},
 					}
 
-					// unreserve?
-					let config = Self::body_config(hash);
-		 			if config.fee_model == ControlFeeModel::Reserve {
-						<balances::Module<T>>::unreserve( &account, config.fee );
-	 				}
+					// unreserve what was reserved on joining,
+					// fees paid to the treasury are kept
+					let reserved = ReservedFees::<T>::take(( &hash, &account ));
+					<balances::Module<T>>::unreserve( &account, reserved );
+					PaidFees::<T>::remove(( &hash, &account ));
 
 					// counter --
 					let count = members.len();
@@ -805,6 +1516,20 @@ pub mod module {
 
 	}
 
+	impl<T: Config> BodyProvider<T::AccountId, T::Hash> for Module<T> {
+		fn controller( hash: &T::Hash ) -> Option<T::AccountId> {
+			if Self::body_state(hash) != ControlState::Active { return None }
+			Some( Self::body_controller(hash) )
+		}
+		fn treasury( hash: &T::Hash ) -> Option<T::AccountId> {
+			if !<Bodies<T>>::contains_key(hash) { return None }
+			Some( Self::body_treasury(hash) )
+		}
+		fn is_member( hash: &T::Hash, account: &T::AccountId ) -> bool {
+			Self::is_active_member(hash, account)
+		}
+	}
+
 	//
 	//
 	//
@@ -814,11 +1539,19 @@ pub mod module {
 			<T as system::Config>::AccountId,
 			<T as system::Config>::BlockNumber,
 			<T as system::Config>::Hash,
+			<T as balances::Config>::Balance,
 		{
 			Message(Vec<u8>),
 			BodyCreated( AccountId, Hash, BlockNumber, u64),
 			BodyUpdated( AccountId, Hash, BlockNumber),
+			BodyEnabled( Hash ),
 			BodyDisabled( Hash ),
+			BodyDissolved( Hash, Balance ),
+			TreasuryMoved( Hash, AccountId, AccountId, Balance ),
+			ChildCreated( Hash, Hash ),
+			ChildFunded( Hash, Hash, Balance ),
+			SpendLimitSet( Hash, Balance, BlockNumber ),
+			SponsorshipSet( Hash, Balance, BlockNumber ),
 			BodyTransferred( AccountId, Hash, BlockNumber),
 			AddMember( Hash, AccountId, BlockNumber),
 			RemoveMember( Hash, AccountId, BlockNumber),
@@ -857,6 +1590,30 @@ pub mod module {
 			UnknownError,
 			/// Duplicate Address
 			DuplicateAddress,
+			/// Trust Too Low
+			TrustTooLow,
+			/// Body Archived
+			BodyArchived,
+			/// Not the Controller
+			NotController,
+			/// Member Limit below current Members
+			MemberLimitTooLow,
+			/// Treasury migrated
+			TreasuryMigrated,
+			/// Origin is not the legacy treasury
+			NotLegacyTreasury,
+			/// Body has no Parent
+			NoParent,
+			/// Body Tree too deep
+			MaxDepthReached,
+			/// Too many Children
+			TooManyChildren,
+			/// Children must be dissolved first
+			BodyHasChildren,
+			/// Membership of the Parent required
+			ParentMembershipRequired,
+			/// Spend Limit exceeded
+			SpendLimitExceeded,
 		}
 	}
 
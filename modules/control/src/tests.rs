@@ -1,107 +1,534 @@
-#[cfg(test)]
-mod tests {
-
-	use super::*;
-
-	use support::{impl_outer_origin, assert_ok, assert_noop};
-	use runtime_io::{with_externalities, TestExternalities};
-	use primitives::{H256, Blake2Hasher};
-	use runtime_primitives::{
-		BuildStorage,
-		traits::{BlakeTwo256, IdentityLookup},
-		testing::{Digest, DigestItem, Header}
-	};
-
-	impl_outer_origin! {
-		pub enum Origin for CampaignTest {}
-	}
-
-	#[derive(Clone, Eq, PartialEq)]
-	pub struct CampaignTest;
-
-	impl system::Trait for CampaignTest {
-		type Origin = Origin;
-		type Index = u64;
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type Digest = Digest;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type Log = DigestItem;
-	}
-
-	impl balances::Trait for ControlTest {
-		type Balance = u64;
-		type OnFreeBalanceZero = ();
-		type OnNewAccount = ();
-		type Event = ();
-		type TransactionPayment = ();
-		type TransferPayment = ();
-		type DustRemoval = ();
-	}
-
-	impl super::Trait for ControlTest {
-		type Event = ();
-	}
-
-	type Control = super::Module<ControlTest>;
-
-	fn build_ext() -> TestExternalities<Blake2Hasher> {
-		let mut t = system::GenesisConfig::<ControlTest>::default().build_storage().unwrap().0;
-		t.extend(balances::GenesisConfig::<ControlTest>::default().build_storage().unwrap().0);
-		t.into()
-	}
-
-	#[test]
-	fn create_campaign_should_work() {
-		with_externalities(&mut build_ext(), {
-
-			// create a DAO with account #5.
-			assert_ok!(
-				Control::create(
-					Origin::signed(5),	// creator == account 5
-					Origin::signed(4),	// controller == account 4
-					Origin::signed(3),	// treasury == account 3
-					vec![12,56],		// name
-					vec![11,111],		// cid
-					1,0,1,100,0,0,10
-				)
-			);
-
-			// check that there are now 1 Control in storage
-			assert_eq!(Control::nonce(), 1);
-
-			// check that account #5 is creator
-			let creator_hash = Control::body_by_hash(0);
-			assert_eq!(Control::body_creator(creator_hash), 5);
-
-			// check that account #4 is controller
-			let controller_hash = Control::body_by_hash(0);
-			assert_eq!(Control::body_controller(controller_hash), 4);
-
-			// check that account #3 is treasury
-			let treasury_hash = Control::body_by_hash(0);
-			assert_eq!(Control::body_treasury(treasury_hash), 3);
-
-			//
-
-			// check that account #6 owns 1 Campaign
-			assert_eq!(Control::campaigns_owned_count(5), 1);
-
-			// check that some random account #3 does not own a Campaign
-			assert_eq!(Control::campaigns_owned_count(3), 0);
-
-			// check that this Campaign is specifically owned by account #6
-			let owner_hash = Control::campaign_by_id(0);
-			assert_eq!(Control::campaign_owner(owner_hash), Some(5));
-
-			let other_hash = Control::campaigns_owned_index(5, 0);
-			assert_eq!(hash, other_hash);
-
-		})
-	}
+use super::*;
+use crate::mock::*;
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	migration::put_storage_value,
+	traits::{ Currency, OnRuntimeUpgrade, ReservableCurrency },
+	Blake2_128Concat, StorageHasher,
+};
+use primitives::BodyProvider;
+use sp_core::H256;
 
+//
+//	update and dissolve
+//
+
+#[test]
+fn controller_updates_body() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Transfer, 100);
+		assert_ok!( Control::add_member( Origin::signed(BOB), hash, BOB ) );
+
+		assert_noop!(
+			Control::update_body( Origin::signed(BOB), hash, b"x".to_vec(), vec![], ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0 ),
+			Error::<Test>::NotController
+		);
+		assert_noop!(
+			Control::update_body( Origin::signed(ALICE), hash, b"x".to_vec(), vec![], ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 1 ),
+			Error::<Test>::MemberLimitTooLow
+		);
+
+		// fees paid before the update are kept
+		assert_ok!( Control::update_body( Origin::signed(ALICE), hash, b"x".to_vec(), vec![], ControlAccessModel::Controller, ControlFeeModel::NoFees, 50, 3 ) );
+		assert_eq!( Control::paid_fee((hash, BOB)), 100 );
+		assert_eq!( Control::body_access(hash), ControlAccessModel::Controller );
+
+		// no fees ignores the fee amount
+		assert_ok!( Control::add_member( Origin::signed(CHARLIE), hash, CHARLIE ) );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+		assert!( Control::body_member_state((hash, CHARLIE)) == ControlMemberState::Pending );
+
+		// governance updates any body
+		assert_ok!( Control::update_body( Origin::root(), hash, b"y".to_vec(), vec![], ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0 ) );
+	});
+}
+
+#[test]
+fn dissolve_pays_out_pro_rata_to_paid_fees() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Transfer, 100);
+		let treasury = Control::body_treasury(hash);
+		assert_ok!( Control::add_member( Origin::signed(BOB), hash, BOB ) );
+		assert_ok!( Control::update_body( Origin::signed(ALICE), hash, b"body".to_vec(), vec![], ControlAccessModel::Open, ControlFeeModel::Transfer, 300, 0 ) );
+		assert_ok!( Control::add_member( Origin::signed(CHARLIE), hash, CHARLIE ) );
+		assert_eq!( Balances::free_balance(treasury), 500 );
+
+		assert_noop!( Control::dissolve( Origin::signed(BOB), hash ), Error::<Test>::NotController );
+		assert_ok!( Control::dissolve( Origin::signed(ALICE), hash ) );
+
+		// 500 split 100 : 100 : 300
+		assert_eq!( Balances::free_balance(ALICE), 1_000 - 10 - 100 + 100 );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+		assert_eq!( Balances::free_balance(treasury), 0 );
+		assert_eq!(
+			System::events().last().unwrap().event,
+			mock::Event::control(RawEvent::BodyDissolved(hash, 500))
+		);
+	});
+}
+
+#[test]
+fn dissolve_refunds_reserved_fees_and_splits_evenly() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Reserve, 50);
+		let treasury = Control::body_treasury(hash);
+		assert_ok!( Control::add_member( Origin::signed(BOB), hash, BOB ) );
+		assert_eq!( Balances::reserved_balance(BOB), 50 );
+
+		// donations without paid fees are split evenly
+		assert_ok!( Balances::transfer( Origin::signed(CHARLIE), treasury, 100 ) );
+		assert_ok!( Control::dissolve( Origin::root(), hash ) );
+
+		assert_eq!( Balances::reserved_balance(ALICE), 0 );
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert_eq!( Balances::free_balance(ALICE), 1_000 - 10 + 50 );
+		assert_eq!( Balances::free_balance(BOB), 1_000 + 50 );
+		assert_eq!( Control::reserved_fee((hash, BOB)), 0 );
+	});
+}
+
+#[test]
+fn dissolve_archives_body_and_realms() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::NoFees, 0);
+		let realm = Tangram::realm_for_owner(&hash).unwrap();
+		assert_ok!( Control::add_member( Origin::signed(BOB), hash, BOB ) );
+		assert_ok!( Control::dissolve( Origin::signed(ALICE), hash ) );
+
+		assert!( Control::body_state(hash) == ControlState::Archived );
+		assert!( Control::body_member_state((hash, BOB)) == ControlMemberState::Exited );
+		assert_eq!( Control::body_member_count(hash), 0 );
+		assert!( Control::memberships(BOB).is_empty() );
+		assert!( Tangram::realm_archived(realm) );
+		assert_eq!( <Control as BodyProvider<_, _>>::controller(&hash), None );
+
+		assert_noop!( Control::dissolve( Origin::signed(ALICE), hash ), Error::<Test>::BodyArchived );
+		assert_noop!( Control::add_member( Origin::signed(CHARLIE), hash, CHARLIE ), Error::<Test>::BodyArchived );
+		assert_noop!( Control::enable( Origin::root(), hash ), Error::<Test>::BodyArchived );
+		assert_noop!(
+			Tangram::create_class( Origin::signed(ALICE), realm, b"class".to_vec(), 10, 0 ),
+			tangram::Error::<Test>::RealmArchived
+		);
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+//
+//	treasury
+//
+
+// a body as a V0 chain left it, with an externally owned treasury
+fn set_legacy_treasury(hash: H256, legacy: u128) {
+	put_storage_value( b"Control50", b"BodyTreasury", &Blake2_128Concat::hash(&hash.encode()), legacy );
+	put_storage_value( b"Control50", b"StorageVersion", &[], Releases::V0 );
+}
+
+#[test]
+fn bodies_get_keyless_treasuries() {
+	new_test_ext().execute_with(|| {
+		let first = create_body(ControlFeeModel::Transfer, 100);
+		let second = create_body(ControlFeeModel::NoFees, 0);
+
+		assert_eq!( Control::body_treasury(first), Control::treasury_account(0) );
+		assert_eq!( Control::body_treasury(second), Control::treasury_account(1) );
+		assert_ne!( Control::treasury_account(0), Control::treasury_account(1) );
+		assert_eq!( Balances::free_balance(Control::treasury_account(0)), 100 );
+		assert_eq!( Balances::free_balance(GAMEDAO), 1 + 10 + 10 );
+		assert_eq!( Control::legacy_treasury(first), None );
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+#[test]
+fn upgrade_records_legacy_treasuries() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Transfer, 100);
+		set_legacy_treasury(hash, CHARLIE);
+		assert_eq!( Control::storage_version(), Releases::V0 );
+		assert_eq!( Control::body_treasury(hash), CHARLIE );
+
+		// the legacy treasury holds the funds until they are moved
+		Control::on_runtime_upgrade();
+		assert_eq!( Control::storage_version(), Releases::V1 );
+		assert_eq!( Control::body_treasury(hash), CHARLIE );
+		assert_eq!( Control::legacy_treasury(hash), Some(CHARLIE) );
+		assert_ok!( Control::check_invariants() );
+
+		// migrated once
+		assert_eq!( Control::on_runtime_upgrade(), 0 );
+		assert_eq!( Control::legacy_treasury(hash), Some(CHARLIE) );
+	});
+}
+
+#[test]
+fn legacy_treasury_moves_its_balance() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Transfer, 100);
+		let treasury = Control::treasury_account(0);
+		set_legacy_treasury(hash, CHARLIE);
+		Control::on_runtime_upgrade();
+		// campaign balances and deposits are reserved on the legacy treasury
+		assert_ok!( Balances::reserve( &Control::body_treasury(hash), 200 ) );
+
+		assert_noop!( Control::migrate_treasury( Origin::signed(BOB), hash ), Error::<Test>::NotLegacyTreasury );
+
+		assert_ok!( Control::migrate_treasury( Origin::signed(CHARLIE), hash ) );
+		assert_eq!( Balances::total_balance(&CHARLIE), 0 );
+		assert_eq!( Balances::free_balance(treasury), 100 + 800 );
+		assert_eq!( Balances::reserved_balance(treasury), 200 );
+		assert_eq!( Control::body_treasury(hash), treasury );
+		assert_eq!( Control::legacy_treasury(hash), None );
+		assert_noop!( Control::migrate_treasury( Origin::root(), hash ), Error::<Test>::TreasuryMigrated );
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+#[test]
+fn governance_migrates_drained_legacy_treasury() {
+	new_test_ext().execute_with(|| {
+		let hash = create_body(ControlFeeModel::Transfer, 100);
+		set_legacy_treasury(hash, BOB);
+		Control::on_runtime_upgrade();
+		assert_ok!( Balances::transfer( Origin::signed(BOB), CHARLIE, 960 ) );
+
+		// moves what is left
+		assert_ok!( Control::migrate_treasury( Origin::root(), hash ) );
+		assert_eq!( Balances::free_balance(BOB), 0 );
+		assert_eq!( Control::body_treasury(hash), Control::treasury_account(0) );
+		assert_eq!( Balances::free_balance(Control::treasury_account(0)), 140 );
+	});
+}
+
+//
+//	sub bodies
+//
+
+// parent controlled by alice with 500 in its treasury
+fn create_parent() -> H256 {
+	let parent = create_body(ControlFeeModel::NoFees, 0);
+	assert_ok!( Balances::transfer( Origin::signed(CHARLIE), Control::body_treasury(parent), 500 ) );
+	parent
+}
+
+// child controlled by bob
+fn create_child(parent: H256, membership: ControlMembershipModel, funding: u64) -> H256 {
+	assert_ok!( Control::create_child(
+		Origin::signed(ALICE), parent, BOB, b"guild".to_vec(), vec![],
+		ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+		membership, funding,
+	) );
+	*Control::body_children(parent).last().unwrap()
+}
+
+#[test]
+fn parent_creates_and_funds_children() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let child = create_child(parent, ControlMembershipModel::Independent, 100);
+		let grandchild = create_child(child, ControlMembershipModel::Independent, 0);
+
+		// the parent treasury pays creation and funding
+		assert_eq!( Balances::free_balance(Control::body_treasury(parent)), 500 - 10 - 100 );
+		assert_eq!( Balances::free_balance(Control::body_treasury(child)), 100 - 10 );
+		assert_eq!( Control::body_parent(child), Some(parent) );
+		assert_eq!( Control::ancestors(&grandchild), vec![child, parent] );
+		assert_eq!( Control::descendants(&parent), vec![child, grandchild] );
+		assert_eq!( Control::root_of(&grandchild), parent );
+
+		// the child controller does not control the parent treasury
+		assert_noop!( Control::fund_child( Origin::signed(BOB), child, 10 ), Error::<Test>::NotController );
+		assert_ok!( Control::fund_child( Origin::signed(ALICE), child, 10 ) );
+		assert_eq!( Balances::free_balance(Control::body_treasury(child)), 100 );
+
+		// ancestors control their descendants
+		assert_ok!( Control::update_body(
+			Origin::signed(ALICE), grandchild, b"team".to_vec(), vec![],
+			ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0
+		) );
+		assert_noop!( Control::fund_child( Origin::signed(ALICE), parent, 10 ), Error::<Test>::NoParent );
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+#[test]
+fn children_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let child = create_child(parent, ControlMembershipModel::Independent, 100);
+		let grandchild = create_child(child, ControlMembershipModel::Independent, 0);
+		assert_noop!(
+			Control::create_child(
+				Origin::signed(ALICE), grandchild, BOB, b"deep".to_vec(), vec![],
+				ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+				ControlMembershipModel::Independent, 0,
+			),
+			Error::<Test>::MaxDepthReached
+		);
+
+		create_child(parent, ControlMembershipModel::Independent, 0);
+		assert_noop!(
+			Control::create_child(
+				Origin::signed(ALICE), parent, BOB, b"wide".to_vec(), vec![],
+				ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+				ControlMembershipModel::Independent, 0,
+			),
+			Error::<Test>::TooManyChildren
+		);
+	});
+}
+
+#[test]
+fn child_membership_follows_model() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let inherited = create_child(parent, ControlMembershipModel::Inherited, 0);
+		let required = create_child(parent, ControlMembershipModel::Required, 0);
+
+		// alice is a member of the parent only
+		assert!( Control::is_active_member(&inherited, &ALICE) );
+		assert!( !Control::is_active_member(&inherited, &CHARLIE) );
+
+		assert_noop!(
+			Control::add_member( Origin::signed(CHARLIE), required, CHARLIE ),
+			Error::<Test>::ParentMembershipRequired
+		);
+		assert_ok!( Control::add_member( Origin::signed(CHARLIE), parent, CHARLIE ) );
+		assert_ok!( Control::add_member( Origin::signed(CHARLIE), required, CHARLIE ) );
+	});
+}
+
+#[test]
+fn spend_limit_resets_per_period() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let child = create_child(parent, ControlMembershipModel::Independent, 100);
+		assert_noop!( Control::set_spend_limit( Origin::signed(BOB), child, 50, 10 ), Error::<Test>::NotController );
+		assert_ok!( Control::set_spend_limit( Origin::signed(ALICE), child, 50, 10 ) );
+
+		assert_ok!( Control::spend(&child, 40) );
+		assert_noop!( Control::spend(&child, 20), Error::<Test>::SpendLimitExceeded );
+		assert_eq!( Control::spent(child), (0, 40) );
+
+		System::set_block_number(11);
+		assert_ok!( Control::spend(&child, 50) );
+		assert_eq!( Control::spent(child), (11, 50) );
+
+		// creating a grandchild spends the creation fee
+		assert_noop!(
+			Control::create_child(
+				Origin::signed(BOB), child, BOB, b"team".to_vec(), vec![],
+				ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+				ControlMembershipModel::Independent, 0,
+			),
+			Error::<Test>::SpendLimitExceeded
+		);
+
+		// a zero period removes the limit
+		assert_ok!( Control::set_spend_limit( Origin::signed(ALICE), child, 0, 0 ) );
+		assert_eq!( Control::spend_limit(child), None );
+		assert_ok!( Control::spend(&child, 1_000) );
+	});
+}
+
+#[test]
+fn dissolved_child_returns_treasury_to_parent() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let child = create_child(parent, ControlMembershipModel::Independent, 100);
+		assert_noop!( Control::dissolve( Origin::signed(ALICE), parent ), Error::<Test>::BodyHasChildren );
+
+		// the payout is not limited by the spend limit
+		assert_ok!( Control::set_spend_limit( Origin::signed(ALICE), child, 50, 10 ) );
+		assert_ok!( Control::dissolve( Origin::signed(BOB), child ) );
+		assert_eq!( Balances::free_balance(Control::body_treasury(parent)), 500 - 10 );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+
+		assert_ok!( Control::dissolve( Origin::signed(ALICE), parent ) );
+	});
+}
+
+//
+//	sponsorship
+//
+
+#[test]
+fn controller_sets_sponsorship() {
+	new_test_ext().execute_with(|| {
+		let hash = create_parent();
+		assert_eq!( Control::sponsor(&hash, &ALICE, 1), None );
+
+		assert_noop!( Control::set_sponsorship( Origin::signed(BOB), hash, 30, 10 ), Error::<Test>::NotController );
+		assert_ok!( Control::set_sponsorship( Origin::signed(ALICE), hash, 30, 10 ) );
+		assert_eq!( Control::sponsorship(hash), Some((30, 10)) );
+
+		// a zero period stops sponsoring
+		assert_ok!( Control::set_sponsorship( Origin::root(), hash, 30, 0 ) );
+		assert_eq!( Control::sponsorship(hash), None );
+	});
+}
+
+#[test]
+fn sponsorship_is_bounded_by_member_budget() {
+	new_test_ext().execute_with(|| {
+		let hash = create_parent();
+		let treasury = Control::body_treasury(hash);
+		assert_ok!( Control::set_sponsorship( Origin::signed(ALICE), hash, 30, 10 ) );
+
+		// members only
+		assert_eq!( Control::sponsor(&hash, &CHARLIE, 10), None );
+		assert_eq!( Control::sponsor(&hash, &ALICE, 31), None );
+		assert_eq!( Control::sponsor(&hash, &ALICE, 20), Some(treasury) );
+		Control::note_sponsored(&hash, &ALICE, 20);
+		assert_eq!( Control::sponsor(&hash, &ALICE, 20), None );
+		assert_eq!( Control::sponsor(&hash, &ALICE, 10), Some(treasury) );
+
+		// overestimated fees return to the budget
+		Control::refund_sponsored(&hash, &ALICE, 15);
+		assert_eq!( Control::sponsored_fees((hash, ALICE)), (0, 5) );
+		assert_eq!( Control::sponsor(&hash, &ALICE, 25), Some(treasury) );
+
+		// the budget renews every period
+		Control::note_sponsored(&hash, &ALICE, 25);
+		assert_eq!( Control::sponsor(&hash, &ALICE, 1), None );
+		System::set_block_number(11);
+		assert_eq!( Control::sponsor(&hash, &ALICE, 30), Some(treasury) );
+	});
+}
+
+#[test]
+fn sponsorship_requires_active_body_and_member() {
+	new_test_ext().execute_with(|| {
+		let hash = create_parent();
+		assert_ok!( Control::set_sponsorship( Origin::signed(ALICE), hash, 30, 10 ) );
+		assert_ok!( Control::update_body(
+			Origin::signed(ALICE), hash, b"body".to_vec(), vec![],
+			ControlAccessModel::Controller, ControlFeeModel::NoFees, 0, 0
+		) );
+		assert_ok!( Control::add_member( Origin::signed(BOB), hash, BOB ) );
+		assert_eq!( Control::sponsor(&hash, &BOB, 1), None );
+
+		assert_ok!( Control::disable( Origin::root(), hash ) );
+		assert_eq!( Control::sponsor(&hash, &ALICE, 1), None );
+		assert_ok!( Control::enable( Origin::root(), hash ) );
+		assert!( Control::sponsor(&hash, &ALICE, 1).is_some() );
+	});
+}
+
+#[test]
+fn sponsored_fees_count_against_spend_limit() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let child = create_child(parent, ControlMembershipModel::Inherited, 100);
+		assert_ok!( Control::set_sponsorship( Origin::signed(ALICE), child, 30, 10 ) );
+		assert_ok!( Control::set_spend_limit( Origin::signed(ALICE), child, 20, 10 ) );
+
+		// inherited members of the parent are sponsored
+		assert_eq!( Control::sponsor(&child, &ALICE, 25), None );
+		assert!( Control::sponsor(&child, &ALICE, 15).is_some() );
+		Control::note_sponsored(&child, &ALICE, 15);
+		assert_eq!( Control::spent(child), (0, 15) );
+		assert_eq!( Control::sponsor(&child, &BOB, 10), None );
+
+		Control::refund_sponsored(&child, &ALICE, 5);
+		assert_eq!( Control::spent(child), (0, 10) );
+		assert!( Control::sponsor(&child, &BOB, 10).is_some() );
+	});
+}
+
+//
+//	genesis
+//
+
+#[test]
+fn genesis_creates_bodies_with_members() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![ (ALICE, 1_000), (BOB, 1_000), (GAMEDAO, 1) ],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> {
+		bodies: vec![(
+			ALICE, ALICE, b"genesis".to_vec(), vec![], ControlType::Dao,
+			ControlAccessModel::Open, ControlFeeModel::Reserve, 50, 0, vec![ ALICE, BOB ],
+		)],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		let hash = Control::body_by_nonce(0);
+		assert_eq!( Control::body_members(hash), vec![ ALICE, BOB ] );
+		assert_eq!( Control::body_controller(hash), ALICE );
+		assert_eq!( Control::body_treasury(hash), Control::treasury_account(0) );
+		assert!( Control::body_state(hash) == ControlState::Active );
+		assert_eq!( Balances::reserved_balance(BOB), 50 );
+		assert_eq!( Balances::free_balance(GAMEDAO), 1 + 10 );
+		assert_eq!( Tangram::realm_for_owner(&hash), Some(0) );
+		assert_eq!( Control::storage_version(), Releases::V1 );
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+#[test]
+#[should_panic(expected = "genesis member is valid")]
+fn genesis_rejects_invalid_members() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![ (ALICE, 1_000), (BOB, 10), (GAMEDAO, 1) ],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> {
+		bodies: vec![(
+			ALICE, ALICE, b"genesis".to_vec(), vec![], ControlType::Dao,
+			ControlAccessModel::Open, ControlFeeModel::Reserve, 50, 0, vec![ BOB ],
+		)],
+	}.assimilate_storage(&mut t).unwrap();
+}
+
+//
+//	atomic creation
+//
+
+#[test]
+fn failed_creation_rolls_back_fee_body_and_realm() {
+	new_test_ext().execute_with(|| {
+		// the controller cannot pay the membership fee
+		// after the fee, body, realm and class were written
+		assert_noop!(
+			Control::create(
+				Origin::signed(ALICE), BOB, b"body".to_vec(), vec![], ControlType::Dao,
+				ControlAccessModel::Open, ControlFeeModel::Reserve, 2_000, 0, 0, 0,
+			),
+			Error::<Test>::BalanceTooLow
+		);
+		assert_eq!( Balances::free_balance(ALICE), 1_000 );
+		assert_eq!( Tangram::next_realm_index(), 0 );
+
+		assert_ok!( Control::create(
+			Origin::signed(ALICE), BOB, b"body".to_vec(), vec![], ControlType::Dao,
+			ControlAccessModel::Open, ControlFeeModel::Reserve, 20, 0, 0, 0,
+		) );
+		let hash = Control::body_by_nonce(0);
+		assert_eq!( Control::body_members(hash), vec![ BOB ] );
+		assert_eq!( Tangram::realm_for_owner(&hash), Some(0) );
+		assert_ok!( Control::check_invariants() );
+	});
+}
+
+#[test]
+fn failed_child_creation_keeps_parent_treasury() {
+	new_test_ext().execute_with(|| {
+		let parent = create_parent();
+		let treasury = Control::body_treasury(parent);
+
+		// funding beyond the parent treasury fails after the child was created
+		assert_noop!(
+			Control::create_child(
+				Origin::signed(ALICE), parent, BOB, b"guild".to_vec(), vec![],
+				ControlType::Guild, ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0,
+				ControlMembershipModel::Independent, 1_000,
+			),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_eq!( Balances::free_balance(treasury), 500 );
+		assert!( Control::body_children(parent).is_empty() );
+	});
 }
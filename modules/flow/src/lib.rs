@@ -43,8 +43,26 @@
 //! - creator can request allocation of funds
 //! - investors can collectively approve allocation of funds
 //!
-//! TODO:
-//! - supervisor can lock, cancel campaigns
+//! Campaigns can declare milestones up front. Each milestone releases
+//! a tranche of the raised funds after a contributor vote. If a milestone
+//! vote fails or its deadline passes, contributors are refunded pro rata
+//! from the remaining balance.
+//!
+//! Campaigns can be scheduled to start in the future, restrict contributions
+//! per account and to an allowlist of body members. Owners can request one
+//! extension of the expiry, which contributors can veto with their contributions.
+//!
+//! Reward tiers mint tangram items in the realm of the body to contributors
+//! of a successful campaign, according to their total contribution.
+//!
+//! Campaign states follow a transition table:
+//!
+//! - owner can pause, resume and cancel
+//! - admin can lock and unlock
+//! - only finalization settles a campaign as success or failure
+//!
+//! Cancelled and destroyed campaigns refund their contributors
+//! and are removed from all indexes.
 //!
 
 // 1. create campaigns with custom funding goal and runtime
@@ -64,7 +82,8 @@
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage,
 	ensure,
-	dispatch::DispatchResult,
+	dispatch::{ DispatchError, DispatchResult },
+	weights::Weight,
 	traits::{
 		EnsureOrigin,
 		Randomness,
@@ -75,7 +94,7 @@ use frame_support::{
 		Time,
 		UnixTime,
 	},
-	storage::child::exists
+	storage::{ child::exists, IterableStorageMap, StoragePrefixedMap },
 };
 use frame_system::{ self as system, ensure_signed, ensure_root};
 
@@ -84,11 +103,15 @@ use sp_core::{ Hasher, H256 };
 use sp_core::U256;
 use sp_runtime::{
 	traits::{
+		CheckedAdd,
 		CheckedMul,
 		One,
 		Saturating,
-		UniqueSaturatedInto
+		SaturatedConversion,
+		UniqueSaturatedInto,
+		Zero,
 	},
+	helpers_128bit::multiply_by_rational,
 	FixedPointNumber,
 };
 
@@ -101,11 +124,14 @@ use sp_std::prelude::*;
 use codec::{ Encode, Decode };
 
 use timestamp;
-use primitives::{ Balance };
+use primitives::{ Balance, SenseAction, SenseHooks };
+use tangram::{ ClassIndex, RealmIndex };
 
 use scale_info::TypeInfo;
 
-// TODO: tests
+#[cfg(test)]
+mod mock;
+
 #[cfg(test)]
 mod tests;
 
@@ -124,6 +150,20 @@ mod tests;
 const MODULE_ID: ModuleId = ModuleId(*b"modraise");
 const MODULE_VERSION: &str = "1.0";
 
+/// Storage version
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo)]
+#[derive(Debug)]
+pub enum Releases {
+	/// unversioned, campaigns without start block
+	V0,
+	/// scheduled campaigns, open campaigns counted per owner
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self { Releases::V0 }
+}
+
 //
 //	E N U M S
 //
@@ -163,6 +203,50 @@ pub enum FlowState {
 	Locked = 5
 }
 
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, PartialOrd, Ord, TypeInfo)]
+#[derive(Debug)]
+#[repr(u8)]
+pub enum MilestoneState {
+	#[default]
+	Pending = 0,
+	Voting = 1,
+	Released = 2,
+	Failed = 3
+}
+
+/// Roles allowed to change the state of a campaign
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo)]
+#[derive(Debug)]
+pub enum FlowRole {
+	Owner,
+	Admin,
+}
+
+impl FlowState {
+
+	/// transition table, settlement is reserved for finalization
+	pub fn can_transition( &self, to: &FlowState, role: FlowRole ) -> bool {
+		use FlowState::*;
+		match (role, self, to) {
+			(FlowRole::Owner, Active, Paused) => true,
+			(FlowRole::Owner, Paused, Active) => true,
+			(FlowRole::Admin, Init, Locked) => true,
+			(FlowRole::Admin, Active, Locked) => true,
+			(FlowRole::Admin, Paused, Locked) => true,
+			(FlowRole::Admin, Locked, Init) => true,
+			(FlowRole::Admin, Locked, Active) => true,
+			(FlowRole::Admin, Locked, Paused) => true,
+			_ => false,
+		}
+	}
+
+	/// campaign is not settled yet
+	pub fn is_open( &self ) -> bool {
+		*self < FlowState::Success || *self == FlowState::Locked
+	}
+
+}
+
 //
 //	C O N F I G
 //
@@ -183,7 +267,10 @@ pub trait Config: system::Config + balances::Config + timestamp::Config + contro
 
 	type MaxCampaignsPerAddress: Get<usize>;
 	type MaxCampaignsPerBlock: Get<usize>;
+	/// max contributions to a campaign per block
 	type MaxContributionsPerBlock: Get<usize>;
+	/// experience for contributions
+	type SenseHooks: SenseHooks<Self::AccountId>;
 
 	type MinDuration: Get<Self::BlockNumber>;
 	type MaxDuration: Get<Self::BlockNumber>;
@@ -195,6 +282,16 @@ pub trait Config: system::Config + balances::Config + timestamp::Config + contro
 
 	type CampaignFee: Get<Self::Balance>;
 
+	type MaxMilestones: Get<usize>;
+	/// max milestone deadlines per block
+	type MaxMilestonesPerBlock: Get<usize>;
+	type MaxRewardTiers: Get<usize>;
+	/// max contributors rewarded per block
+	type MaxRewardsPerBlock: Get<u32>;
+	/// max contributors of removed campaigns refunded and cleaned up per block,
+	/// and max contributors of failed milestones refunded per block
+	type MaxCleanupPerBlock: Get<u32>;
+
 }
 
 // TODO: this can be decomposed to improve weight
@@ -222,8 +319,8 @@ pub struct Campaign<Hash, AccountId, Balance, BlockNumber, Timestamp, FlowProtoc
 	/// campaign owners deposit
 	deposit: Balance,
 
-	// TODO: /// campaign start block
-	// start: BlockNumber,
+	/// campaign start block
+	start: BlockNumber,
 	/// block until campaign has to reach cap
 	expiry: BlockNumber,
 	/// minimum amount of token to become a successful campaign
@@ -252,6 +349,89 @@ pub struct Campaign<Hash, AccountId, Balance, BlockNumber, Timestamp, FlowProtoc
 
 }
 
+// campaign layout before scheduled starts, only decoded by the v1 migration
+#[derive(Encode, Decode)]
+struct OldCampaign<Hash, AccountId, Balance, BlockNumber, Timestamp, FlowProtocol, FlowGovernance> {
+	id: Hash,
+	org: Hash,
+	name: Vec<u8>,
+	owner: AccountId,
+	admin: AccountId,
+	deposit: Balance,
+	expiry: BlockNumber,
+	cap: Balance,
+	protocol: FlowProtocol,
+	governance: FlowGovernance,
+	cid: Vec<u8>,
+	token_symbol: Vec<u8>,
+	token_name: Vec<u8>,
+	created: Timestamp,
+}
+
+/// Milestone of a campaign, releasing a tranche of the raised funds
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[derive(Debug)]
+pub struct Milestone<Balance, BlockNumber> {
+	/// amount released with this tranche
+	pub amount: Balance,
+	/// content storage
+	pub cid: Vec<u8>,
+	/// block until the tranche has to be released
+	pub deadline: BlockNumber,
+}
+
+/// Contribution rules of a campaign
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[derive(Debug)]
+pub struct ContributionRules<Balance> {
+	/// minimum per contribution
+	pub min_contribution: Balance,
+	/// maximum total contribution per account
+	pub max_contribution: Option<Balance>,
+	/// only allowlisted body members may contribute
+	pub allowlist: bool,
+}
+
+/// Reward tier of a campaign, minted as tangram item
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[derive(Debug)]
+pub struct RewardTier<Balance> {
+	/// minimum total contribution for the tier
+	pub min_contribution: Balance,
+	/// class in the realm of the body
+	pub class: ClassIndex,
+	/// max items minted for the tier
+	pub max_supply: u64,
+	/// item name
+	pub name: Vec<u8>,
+	/// content storage
+	pub cid: Vec<u8>,
+}
+
+/// Terms of a campaign beyond funding: schedule, rules, milestones and rewards
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[derive(Debug)]
+pub struct CampaignTerms<Balance, BlockNumber> {
+	/// block the campaign opens for contributions
+	pub start: BlockNumber,
+	/// contribution rules
+	pub rules: ContributionRules<Balance>,
+	/// tranches released after success, none to release on success
+	pub milestones: Vec<Milestone<Balance, BlockNumber>>,
+	/// reward tiers minted on success
+	pub tiers: Vec<RewardTier<Balance>>,
+}
+
+/// Requested extension of a campaign expiry
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[derive(Debug)]
+pub struct Extension<Balance, BlockNumber> {
+	/// requested expiry
+	pub expiry: BlockNumber,
+	/// contributions vetoing the extension
+	pub vetoed: Balance,
+}
+
 decl_storage! {
 	trait Store for Module<T: Config> as Flow50 {
 
@@ -321,11 +501,89 @@ decl_storage! {
 		CampaignContributors get(fn campaign_contributors): map hasher(blake2_128_concat) T::Hash => Vec<T::AccountId>;
 		CampaignContributorsCount get(fn campaign_contributors_count): map hasher(blake2_128_concat) T::Hash => u64;
 
+		/// Campaigns starting in block x
+		CampaignsByStart get(fn campaigns_by_start): map hasher(blake2_128_concat) T::BlockNumber => Vec<T::Hash>;
+		/// Open campaigns by creator
+		CampaignsOpenByOwner get(fn campaigns_open_by_owner): map hasher(blake2_128_concat) T::AccountId => u32;
+		/// Campaigns created in the current block
+		CampaignsInBlock get(fn campaigns_in_block): (T::BlockNumber, u32);
+		/// Contributions to a campaign in the current block
+		ContributionsInBlock get(fn contributions_in_block): map hasher(blake2_128_concat) T::Hash => (T::BlockNumber, u32);
+
+		/// Contribution rules of a campaign
+		CampaignRules get(fn campaign_rules): map hasher(blake2_128_concat) T::Hash => ContributionRules<T::Balance>;
+		/// Body members allowed to contribute to a campaign
+		CampaignAllowlist get(fn allowlisted): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// Requested extension of a campaign, one per campaign
+		CampaignExtensions get(fn campaign_extension): map hasher(blake2_128_concat) T::Hash => Option<Extension<T::Balance, T::BlockNumber>>;
+		/// Contributors vetoing the extension of a campaign
+		ExtensionVetoes get(fn vetoed): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => bool;
+
+		/// Reward tiers of a campaign, ascending by contribution
+		CampaignRewardTiers get(fn reward_tiers): map hasher(blake2_128_concat) T::Hash => Vec<RewardTier<T::Balance>>;
+		/// Realm rewards are minted in
+		CampaignRealm get(fn campaign_realm): map hasher(blake2_128_concat) T::Hash => RealmIndex;
+		/// Items minted per campaign and tier
+		RewardTierMinted get(fn reward_tier_minted): map hasher(blake2_128_concat) (T::Hash, u32) => u64;
+		/// Successful campaigns minting rewards, with the next contributor to reward
+		RewardQueue get(fn reward_queue): Vec<(T::Hash, u32)>;
+		/// Removed campaigns with contributors left to clean up, and whether to refund them
+		CleanupQueue get(fn cleanup_queue): Vec<(T::Hash, bool)>;
+
+		/// Milestones of a campaign, released in order
+		CampaignMilestones get(fn campaign_milestones): map hasher(blake2_128_concat) T::Hash => Vec<Milestone<T::Balance, T::BlockNumber>>;
+		/// State of a milestone by campaign and index
+		CampaignMilestoneState get(fn milestone_state): map hasher(blake2_128_concat) (T::Hash, u32) => MilestoneState;
+		/// Index of the next milestone to release
+		CampaignMilestoneIndex get(fn current_milestone): map hasher(blake2_128_concat) T::Hash => u32;
+		/// Balance released through milestones
+		CampaignBalanceReleased get(fn released_balance): map hasher(blake2_128_concat) T::Hash => T::Balance;
+		/// Milestones with a deadline in block x
+		MilestonesByBlock get(fn milestones_by_block): map hasher(blake2_128_concat) T::BlockNumber => Vec<(T::Hash, u32)>;
+		/// Campaigns with a failed milestone, the balance to refund and the next contributor to refund
+		MilestoneRefunds get(fn milestone_refunds): Vec<(T::Hash, T::Balance, u32)>;
+
 		// Max campaign block limit
 		// CampaignMaxDuration get(fn get_max_duration) config(): T::BlockNumber = T::BlockNumber::from(T::MaxDuration::get());
 
 		// Campaign nonce, increases per created campaign
 		Nonce: u64;
+
+		/// Storage version, new chains start at the latest
+		StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V1): Releases;
+	}
+	add_extra_genesis {
+		/// campaigns created at genesis by the body controller:
+		/// (body nonce, name, target, deposit, expiry, cid)
+		config(campaigns): Vec<(u128, Vec<u8>, T::Balance, T::Balance, T::BlockNumber, Vec<u8>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (body, name, target, deposit, expiry, cid) in config.campaigns.iter() {
+				let org = control::Module::<T>::body_by_nonce(body);
+				let controller = control::Module::<T>::body_controller(&org);
+				Module::<T>::create(
+					system::RawOrigin::Signed(controller.clone()).into(),
+					org,
+					controller,
+					name.clone(),
+					target.clone(),
+					deposit.clone(),
+					expiry.clone(),
+					FlowProtocol::default(),
+					FlowGovernance::default(),
+					cid.clone(),
+					b"PLAY".to_vec(),
+					name.clone(),
+					CampaignTerms {
+						rules: ContributionRules {
+							min_contribution: T::MinContribution::get(),
+							..Default::default()
+						},
+						..Default::default()
+					},
+				).expect("genesis campaign is valid");
+			}
+		});
 	}
 }
 
@@ -339,11 +597,22 @@ decl_event! {
 		State = FlowState
 	{
 		CampaignDestroyed(Hash),
+		CampaignCancelled(Hash),
 		CampaignCreated(Hash, AccountId, AccountId, Balance, Balance, BlockNumber, Vec<u8>),
 		CampaignContributed(Hash, AccountId, Balance, BlockNumber),
 		CampaignFinalized(Hash, Balance, BlockNumber, bool),
 		CampaignFailed(Hash, Balance, BlockNumber, bool),
 		CampaignUpdated(Hash, State, BlockNumber),
+		Allowed(Hash, AccountId),
+		Disallowed(Hash, AccountId),
+		ExtensionRequested(Hash, BlockNumber),
+		ExtensionVetoed(Hash, AccountId, Balance),
+		CampaignExtended(Hash, BlockNumber),
+		RewardMinted(Hash, AccountId, u32, Hash),
+		MilestoneSubmitted(Hash, u32),
+		MilestoneReleased(Hash, u32, Balance),
+		MilestoneReopened(Hash, u32),
+		MilestoneFailed(Hash, u32, Balance),
 		Message(EventMessage),
 	}
 }
@@ -356,19 +625,43 @@ decl_module! {
 		fn deposit_event() = default;
 
 		// possibly this needs to become invalidated vs destroyed
-		#[weight = 1_000_000]
+		// refunds contributors and removes the campaign,
+		// successful campaigns are settled and can not be destroyed
+		#[weight = 10_000_000]
 		fn destroy( origin, id: T:: Hash ) -> DispatchResult {
 			ensure_root(origin)?;
+			ensure!( Campaigns::<T>::contains_key(&id), Error::<T>::InvalidId );
+			ensure!( Self::campaign_state(&id) != FlowState::Success, Error::<T>::CampaignSettled );
+			Self::remove_campaign(&id);
 			Self::deposit_event(
 				RawEvent::CampaignDestroyed(id)
 			);
 			Ok(())
 		}
 
+		// owner cancels an unsettled campaign,
+		// refunds contributors and removes the campaign
+		#[weight = 10_000_000]
+		fn cancel( origin, id: T::Hash ) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::campaign_owner(&id).ok_or(Error::<T>::OwnerUnknown)?;
+			ensure!( sender == owner, Error::<T>::AuthorizationError );
+			let state = Self::campaign_state(&id);
+			ensure!(
+				state == FlowState::Init || state == FlowState::Active || state == FlowState::Paused,
+				Error::<T>::InvalidTransition
+			);
+			Self::remove_campaign(&id);
+			Self::deposit_event(
+				RawEvent::CampaignCancelled(id)
+			);
+			Ok(())
+		}
+
 		// update the campaign status
 		// 0 init, 1 active, 2 paused, 3 complete success, 4 complete failed, 5 authority lock
-		// admin can set any status
-		// owner can pause, cancel
+		// owner can pause, resume
+		// admin can lock, unlock
 		#[weight = 1_000_000]
 		fn update_state(
 			origin,
@@ -381,24 +674,32 @@ decl_module! {
 
 			let owner = Self::campaign_owner(campaign_id).ok_or(Error::<T>::OwnerUnknown)?;
 			let admin = Self::campaign_admin(campaign_id).ok_or(Error::<T>::AdminUnknown)?;
-			ensure!( sender == admin, Error::<T>::AuthorizationError );
+			ensure!( sender == owner || sender == admin, Error::<T>::AuthorizationError );
 
 			// expired?
 			let campaign = Self::campaign_by_id(&campaign_id);
-			ensure!(<system::Module<T>>::block_number() < campaign.expiry, Error::<T>::CampaignExpired );
+			let now = <system::Module<T>>::block_number();
+			ensure!( now < campaign.expiry, Error::<T>::CampaignExpired );
 
-			// not finished or locked?
+			// allowed for the role?
 			let current_state = Self::campaign_state(campaign_id);
-			ensure!(
-				current_state < FlowState::Success,
-				Error::<T>::CampaignExpired
-			);
+			let allowed =
+				( sender == owner && current_state.can_transition(&state, FlowRole::Owner) ) ||
+				( sender == admin && current_state.can_transition(&state, FlowRole::Admin) );
+			ensure!( allowed, Error::<T>::InvalidTransition );
+
+			// only scheduled campaigns wait for their start,
+			// before it they can only be locked or unlocked back to init
+			if now < campaign.start {
+				ensure!( state == FlowState::Init || state == FlowState::Locked, Error::<T>::InvalidTransition );
+			} else {
+				ensure!( state != FlowState::Init, Error::<T>::InvalidTransition );
+			}
 
 			// set
 			Self::set_state(campaign_id.clone(), state.clone());
 
 			// dispatch status update event
-			let now = <system::Module<T>>::block_number();
 			Self::deposit_event(
 				RawEvent::CampaignUpdated(
 					campaign_id,
@@ -424,10 +725,13 @@ decl_module! {
 			cid: Vec<u8>,           // content cid
 			token_symbol: Vec<u8>,  // up to 5
 			token_name: Vec<u8>,    // cleartext
+			terms: CampaignTerms<T::Balance, T::BlockNumber>,
 			// token_curve_a: u8,      // preset
 			// token_curve_b: Vec<u8>, // custom
 		) {
 
+			let CampaignTerms { start, rules, milestones, tiers } = terms;
+
 			let creator = ensure_signed(origin)?;
 			let controller = control::Module::<T>::body_controller(org.clone());
 			ensure!( creator == controller, Error::<T>::AuthorizationError );
@@ -437,6 +741,7 @@ decl_module! {
 
 			let free_balance = balances::Module::<T>::free_balance(&treasury);
 			ensure!(free_balance > deposit, Error::<T>::TreasuryBalanceTooLow );
+			ensure!(deposit >= T::MinCreatorDeposit::get(), Error::<T>::DepositTooLow );
 			ensure!(deposit <= target, Error::<T>::DepositTooHigh );
 
 			// check name length boundary
@@ -447,21 +752,41 @@ decl_module! {
 			let now = <system::Module<T>>::block_number();
 			let timestamp = <timestamp::Module<T>>::get();
 
-			// ensure campaign expires after now
-			ensure!(expiry > now, Error::<T>::EndTooEarly );
+			// a start in the past starts right away
+			let start = start.max(now);
 
-			let max_length = T::MaxDuration::get();
-			let max_end_block = now + max_length;
-			ensure!(expiry <= max_end_block, Error::<T>::EndTooLate );
+			// ensure campaign runs at least min, at most max duration
+			ensure!(expiry > start, Error::<T>::EndTooEarly );
+			ensure!(expiry >= start + T::MinDuration::get(), Error::<T>::EndTooEarly );
+			ensure!(expiry <= start + T::MaxDuration::get(), Error::<T>::EndTooLate );
+
+			// milestones release at most the target, in order, after expiry
+			Self::validate_milestones(&milestones, target, expiry)?;
+
+			// rewards are minted in the realm of the body
+			Self::validate_reward_tiers(&org, &tiers)?;
+
+			// per account contribution bounds
+			ensure!(rules.min_contribution >= T::MinContribution::get(), Error::<T>::ContributionTooSmall );
+			if let Some(max) = rules.max_contribution {
+				ensure!(max >= rules.min_contribution, Error::<T>::ContributionRulesInvalid );
+			}
+
+			// open campaigns per creator
+			let open = Self::campaigns_open_by_owner(&creator);
+			ensure!((open as usize) < T::MaxCampaignsPerAddress::get(), Error::<T>::CampaignsPerAddressExceeded );
 
 			// generate the unique campaign id + ensure uniqueness
 			let phrase = b"crowdfunding_campaign"; // create from name?
 			let id = <T as Config>::Randomness::random(phrase);
-			// ensure!(!<CampaignOwner<T>>::exists(&id), Error::<T>::IdExists ); // check for collision
+			ensure!(!Campaigns::<T>::contains_key(&id), Error::<T>::IdExists ); // check for collision
 
-			// check contribution limit per block
-			let contributions = Self::campaigns_by_block(expiry);
-			ensure!(contributions.len() < T::MaxCampaignsPerBlock::get(), Error::<T>::ContributionsPerBlockExceeded );
+			// check campaign limit per block, for creation and finalization
+			let (block, created) = Self::campaigns_in_block();
+			let created = if block == now { created } else { 0 };
+			ensure!((created as usize) < T::MaxCampaignsPerBlock::get(), Error::<T>::CampaignsPerBlockExceeded );
+			let expiring = Self::campaigns_by_block(expiry);
+			ensure!(expiring.len() < T::MaxCampaignsPerBlock::get(), Error::<T>::CampaignsPerBlockExceeded );
 
 			//
 			//
@@ -474,6 +799,7 @@ decl_module! {
 				owner: creator.clone(),
 				admin: admin.clone(),
 				deposit: deposit.clone(),
+				start: start.clone(),
 				expiry: expiry.clone(),
 				cap: target.clone(),
 				protocol: protocol.clone(),
@@ -489,12 +815,20 @@ decl_module! {
 			Self::mint(
 				new_campaign
 			)?;
+			Self::insert_milestones(&id, milestones);
+			Self::insert_reward_tiers(&id, &org, tiers);
+			CampaignRules::<T>::insert(&id, rules);
+			CampaignsOpenByOwner::<T>::insert(&creator, open + 1);
+			CampaignsInBlock::<T>::put((now, created + 1));
 
 			// 0 init, 1 active, 2 paused, 3 complete success, 4 complete failed, 5 authority lock
-			Self::set_state(
-				id.clone(),
-				FlowState::Active
-			);
+			// scheduled campaigns stay in init until their start block
+			if start > now {
+				CampaignsByStart::<T>::mutate( &start, |campaigns| campaigns.push(id.clone()) );
+				Self::set_state( id.clone(), FlowState::Init );
+			} else {
+				Self::set_state( id.clone(), FlowState::Active );
+			}
 
 			// deposit the event
 			Self::deposit_event(
@@ -534,15 +868,37 @@ decl_module! {
 			let state = Self::campaign_state(campaign_id);
 			ensure!( state == FlowState::Active, Error::<T>::NoContributionsAllowed);
 			let campaign = Self::campaign_by_id(&campaign_id);
-			ensure!(<system::Module<T>>::block_number() < campaign.expiry, Error::<T>::CampaignExpired );
+			let now = <system::Module<T>>::block_number();
+			ensure!( now < campaign.expiry, Error::<T>::CampaignExpired );
+
+			// rules
+
+			let rules = Self::campaign_rules(&campaign_id);
+			ensure!( contribution >= T::MinContribution::get(), Error::<T>::ContributionTooSmall );
+			ensure!( contribution >= rules.min_contribution, Error::<T>::ContributionTooSmall );
+			if let Some(max) = rules.max_contribution {
+				let total = Self::campaign_contribution((&campaign_id, &sender)).saturating_add(contribution);
+				ensure!( total <= max, Error::<T>::ContributionTooLarge );
+			}
+			if rules.allowlist {
+				ensure!( Self::allowlisted(&campaign_id, &sender), Error::<T>::NotAllowlisted );
+				ensure!( control::Module::<T>::is_active_member(&campaign.org, &sender), Error::<T>::NotAllowlisted );
+			}
+
+			// traffic
+
+			let (block, contributions) = Self::contributions_in_block(&campaign_id);
+			let contributions = if block == now { contributions } else { 0 };
+			ensure!( (contributions as usize) < T::MaxContributionsPerBlock::get(), Error::<T>::ContributionsPerBlockExceeded );
 
 			// write
 
 			Self::create_contribution(sender.clone(), campaign_id.clone(), contribution.clone())?;
+			ContributionsInBlock::<T>::insert(&campaign_id, (now.clone(), contributions + 1));
+			<T as Config>::SenseHooks::on_action( SenseAction::Contribute, &sender );
 
 			// event
 
-			let now = <system::Module<T>>::block_number();
 			Self::deposit_event(
 				RawEvent::CampaignContributed(
 					campaign_id,
@@ -555,6 +911,149 @@ decl_module! {
 			Ok(())
 		}
 
+		/// allow members of the owning body to contribute
+		/// to a campaign restricted by an allowlist
+		#[weight = 1_000_000]
+		fn allow(
+			origin,
+			campaign_id: T::Hash,
+			accounts: Vec<T::AccountId>,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+			let owner = Self::campaign_owner(campaign_id).ok_or(Error::<T>::OwnerUnknown)?;
+			ensure!( sender == owner, Error::<T>::AuthorizationError );
+			ensure!( Self::campaign_rules(&campaign_id).allowlist, Error::<T>::NoAllowlist );
+
+			let org = Self::campaign_org(&campaign_id);
+			for account in &accounts {
+				ensure!( control::Module::<T>::is_active_member(&org, account), Error::<T>::NotBodyMember );
+			}
+			for account in accounts {
+				CampaignAllowlist::<T>::insert(&campaign_id, &account, true);
+				Self::deposit_event( RawEvent::Allowed(campaign_id.clone(), account) );
+			}
+
+			Ok(())
+		}
+
+		/// remove accounts from the allowlist of a campaign
+		#[weight = 1_000_000]
+		fn disallow(
+			origin,
+			campaign_id: T::Hash,
+			accounts: Vec<T::AccountId>,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+			let owner = Self::campaign_owner(campaign_id).ok_or(Error::<T>::OwnerUnknown)?;
+			ensure!( sender == owner, Error::<T>::AuthorizationError );
+
+			for account in accounts {
+				CampaignAllowlist::<T>::remove(&campaign_id, &account);
+				Self::deposit_event( RawEvent::Disallowed(campaign_id.clone(), account) );
+			}
+
+			Ok(())
+		}
+
+		/// request an extension of the campaign expiry
+		/// applies at the current expiry unless
+		/// contributors holding more than half of the balance veto
+		#[weight = 1_000_000]
+		fn extend(
+			origin,
+			campaign_id: T::Hash,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+			let owner = Self::campaign_owner(campaign_id).ok_or(Error::<T>::OwnerUnknown)?;
+			ensure!( sender == owner, Error::<T>::AuthorizationError );
+
+			let state = Self::campaign_state(&campaign_id);
+			ensure!( state == FlowState::Active, Error::<T>::NoContributionsAllowed );
+			ensure!( !CampaignExtensions::<T>::contains_key(&campaign_id), Error::<T>::ExtensionExists );
+
+			let campaign = Self::campaign_by_id(&campaign_id);
+			ensure!( <system::Module<T>>::block_number() < campaign.expiry, Error::<T>::CampaignExpired );
+			ensure!( expiry > campaign.expiry, Error::<T>::EndTooEarly );
+			ensure!( expiry <= campaign.start + T::MaxDuration::get(), Error::<T>::EndTooLate );
+			ensure!( Self::campaigns_by_block(expiry).len() < T::MaxCampaignsPerBlock::get(), Error::<T>::CampaignsPerBlockExceeded );
+			// milestones must stay after the expiry
+			if let Some(first) = Self::campaign_milestones(&campaign_id).first() {
+				ensure!( expiry < first.deadline, Error::<T>::EndTooLate );
+			}
+
+			let extension = Extension {
+				expiry: expiry.clone(),
+				vetoed: T::Balance::zero(),
+			};
+			CampaignExtensions::<T>::insert(&campaign_id, extension);
+
+			Self::deposit_event( RawEvent::ExtensionRequested(campaign_id, expiry) );
+			Ok(())
+		}
+
+		/// veto a requested extension with the own contribution
+		#[weight = 1_000_000]
+		fn veto_extension(
+			origin,
+			campaign_id: T::Hash,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+			let mut extension = Self::campaign_extension(&campaign_id).ok_or(Error::<T>::ExtensionUnknown)?;
+
+			let campaign = Self::campaign_by_id(&campaign_id);
+			ensure!( <system::Module<T>>::block_number() < campaign.expiry, Error::<T>::CampaignExpired );
+			ensure!( campaign.expiry < extension.expiry, Error::<T>::ExtensionUnknown );
+
+			let contribution = Self::campaign_contribution((&campaign_id, &sender));
+			ensure!( !contribution.is_zero(), Error::<T>::NoContribution );
+			ensure!( !Self::vetoed((&campaign_id, &sender)), Error::<T>::AlreadyVetoed );
+
+			extension.vetoed = extension.vetoed.saturating_add(contribution);
+			ExtensionVetoes::<T>::insert((&campaign_id, &sender), true);
+			CampaignExtensions::<T>::insert(&campaign_id, extension);
+
+			Self::deposit_event( RawEvent::ExtensionVetoed(campaign_id, sender, contribution) );
+			Ok(())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			match Self::storage_version() {
+				Releases::V0 => Self::migrate_to_v1(),
+				_ => 0,
+			}
+		}
+
+		/// start campaigns scheduled for the current block,
+		/// mint rewards of successful campaigns,
+		/// refund contributors of failed milestones,
+		/// refund and clean up contributors of removed campaigns
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+
+			let campaign_hashes = Self::campaigns_by_start(&block_number);
+			for campaign_id in &campaign_hashes {
+				if Self::campaign_state(campaign_id) != FlowState::Init { continue }
+				Self::set_state(campaign_id.clone(), FlowState::Active);
+				Self::deposit_event(
+					RawEvent::CampaignUpdated(
+						campaign_id.clone(),
+						FlowState::Active,
+						block_number.clone()
+					)
+				);
+			}
+			CampaignsByStart::<T>::remove(&block_number);
+
+			(campaign_hashes.len() as Weight).saturating_add(1).saturating_mul(50_000_000)
+				.saturating_add(Self::mint_rewards())
+				.saturating_add(Self::refund_milestones())
+				.saturating_add(Self::clean_up_campaigns())
+		}
+
 		/// finalize campaigns ending in current block
 		fn on_finalize() {
 
@@ -566,14 +1065,30 @@ decl_module! {
 			// iterate over campaigns ending in this block
 			for campaign_id in &campaign_hashes {
 
+				// settled campaigns are skipped
+				let state = Self::campaign_state(campaign_id);
+				if !state.is_open() { continue }
+
 				// get campaign struct
-				let campaign = Self::campaign_by_id(campaign_id);
+				let mut campaign = Self::campaign_by_id(campaign_id);
 				let campaign_balance = Self::campaign_balance(campaign_id);
+
+				// apply a requested extension unless vetoed by the majority
+				if let Some(extension) = Self::campaign_extension(campaign_id) {
+					let vetoed = extension.vetoed.saturating_add(extension.vetoed) > campaign_balance;
+					if extension.expiry > block_number && !vetoed {
+						campaign.expiry = extension.expiry.clone();
+						Campaigns::<T>::insert(campaign_id, campaign);
+						CampaignsByBlock::<T>::mutate( &extension.expiry, |campaigns| campaigns.push(campaign_id.clone()) );
+						Self::deposit_event( RawEvent::CampaignExtended(campaign_id.clone(), extension.expiry) );
+						continue;
+					}
+				}
 				let dao = Self::campaign_org(&campaign_id);
 				let dao_treasury = control::Module::<T>::body_treasury(dao);
 
-				// check for cap reached
-				if campaign_balance >= campaign.cap {
+				// check for cap reached, locked campaigns fail
+				if campaign_balance >= campaign.cap && state != FlowState::Locked {
 
 					// get campaign owner
 					// should be controller --- test?
@@ -636,6 +1151,11 @@ decl_module! {
 									campaign_balance.clone()
 								);
 
+								// contributor perks, minted over the next blocks
+								if !Self::reward_tiers(campaign_id).is_empty() {
+									RewardQueue::<T>::mutate( |queue| queue.push(( campaign_id.clone(), 0 )) );
+								}
+
 								//
 								//
 
@@ -678,6 +1198,7 @@ decl_module! {
 								// }
 
 								Self::set_state(campaign.id.clone(), FlowState::Success);
+								Self::close(&campaign.owner);
 
 								// finalized event
 								Self::deposit_event(
@@ -707,6 +1228,7 @@ decl_module! {
 
 					// update campaign state to failed
 					Self::set_state(campaign.id,FlowState::Failed);
+					Self::close(&campaign.owner);
 
 					// unreserve DEPOSIT
 
@@ -726,6 +1248,18 @@ decl_module! {
 				}
 			}
 
+			// fail milestones not released by their deadline
+			for (campaign_id, index) in Self::milestones_by_block(block_number) {
+				if Self::campaign_state(&campaign_id) != FlowState::Success { continue }
+				if Self::current_milestone(&campaign_id) != index { continue }
+				match Self::milestone_state((&campaign_id, index)) {
+					MilestoneState::Pending | MilestoneState::Voting => {
+						let _ = Self::fail_milestone(&campaign_id);
+					},
+					_ => {}
+				}
+			}
+
 		}
 
 	}
@@ -739,12 +1273,12 @@ impl<T: Config> Module<T> {
 
 		// remove
 		let mut current_state_members = Self::campaigns_by_state( &current_state );
-		match current_state_members.binary_search(&id) {
-			Ok(index) => {
+		match current_state_members.iter().position(|c| c == &id) {
+			Some(index) => {
 				current_state_members.remove(index);
 				CampaignsByState::<T>::insert( &current_state, current_state_members );
 			},
-			Err(_) => () //(Error::<T>::IdUnknown)
+			None => () //(Error::<T>::IdUnknown)
 		}
 
 		// add
@@ -808,7 +1342,7 @@ impl<T: Config> Module<T> {
 
 		// update owned campaigns for dao
 		CampaignsOwnedArray::<T>::mutate( &campaign.org, |campaigns| campaigns.push(campaign.id) );
-		CampaignsOwnedCount::<T>::insert(&campaign.org, update_campaigns_count);
+		CampaignsOwnedCount::<T>::insert(&campaign.org, update_campaigns_owned_count);
 		// ???
 		CampaignsOwnedIndex::<T>::insert((&campaign.org, &campaign.id), campaigns_owned_count);
 
@@ -826,6 +1360,505 @@ impl<T: Config> Module<T> {
 		Ok(())
 	}
 
+	// campaigns get a start block, legacy campaigns started at creation
+	// (unknown, zero), and open campaigns are counted per owner
+	fn migrate_to_v1() -> Weight {
+		let mut count: Weight = 0;
+		Campaigns::<T>::translate::<OldCampaign<T::Hash, T::AccountId, T::Balance, T::BlockNumber, T::Moment, FlowProtocol, FlowGovernance>, _>(
+			|_, old| {
+				count = count.saturating_add(1);
+				Some(Campaign {
+					id: old.id,
+					org: old.org,
+					name: old.name,
+					owner: old.owner,
+					admin: old.admin,
+					deposit: old.deposit,
+					start: Zero::zero(),
+					expiry: old.expiry,
+					cap: old.cap,
+					protocol: old.protocol,
+					governance: old.governance,
+					cid: old.cid,
+					token_symbol: old.token_symbol,
+					token_name: old.token_name,
+					created: old.created,
+				})
+			}
+		);
+
+		CampaignsOpenByOwner::<T>::remove_all();
+		for (id, campaign) in Campaigns::<T>::iter() {
+			let state = Self::campaign_state(&id);
+			if state == FlowState::Init || state == FlowState::Active || state == FlowState::Paused {
+				CampaignsOpenByOwner::<T>::mutate( &campaign.owner, |open| *open = open.saturating_add(1) );
+			}
+		}
+
+		StorageVersion::put(Releases::V1);
+		T::DbWeight::get().reads_writes(
+			count.saturating_mul(3).saturating_add(1),
+			count.saturating_mul(2).saturating_add(1)
+		)
+	}
+
+	/// storage invariants, checked against state snapshots after migrations
+	#[cfg(feature = "std")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for (id, campaign) in Campaigns::<T>::iter() {
+			let contributors = Self::campaign_contributors(&id);
+			if Self::campaign_contributors_count(&id) != contributors.len() as u64 {
+				return Err("contributor count does not match contributor list")
+			}
+			let state = Self::campaign_state(&id);
+			// settled campaigns have paid out their balance
+			if state == FlowState::Success || state == FlowState::Failed { continue }
+			let contributed = contributors.iter().fold(
+				T::Balance::zero(),
+				|sum, account| sum.saturating_add(Self::campaign_contribution(( id.clone(), account.clone() )))
+			);
+			if Self::campaign_balance(&id) != contributed {
+				return Err("campaign balance does not match contributions")
+			}
+			if Self::campaign_org(&id) != campaign.org {
+				return Err("campaign org mismatch")
+			}
+		}
+		if Self::storage_version() != Releases::V1 {
+			return Err("storage version not migrated")
+		}
+		Ok(())
+	}
+
+	// release the open campaign slot of the creator
+	fn close( owner: &T::AccountId ) {
+		CampaignsOpenByOwner::<T>::mutate( owner, |open| *open = open.saturating_sub(1) );
+	}
+
+	fn validate_reward_tiers(
+		org: &T::Hash,
+		tiers: &[RewardTier<T::Balance>],
+	) -> DispatchResult {
+
+		if tiers.is_empty() { return Ok(()) }
+		ensure!( tiers.len() <= T::MaxRewardTiers::get(), Error::<T>::TooManyRewardTiers );
+
+		let realm = tangram::Module::<T>::realm_for_owner(org).ok_or(Error::<T>::RealmUnknown)?;
+		let classes = tangram::Module::<T>::next_class_index(&realm);
+
+		let mut previous: Option<T::Balance> = None;
+		for tier in tiers {
+			ensure!( tier.class < classes, Error::<T>::ClassUnknown );
+			ensure!( tier.max_supply > 0, Error::<T>::RewardTierInvalid );
+			if let Some(min) = previous {
+				ensure!( tier.min_contribution > min, Error::<T>::RewardTierInvalid );
+			}
+			previous = Some(tier.min_contribution);
+		}
+
+		Ok(())
+	}
+
+	fn insert_reward_tiers(
+		campaign_id: &T::Hash,
+		org: &T::Hash,
+		tiers: Vec<RewardTier<T::Balance>>,
+	) {
+		if tiers.is_empty() { return }
+		if let Some(realm) = tangram::Module::<T>::realm_for_owner(org) {
+			CampaignRealm::<T>::insert( campaign_id, realm );
+		}
+		CampaignRewardTiers::<T>::insert( campaign_id, tiers );
+	}
+
+	// mint the highest available tier for each contributor,
+	// exhausted tiers fall back to the next lower one
+	// reward at most `MaxRewardsPerBlock` contributors of queued campaigns,
+	// the rest is carried over to the next block
+	fn mint_rewards() -> Weight {
+
+		let mut queue = Self::reward_queue();
+		if queue.is_empty() { return T::DbWeight::get().reads(1) }
+
+		let mut budget = T::MaxRewardsPerBlock::get();
+		let mut scanned: Weight = 0;
+		let mut rewarded: Weight = 0;
+
+		while budget > 0 && !queue.is_empty() {
+			let (campaign_id, next) = queue[0];
+			let tiers = Self::reward_tiers(&campaign_id);
+			let realm = Self::campaign_realm(&campaign_id);
+			let contributors = Self::campaign_contributors(&campaign_id);
+			scanned += 1;
+
+			let start = (next as usize).min(contributors.len());
+			let end = start.saturating_add(budget as usize).min(contributors.len());
+			for contributor in &contributors[start..end] {
+				Self::mint_reward( &campaign_id, realm, &tiers, contributor );
+			}
+			budget -= (end - start) as u32;
+			rewarded += (end - start) as Weight;
+
+			if end == contributors.len() {
+				queue.remove(0);
+			} else {
+				queue[0].1 = end as u32;
+			}
+		}
+		RewardQueue::<T>::put(queue);
+
+		T::DbWeight::get().reads_writes(
+			scanned.saturating_mul(3).saturating_add(rewarded.saturating_mul(8)).saturating_add(1),
+			rewarded.saturating_mul(6).saturating_add(1)
+		)
+	}
+
+	// mint the highest tier a contributor qualifies for with supply left
+	fn mint_reward(
+		campaign_id: &T::Hash,
+		realm: RealmIndex,
+		tiers: &[RewardTier<T::Balance>],
+		contributor: &T::AccountId,
+	) {
+		let contribution = Self::campaign_contribution((campaign_id, contributor));
+		for (index, tier) in tiers.iter().enumerate().rev() {
+			if contribution < tier.min_contribution { continue }
+			let index = index as u32;
+			let minted = Self::reward_tier_minted((campaign_id, index));
+			if minted >= tier.max_supply { continue }
+			let item = tangram::Module::<T>::mint_item(
+				realm,
+				tier.class,
+				tier.name.clone(),
+				tier.cid.clone(),
+				contributor
+			);
+			if let Ok(item) = item {
+				RewardTierMinted::<T>::insert( (campaign_id, index), minted + 1 );
+				Self::deposit_event( RawEvent::RewardMinted(campaign_id.clone(), contributor.clone(), index, item) );
+				break;
+			}
+		}
+	}
+
+	fn validate_milestones(
+		milestones: &[Milestone<T::Balance, T::BlockNumber>],
+		target: T::Balance,
+		expiry: T::BlockNumber,
+	) -> DispatchResult {
+
+		ensure!( milestones.len() <= T::MaxMilestones::get(), Error::<T>::TooManyMilestones );
+
+		let mut total = T::Balance::zero();
+		let mut previous = expiry;
+		for milestone in milestones {
+			ensure!( !milestone.amount.is_zero(), Error::<T>::MilestoneInvalid );
+			ensure!( milestone.deadline > previous, Error::<T>::MilestoneInvalid );
+			ensure!(
+				Self::milestones_by_block(&milestone.deadline).len() < T::MaxMilestonesPerBlock::get(),
+				Error::<T>::MilestonesPerBlockExceeded
+			);
+			total = total.checked_add(&milestone.amount).ok_or(Error::<T>::MilestonesExceedTarget)?;
+			previous = milestone.deadline;
+		}
+		ensure!( total <= target, Error::<T>::MilestonesExceedTarget );
+
+		Ok(())
+	}
+
+	fn insert_milestones(
+		campaign_id: &T::Hash,
+		milestones: Vec<Milestone<T::Balance, T::BlockNumber>>,
+	) {
+		if milestones.is_empty() { return }
+		for (index, milestone) in milestones.iter().enumerate() {
+			MilestonesByBlock::<T>::mutate( &milestone.deadline, |m| m.push((campaign_id.clone(), index as u32)) );
+		}
+		CampaignMilestones::<T>::insert( campaign_id, milestones );
+	}
+
+	/// submit the next milestone of a successful campaign for a contributor vote
+	pub fn submit_milestone( campaign_id: &T::Hash ) -> Result<u32, DispatchError> {
+
+		ensure!( Self::campaign_state(campaign_id) == FlowState::Success, Error::<T>::UnsuccessfulCampaign );
+		let milestones = Self::campaign_milestones(campaign_id);
+		let index = Self::current_milestone(campaign_id);
+		let milestone = milestones.get(index as usize).ok_or(Error::<T>::MilestoneUnknown)?;
+		ensure!( Self::milestone_state((campaign_id, index)) == MilestoneState::Pending, Error::<T>::MilestoneNotPending );
+		ensure!( <system::Module<T>>::block_number() < milestone.deadline, Error::<T>::MilestoneExpired );
+
+		CampaignMilestoneState::<T>::insert( (campaign_id, index), MilestoneState::Voting );
+		Self::deposit_event( RawEvent::MilestoneSubmitted(campaign_id.clone(), index) );
+
+		Ok(index)
+	}
+
+	/// resolve the contributor vote on the current milestone
+	/// approved releases the tranche, rejected refunds the contributors,
+	/// without a decision the milestone can be submitted again
+	pub fn resolve_milestone( campaign_id: &T::Hash, approved: Option<bool> ) -> DispatchResult {
+
+		let index = Self::current_milestone(campaign_id);
+		ensure!( Self::milestone_state((campaign_id, index)) == MilestoneState::Voting, Error::<T>::MilestoneNotVoting );
+
+		match approved {
+			Some(true) => Self::release_milestone(campaign_id),
+			Some(false) => Self::fail_milestone(campaign_id),
+			None => {
+				CampaignMilestoneState::<T>::insert( (campaign_id, index), MilestoneState::Pending );
+				Self::deposit_event( RawEvent::MilestoneReopened(campaign_id.clone(), index) );
+				Ok(())
+			}
+		}
+	}
+
+	// unlock the tranche in the body treasury,
+	// the final tranche includes everything raised above the milestones
+	fn release_milestone( campaign_id: &T::Hash ) -> DispatchResult {
+
+		let milestones = Self::campaign_milestones(campaign_id);
+		let index = Self::current_milestone(campaign_id);
+		let milestone = milestones.get(index as usize).ok_or(Error::<T>::MilestoneUnknown)?;
+
+		let released = Self::released_balance(campaign_id);
+		let remaining = Self::campaign_balance(campaign_id).saturating_sub(released);
+		let amount = if index as usize + 1 == milestones.len() { remaining } else { milestone.amount.min(remaining) };
+
+		// the tranche counts against the spend limit of the body,
+		// over the limit the milestone is reopened and can be submitted again
+		let org = Self::campaign_org(campaign_id);
+		if control::Module::<T>::spend( &org, amount ).is_err() {
+			CampaignMilestoneState::<T>::insert( (campaign_id, index), MilestoneState::Pending );
+			Self::deposit_event( RawEvent::MilestoneReopened(campaign_id.clone(), index) );
+			return Ok(())
+		}
+
+		let treasury = control::Module::<T>::body_treasury(&org);
+		let _ = <balances::Module<T>>::unreserve( &treasury, amount );
+
+		CampaignBalanceReleased::<T>::insert( campaign_id, released.saturating_add(amount) );
+		CampaignMilestoneState::<T>::insert( (campaign_id, index), MilestoneState::Released );
+		CampaignMilestoneIndex::<T>::insert( campaign_id, index.saturating_add(1) );
+
+		Self::deposit_event( RawEvent::MilestoneReleased(campaign_id.clone(), index, amount) );
+		Ok(())
+	}
+
+	// fail the current milestone, the remaining balance is refunded
+	// pro rata to contributions over the next blocks
+	fn fail_milestone( campaign_id: &T::Hash ) -> DispatchResult {
+
+		let index = Self::current_milestone(campaign_id);
+		let total = Self::campaign_balance(campaign_id);
+		let remaining = total.saturating_sub( Self::released_balance(campaign_id) );
+
+		if !remaining.is_zero() {
+			MilestoneRefunds::<T>::mutate( |queue| queue.push(( campaign_id.clone(), remaining, 0 )) );
+		}
+
+		CampaignBalanceReleased::<T>::insert( campaign_id, total );
+		CampaignMilestoneState::<T>::insert( (campaign_id, index), MilestoneState::Failed );
+
+		Self::deposit_event( RawEvent::MilestoneFailed(campaign_id.clone(), index, remaining) );
+		Ok(())
+	}
+
+	// refund at most `MaxCleanupPerBlock` contributors of failed milestones,
+	// the rest is carried over to the next block
+	fn refund_milestones() -> Weight {
+
+		let mut queue = Self::milestone_refunds();
+		if queue.is_empty() { return T::DbWeight::get().reads(1) }
+
+		let mut budget = T::MaxCleanupPerBlock::get();
+		let mut scanned: Weight = 0;
+		let mut refunded: Weight = 0;
+
+		while budget > 0 && !queue.is_empty() {
+			let (campaign_id, remaining, next) = queue[0];
+			let total = Self::campaign_balance(&campaign_id);
+			let treasury = control::Module::<T>::body_treasury( Self::campaign_org(&campaign_id) );
+			let contributors = Self::campaign_contributors(&campaign_id);
+			scanned += 1;
+
+			let start = (next as usize).min(contributors.len());
+			let end = start.saturating_add(budget as usize).min(contributors.len());
+			for contributor in &contributors[start..end] {
+				let contribution = Self::campaign_contribution((&campaign_id, contributor));
+				let share = multiply_by_rational(
+					remaining.saturated_into::<u128>(),
+					contribution.saturated_into::<u128>(),
+					total.saturated_into::<u128>()
+				).unwrap_or(0).saturated_into::<T::Balance>();
+				if share.is_zero() { continue }
+				let _ = <balances::Module<T>>::unreserve( &treasury, share );
+				let _ = <balances::Module<T> as Currency<_>>::transfer(
+					&treasury,
+					contributor,
+					share,
+					ExistenceRequirement::AllowDeath
+				);
+			}
+			budget -= (end - start) as u32;
+			refunded += (end - start) as Weight;
+
+			if end == contributors.len() {
+				queue.remove(0);
+			} else {
+				queue[0].2 = end as u32;
+			}
+		}
+		MilestoneRefunds::<T>::put(queue);
+
+		T::DbWeight::get().reads_writes(
+			scanned.saturating_mul(4).saturating_add(refunded.saturating_mul(4)).saturating_add(1),
+			refunded.saturating_mul(3).saturating_add(1)
+		)
+	}
+
+	// refund an unsettled campaign and remove it from all indexes,
+	// contributors are refunded and cleaned up over the next blocks
+	fn remove_campaign( id: &T::Hash ) {
+
+		let campaign = Self::campaign_by_id(id);
+		let state = Self::campaign_state(id);
+		let org = campaign.org.clone();
+
+		//	R E F U N D
+
+		// failed campaigns were refunded on finalization
+		let refund = state != FlowState::Failed;
+		if refund {
+			let treasury = control::Module::<T>::body_treasury(&org);
+			let _ = <balances::Module<T>>::unreserve(&treasury, campaign.deposit.clone());
+		}
+		if state.is_open() {
+			Self::close(&campaign.owner);
+		}
+
+		//	C O N T R I B U T I O N S
+
+		if Self::campaign_contributors(id).is_empty() {
+			CampaignContributors::<T>::remove(id);
+			CampaignContributorsCount::<T>::remove(id);
+		} else {
+			CleanupQueue::<T>::mutate( |queue| queue.push(( id.clone(), refund )) );
+		}
+		CampaignAllowlist::<T>::remove_prefix(id);
+		ContributionsInBlock::<T>::remove(id);
+		CampaignBalance::<T>::remove(id);
+
+		//	C A M P A I G N S
+
+		let index = CampaignsIndex::<T>::take(id);
+		let last = Self::campaigns_count().saturating_sub(1);
+		if index != last {
+			let moved = CampaignsArray::<T>::get(last);
+			CampaignsArray::<T>::insert(index, moved.clone());
+			CampaignsIndex::<T>::insert(moved, index);
+		}
+		CampaignsArray::<T>::remove(last);
+		CampaignsCount::put(last);
+
+		CampaignsOwnedArray::<T>::mutate( &org, |campaigns| campaigns.retain(|c| c != id) );
+		CampaignsOwnedCount::<T>::mutate( &org, |count| *count = count.saturating_sub(1) );
+		CampaignsOwnedIndex::<T>::remove((&org, id));
+		CampaignsByBody::<T>::mutate( &org, |campaigns| campaigns.retain(|c| c != id) );
+
+		CampaignsByState::<T>::mutate( &state, |campaigns| campaigns.retain(|c| c != id) );
+		CampaignsByBlock::<T>::mutate( &campaign.expiry, |campaigns| campaigns.retain(|c| c != id) );
+		CampaignsByStart::<T>::mutate( &campaign.start, |campaigns| campaigns.retain(|c| c != id) );
+		if let Some(extension) = Self::campaign_extension(id) {
+			CampaignsByBlock::<T>::mutate( &extension.expiry, |campaigns| campaigns.retain(|c| c != id) );
+		}
+
+		//	M I L E S T O N E S  +  R E W A R D S
+
+		for (index, milestone) in Self::campaign_milestones(id).iter().enumerate() {
+			CampaignMilestoneState::<T>::remove((id, index as u32));
+			MilestonesByBlock::<T>::mutate( &milestone.deadline, |m| m.retain(|(c, _)| c != id) );
+		}
+		for index in 0..Self::reward_tiers(id).len() {
+			RewardTierMinted::<T>::remove((id, index as u32));
+		}
+		CampaignMilestones::<T>::remove(id);
+		CampaignMilestoneIndex::<T>::remove(id);
+		CampaignBalanceReleased::<T>::remove(id);
+		CampaignRewardTiers::<T>::remove(id);
+		CampaignRealm::<T>::remove(id);
+
+		CampaignRules::<T>::remove(id);
+		CampaignExtensions::<T>::remove(id);
+		CampaignState::<T>::remove(id);
+		CampaignAdmin::<T>::remove(id);
+		CampaignOwner::<T>::remove(id);
+		CampaignOrg::<T>::remove(id);
+		Campaigns::<T>::remove(id);
+
+	}
+
+	// refund and clean up at most `MaxCleanupPerBlock` contributors
+	// of removed campaigns, the rest is carried over to the next block
+	fn clean_up_campaigns() -> Weight {
+
+		let mut queue = Self::cleanup_queue();
+		if queue.is_empty() { return T::DbWeight::get().reads(1) }
+
+		let mut budget = T::MaxCleanupPerBlock::get();
+		let mut scanned: Weight = 0;
+		let mut cleaned: Weight = 0;
+
+		while budget > 0 && !queue.is_empty() {
+			let (id, refund) = queue[0];
+			let mut contributors = CampaignContributors::<T>::take(&id);
+			scanned += 1;
+			while budget > 0 {
+				match contributors.pop() {
+					Some(account) => {
+						Self::remove_contributor( &id, &account, refund );
+						budget -= 1;
+						cleaned += 1;
+					},
+					None => break,
+				}
+			}
+			if contributors.is_empty() {
+				CampaignContributorsCount::<T>::remove(&id);
+				queue.remove(0);
+			} else {
+				CampaignContributors::<T>::insert( &id, contributors );
+			}
+		}
+		CleanupQueue::<T>::put(queue);
+
+		T::DbWeight::get().reads_writes(
+			scanned.saturating_add(cleaned.saturating_mul(5)).saturating_add(1),
+			scanned.saturating_mul(2).saturating_add(cleaned.saturating_mul(7)).saturating_add(1)
+		)
+	}
+
+	// refund a contributor of a removed campaign and drop its records
+	fn remove_contributor( id: &T::Hash, account: &T::AccountId, refund: bool ) {
+
+		let contribution = CampaignContribution::<T>::take((id, account));
+		if refund {
+			let _ = <balances::Module<T>>::unreserve(account, contribution);
+		}
+		ExtensionVetoes::<T>::remove((id, account));
+		CampaignsContributed::<T>::mutate( account, |campaigns| campaigns.retain(|c| c != id) );
+
+		// swap the last contributed campaign into the gap
+		let index = CampaignsContributedIndex::<T>::take((account, id));
+		let last = Self::campaigns_contributed_count(account).saturating_sub(1);
+		if index != last {
+			let moved = CampaignsContributedArray::<T>::get((account, last));
+			CampaignsContributedArray::<T>::insert((account, index), moved.clone());
+			CampaignsContributedIndex::<T>::insert((account, moved), index);
+		}
+		CampaignsContributedArray::<T>::remove((account, last));
+		CampaignsContributedCount::<T>::insert(account, last);
+	}
+
 	fn create_contribution(
 		sender: T::AccountId,
 		campaign_id: T::Hash,
@@ -920,7 +1953,7 @@ decl_error! {
 		EndTooEarly,
 		/// Campaign expiry has be lower than the block number limit
 		EndTooLate,
-		/// Max contributions per block exceeded
+		/// Max contributions to the campaign per block exceeded
 		ContributionsPerBlockExceeded,
 		/// Name too long
 		NameTooLong,
@@ -957,6 +1990,68 @@ decl_error! {
 		/// Id Unknown
 		IdUnknown,
 		/// Transfer Error
-		TransferError
+		TransferError,
+		/// Transition not allowed for the state or role
+		InvalidTransition,
+		/// Campaign is settled
+		CampaignSettled,
+
+		//
+		//	rules
+		//
+		/// Deposit below the minimum creator deposit
+		DepositTooLow,
+		/// Maximum contribution below the minimum contribution
+		ContributionRulesInvalid,
+		/// Max open campaigns per address exceeded
+		CampaignsPerAddressExceeded,
+		/// Max campaigns per block exceeded
+		CampaignsPerBlockExceeded,
+		/// Contribution exceeds the maximum per account
+		ContributionTooLarge,
+		/// Account is not allowed to contribute
+		NotAllowlisted,
+		/// Campaign has no allowlist
+		NoAllowlist,
+		/// Account is not a member of the body
+		NotBodyMember,
+		/// Campaign was extended before
+		ExtensionExists,
+		/// No pending extension
+		ExtensionUnknown,
+		/// Already vetoed
+		AlreadyVetoed,
+
+		//
+		//	rewards
+		//
+		/// Too many reward tiers
+		TooManyRewardTiers,
+		/// Reward tiers need a supply and ascending contributions
+		RewardTierInvalid,
+		/// Body has no realm
+		RealmUnknown,
+		/// Class unknown in the realm of the body
+		ClassUnknown,
+
+		//
+		//	milestones
+		//
+		/// Too many milestones
+		TooManyMilestones,
+		/// Too many milestone deadlines in one block
+		MilestonesPerBlockExceeded,
+		/// Milestones need an amount and ascending deadlines after expiry
+		MilestoneInvalid,
+		/// Milestones exceed the campaign target
+		MilestonesExceedTarget,
+		/// Milestone unknown
+		MilestoneUnknown,
+		/// Milestone is not pending
+		MilestoneNotPending,
+		/// Milestone is not up for a vote
+		MilestoneNotVoting,
+		/// Milestone deadline passed
+		MilestoneExpired,
 	}
 }
@@ -0,0 +1,280 @@
+// mock runtime for the flow tests
+
+#![cfg(test)]
+
+use crate as flow;
+use crate::{ CampaignTerms, ContributionRules, FlowGovernance, FlowProtocol };
+use control::{ ControlAccessModel, ControlFeeModel, ControlType };
+use frame_support::{ parameter_types, traits::{ OnFinalize, OnInitialize, Randomness } };
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{ testing::Header, traits::{ BlakeTwo256, Hash, IdentityLookup }, Permill };
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Timestamp: timestamp::{Module, Call, Storage, Inherent},
+		Tangram: tangram::{Module, Call, Storage, Event<T>, Config<T>},
+		Control: control::{Module, Call, Storage, Event<T>, Config<T>},
+		Flow: flow::{Module, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+// sub accounts of the control pallet need more than 8 bytes
+pub const ALICE: u128 = 1;
+pub const BOB: u128 = 2;
+pub const CHARLIE: u128 = 3;
+pub const DAVE: u128 = 4;
+pub const ADMIN: u128 = 10;
+pub const GAMEDAO: u128 = 100;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u128;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 50;
+}
+impl balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = MaxLocks;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+thread_local! {
+	static NONCE: RefCell<u64> = const { RefCell::new(0) };
+}
+
+// every call returns a fresh value
+pub struct MockRandomness;
+impl Randomness<H256> for MockRandomness {
+	fn random(subject: &[u8]) -> H256 {
+		let nonce = NONCE.with(|n| { *n.borrow_mut() += 1; *n.borrow() });
+		BlakeTwo256::hash_of(&(subject, nonce))
+	}
+}
+
+parameter_types! {
+	pub const CreateRealmDeposit: u64 = 0;
+	pub const CreateClassDeposit: u64 = 0;
+	pub const CreateItemDeposit: u64 = 0;
+	pub const MaxRealmsPerOrg: u64 = 1;
+	pub const MaxClassesPerRealm: u64 = 16;
+	pub const MaxTokenPerClass: u128 = 1_000;
+	pub const MaxTotalToken: u128 = 1_000_000;
+	pub const MaxRedeemables: u32 = 4;
+	pub const MaxRedeemablesPerAccount: u32 = 2;
+	pub const MaxIngredients: u32 = 4;
+	pub const MaxRoyalty: Permill = Permill::from_percent(20);
+	pub const MaxOffers: u32 = 2;
+	pub const MaxItemChildren: u32 = 2;
+	pub const MaxItemDepth: u32 = 2;
+	pub const MaxTreeSize: u32 = 4;
+	pub const MaxRentalDuration: u64 = 100;
+	pub const MaxRentalExpiries: u32 = 2;
+	pub const MaxRentalsPerBlock: u32 = 3;
+}
+impl tangram::Config for Test {
+	type Time = Timestamp;
+	type Randomness = MockRandomness;
+	type Currency = Balances;
+	type Event = Event;
+	type CreateRealmDeposit = CreateRealmDeposit;
+	type CreateClassDeposit = CreateClassDeposit;
+	type CreateItemDeposit = CreateItemDeposit;
+	type MaxRealmsPerOrg = MaxRealmsPerOrg;
+	type MaxClassesPerRealm = MaxClassesPerRealm;
+	type MaxTokenPerClass = MaxTokenPerClass;
+	type MaxTotalToken = MaxTotalToken;
+	type NextRealmIndex = ();
+	type NextClassIndex = ();
+	type NextItemIndex = ();
+	type TotalIndex = ();
+	type BurnedIndex = ();
+	type SenseHooks = ();
+	type Control = Control;
+	type MaxRedeemables = MaxRedeemables;
+	type MaxRedeemablesPerAccount = MaxRedeemablesPerAccount;
+	type MaxIngredients = MaxIngredients;
+	type MaxRoyalty = MaxRoyalty;
+	type MaxOffers = MaxOffers;
+	type MaxItemChildren = MaxItemChildren;
+	type MaxItemDepth = MaxItemDepth;
+	type MaxTreeSize = MaxTreeSize;
+	type MaxRentalDuration = MaxRentalDuration;
+	type MaxRentalExpiries = MaxRentalExpiries;
+	type MaxRentalsPerBlock = MaxRentalsPerBlock;
+}
+
+parameter_types! {
+	pub const GameDAOTreasury: u128 = GAMEDAO;
+	pub const CreationFee: u64 = 10;
+	pub const MaxBodiesPerAccount: usize = 8;
+	pub const MaxMembersPerBody: usize = 8;
+	pub const MaxCreationsPerBlock: usize = 4;
+	pub const MaxChildren: usize = 2;
+	pub const MaxDepth: u32 = 2;
+	pub const MinTrustToCreate: u64 = 0;
+}
+impl control::Config for Test {
+	type GameDAOTreasury = GameDAOTreasury;
+	type ForceOrigin = EnsureRoot<u128>;
+	type Currency = Balances;
+	type CreationFee = CreationFee;
+	type MaxBodiesPerAccount = MaxBodiesPerAccount;
+	type MaxMembersPerBody = MaxMembersPerBody;
+	type MaxCreationsPerBlock = MaxCreationsPerBlock;
+	type MaxChildren = MaxChildren;
+	type MaxDepth = MaxDepth;
+	type Sense = ();
+	type MinTrustToCreate = MinTrustToCreate;
+	type SenseHooks = ();
+	type Event = Event;
+	type Randomness = MockRandomness;
+}
+
+parameter_types! {
+	pub const Nonce: u64 = 0;
+	pub const MinLength: usize = 2;
+	pub const MaxLength: usize = 32;
+	pub const MaxCampaignsPerAddress: usize = 2;
+	pub const MaxCampaignsPerBlock: usize = 2;
+	pub const MaxContributionsPerBlock: usize = 3;
+	pub const MinDuration: u64 = 5;
+	pub const MaxDuration: u64 = 100;
+	pub const MinCreatorDeposit: u64 = 10;
+	pub const MinContribution: u64 = 5;
+	pub const CampaignFee: u64 = 0;
+	pub const MaxMilestones: usize = 3;
+	pub const MaxMilestonesPerBlock: usize = 2;
+	pub const MaxRewardTiers: usize = 2;
+	pub const MaxRewardsPerBlock: u32 = 2;
+	pub const MaxCleanupPerBlock: u32 = 2;
+}
+impl flow::Config for Test {
+	type GameDAOAdminOrigin = EnsureRoot<u128>;
+	type GameDAOTreasury = GameDAOTreasury;
+	type Currency = Balances;
+	type Event = Event;
+	type Nonce = Nonce;
+	type Randomness = MockRandomness;
+	type MinLength = MinLength;
+	type MaxLength = MaxLength;
+	type MaxCampaignsPerAddress = MaxCampaignsPerAddress;
+	type MaxCampaignsPerBlock = MaxCampaignsPerBlock;
+	type MaxContributionsPerBlock = MaxContributionsPerBlock;
+	type SenseHooks = ();
+	type MinDuration = MinDuration;
+	type MaxDuration = MaxDuration;
+	type MinCreatorDeposit = MinCreatorDeposit;
+	type MinContribution = MinContribution;
+	type CampaignFee = CampaignFee;
+	type MaxMilestones = MaxMilestones;
+	type MaxMilestonesPerBlock = MaxMilestonesPerBlock;
+	type MaxRewardTiers = MaxRewardTiers;
+	type MaxRewardsPerBlock = MaxRewardsPerBlock;
+	type MaxCleanupPerBlock = MaxCleanupPerBlock;
+}
+
+// body 0 controlled by alice with members bob and charlie,
+// realm 0 with class 0, 1000 in the treasury
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> {
+		balances: vec![
+			(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000), (DAVE, 1_000), (GAMEDAO, 1),
+			(Control::treasury_account(0), 1_000),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+	control::GenesisConfig::<Test> {
+		bodies: vec![(
+			ALICE, ALICE, b"body".to_vec(), vec![], ControlType::Dao,
+			ControlAccessModel::Open, ControlFeeModel::NoFees, 0, 0, vec![ BOB, CHARLIE ],
+		)],
+	}.assimilate_storage(&mut t).unwrap();
+	flow::GenesisConfig::<Test> {
+		campaigns: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		Flow::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		Flow::on_initialize(System::block_number());
+	}
+}
+
+pub fn org() -> H256 {
+	Control::body_by_nonce(0)
+}
+
+// starts now with the minimum contribution of the runtime
+pub fn terms() -> CampaignTerms<u64, u64> {
+	CampaignTerms {
+		rules: ContributionRules { min_contribution: 5, ..Default::default() },
+		..Default::default()
+	}
+}
+
+// campaign of alice raising 100 over 10 blocks
+pub fn create_campaign(terms: CampaignTerms<u64, u64>) -> H256 {
+	let expiry = System::block_number() + 10;
+	Flow::create(
+		Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, expiry,
+		FlowProtocol::Raise, FlowGovernance::No, vec![], b"PLAY".to_vec(), b"play".to_vec(),
+		terms,
+	).unwrap();
+	*Flow::campaigns_by_body(org()).last().unwrap()
+}
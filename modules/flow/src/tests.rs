@@ -1,126 +1,650 @@
-#[cfg(test)]
-mod tests {
-
-	use super::*;
-
-	use support::{impl_outer_origin, assert_ok, assert_noop};
-	use runtime_io::{with_externalities, TestExternalities};
-	use primitives::{H256, Blake2Hasher};
-	use runtime_primitives::{
-		BuildStorage,
-		traits::{BlakeTwo256, IdentityLookup},
-		testing::{Digest, DigestItem, Header}
-	};
+use super::*;
+use crate::mock::*;
+use frame_support::{
+	assert_noop, assert_ok,
+	migration::put_storage_value,
+	traits::OnRuntimeUpgrade,
+	Blake2_128Concat, StorageHasher,
+};
 
-	impl_outer_origin! {
-		pub enum Origin for CampaignTest {}
-	}
+fn milestone(amount: u64, deadline: u64) -> Milestone<u64, u64> {
+	Milestone { amount, cid: vec![], deadline }
+}
 
-	#[derive(Clone, Eq, PartialEq)]
-	pub struct CampaignTest;
-
-	impl system::Trait for CampaignTest {
-		type Origin = Origin;
-		type Index = u64;
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type Digest = Digest;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type Log = DigestItem;
-	}
+// bob 60 and charlie 40 reach the target, settled at block 11
+fn fund(id: H256) {
+	assert_ok!( Flow::contribute( Origin::signed(BOB), id, 60 ) );
+	assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 40 ) );
+	run_to_block(12);
+	assert_eq!( Flow::campaign_state(id), FlowState::Success );
+}
 
-	impl balances::Trait for CampaignTest {
-		type Balance = u64;
-		type OnFreeBalanceZero = ();
-		type OnNewAccount = ();
-		type Event = ();
-		type TransactionPayment = ();
-		type TransferPayment = ();
-		type DustRemoval = ();
-	}
+//
+//	milestones
+//
 
-	impl super::Trait for CampaignTest {
-		type Event = ();
-	}
+#[test]
+fn milestones_are_validated() {
+	new_test_ext().execute_with(|| {
+		let create = |milestones: Vec<Milestone<u64, u64>>| Flow::create(
+			Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+			FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![],
+			CampaignTerms { milestones, ..terms() },
+		);
+		assert_noop!(
+			create(vec![ milestone(10, 20), milestone(10, 30), milestone(10, 40), milestone(10, 50) ]),
+			Error::<Test>::TooManyMilestones
+		);
+		assert_noop!( create(vec![ milestone(0, 20) ]), Error::<Test>::MilestoneInvalid );
+		assert_noop!( create(vec![ milestone(10, 11) ]), Error::<Test>::MilestoneInvalid );
+		assert_noop!( create(vec![ milestone(10, 30), milestone(10, 20) ]), Error::<Test>::MilestoneInvalid );
+		assert_noop!( create(vec![ milestone(60, 20), milestone(50, 30) ]), Error::<Test>::MilestonesExceedTarget );
+		assert_ok!( create(vec![ milestone(60, 20), milestone(40, 30) ]) );
+	});
+}
 
-	type Campaigns = super::Module<CampaignTest>;
+#[test]
+fn milestones_release_tranches_in_order() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20), milestone(30, 30) ], ..terms() });
+		let treasury = Control::body_treasury(org());
+		assert_noop!( Flow::submit_milestone(&id), Error::<Test>::UnsuccessfulCampaign );
 
-	fn build_ext() -> TestExternalities<Blake2Hasher> {
-		let mut t = system::GenesisConfig::<CampaignTest>::default().build_storage().unwrap().0;
-		t.extend(balances::GenesisConfig::<CampaignTest>::default().build_storage().unwrap().0);
-		t.into()
-	}
+		// raised funds are locked in the treasury
+		fund(id);
+		assert_eq!( Balances::reserved_balance(treasury), 10 + 100 );
+		assert_noop!( Flow::resolve_milestone(&id, Some(true)), Error::<Test>::MilestoneNotVoting );
 
-	#[test]
-	fn create_campaign_should_work() {
-		with_externalities(&mut build_ext(), {
-
-			// create a Campaign with account #5.
-			assert_ok!(
-				Campaigns::create(
-					Origin::signed(5),	// owner == account 5
-					Origin::signed(0),	// admin == account 0
-					vec![12,56],		// name
-					20000,				// cap
-					0,					// deposit
-					1000,				// lifetime
-					1,					// protocol
-					1,					// governance
-					vec![11,111],		// ipfs hash
-				)
-			);
-
-			// check that there are now 1 Campaigns in storage
-			assert_eq!(Campaigns::campaigns_count(), 1);
-
-			// check that account #6 owns 1 Campaign
-			assert_eq!(Campaigns::campaigns_owned_count(5), 1);
-
-			// check that account #0 is admin
-			let admin_hash = Campaigns::campaign_by_id(0);
-			assert_eq!(Campaigns::campaign_admin(admin_hash), 0);
-
-			// check that some random account #3 does not own a Campaign
-			assert_eq!(Campaigns::campaigns_owned_count(3), 0);
-
-			// check that this Campaign is specifically owned by account #6
-			let owner_hash = Campaigns::campaign_by_id(0);
-			assert_eq!(Campaigns::campaign_owner(owner_hash), Some(5));
-
-			let other_hash = Campaigns::campaigns_owned_index(5, 0);
-			assert_eq!(hash, other_hash);
-
-		})
-	}
+		assert_eq!( Flow::submit_milestone(&id), Ok(0) );
+		assert_noop!( Flow::submit_milestone(&id), Error::<Test>::MilestoneNotPending );
+		assert_ok!( Flow::resolve_milestone(&id, Some(true)) );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Released );
+		assert_eq!( Flow::released_balance(id), 50 );
+		assert_eq!( Balances::reserved_balance(treasury), 10 + 50 );
+
+		// the final tranche releases the rest
+		assert_eq!( Flow::submit_milestone(&id), Ok(1) );
+		assert_ok!( Flow::resolve_milestone(&id, Some(true)) );
+		assert_eq!( Flow::released_balance(id), 100 );
+		assert_eq!( Balances::reserved_balance(treasury), 10 );
+		assert_noop!( Flow::submit_milestone(&id), Error::<Test>::MilestoneUnknown );
+	});
+}
+
+#[test]
+fn undecided_milestone_is_reopened() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20) ], ..terms() });
+		fund(id);
+
+		assert_ok!( Flow::submit_milestone(&id) );
+		assert_ok!( Flow::resolve_milestone(&id, None) );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Pending );
+		assert_eq!( Flow::submit_milestone(&id), Ok(0) );
+	});
+}
+
+#[test]
+fn milestone_over_spend_limit_is_reopened() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20) ], ..terms() });
+		fund(id);
+		put_storage_value( b"Control50", b"BodySpendLimit", &Blake2_128Concat::hash(&org().encode()), (10u64, 100u64) );
+
+		assert_ok!( Flow::submit_milestone(&id) );
+		assert_ok!( Flow::resolve_milestone(&id, Some(true)) );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Pending );
+		assert_eq!( Flow::released_balance(id), 0 );
+		assert_eq!( Flow::submit_milestone(&id), Ok(0) );
+	});
+}
+
+#[test]
+fn rejected_milestone_refunds_remaining_pro_rata() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20), milestone(30, 30) ], ..terms() });
+		fund(id);
+		assert_ok!( Flow::submit_milestone(&id) );
+		assert_ok!( Flow::resolve_milestone(&id, Some(true)) );
+
+		assert_ok!( Flow::submit_milestone(&id) );
+		assert_ok!( Flow::resolve_milestone(&id, Some(false)) );
+		assert_eq!( Flow::milestone_state((id, 1)), MilestoneState::Failed );
+		assert_eq!( Flow::milestone_refunds(), vec![ (id, 50, 0) ] );
+
+		// 50 left, 60 : 40, refunded in the next block
+		run_to_block(13);
+		assert!( Flow::milestone_refunds().is_empty() );
+		assert_eq!( Balances::free_balance(BOB), 1_000 - 60 + 30 );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 - 40 + 20 );
+		assert_eq!( Flow::released_balance(id), 100 );
+		assert_eq!( Balances::reserved_balance(Control::body_treasury(org())), 10 );
+	});
+}
+
+#[test]
+fn missed_deadline_fails_milestone() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20), milestone(30, 30) ], ..terms() });
+		fund(id);
+
+		run_to_block(20);
+		assert_noop!( Flow::submit_milestone(&id), Error::<Test>::MilestoneExpired );
+		run_to_block(21);
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Failed );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+
+		// later deadlines of a failed campaign are skipped
+		run_to_block(31);
+		assert_eq!( Flow::milestone_state((id, 1)), MilestoneState::Pending );
+	});
+}
+
+#[test]
+fn milestone_refunds_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { milestones: vec![ milestone(50, 20) ], ..terms() });
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 50 ) );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 30 ) );
+		assert_ok!( Flow::contribute( Origin::signed(DAVE), id, 20 ) );
+		run_to_block(12);
+		assert_ok!( Flow::submit_milestone(&id) );
+		assert_ok!( Flow::resolve_milestone(&id, Some(false)) );
+
+		// two contributors per block
+		run_to_block(13);
+		assert_eq!( Flow::milestone_refunds(), vec![ (id, 100, 2) ] );
+		run_to_block(14);
+		assert!( Flow::milestone_refunds().is_empty() );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+		assert_eq!( Balances::free_balance(DAVE), 1_000 );
+	});
+}
+
+#[test]
+fn milestone_deadlines_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		let with_deadline = || CampaignTerms { milestones: vec![ milestone(50, 30) ], ..terms() };
+		create_campaign(with_deadline());
+		create_campaign(with_deadline());
+		run_to_block(2);
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 12,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], b"PLAY".to_vec(), b"play".to_vec(),
+				with_deadline(),
+			),
+			Error::<Test>::MilestonesPerBlockExceeded
+		);
+	});
+}
+
+//
+//	schedule and rules
+//
 
-	#[test]
-	fn contribute_to_campaign_should_work() {
-		with_externalities(&mut build_ext(), || {
+#[test]
+fn scheduled_campaign_starts_at_its_block() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { start: 5, ..terms() });
+		assert_eq!( Flow::campaign_state(id), FlowState::Init );
+		assert_eq!( Flow::campaigns_by_start(5), vec![ id ] );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 10 ), Error::<Test>::NoContributionsAllowed );
 
-			// create a Campaign with account #5.
-			assert_ok!(Campaigns::create(Origin::signed(5), vec![12,56], 20000, 0, 1000));
+		// locked before the start, unlocked to init only
+		assert_ok!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Locked ) );
+		assert_noop!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Active ), Error::<Test>::InvalidTransition );
+		assert_noop!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Paused ), Error::<Test>::InvalidTransition );
+		assert_ok!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Init ) );
+
+		run_to_block(5);
+		assert_eq!( Flow::campaign_state(id), FlowState::Active );
+		assert!( Flow::campaigns_by_start(5).is_empty() );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 10 ) );
+
+		// a start in the past starts right away,
+		// the duration counts from the start
+		let id = create_campaign(CampaignTerms { start: 1, ..terms() });
+		assert_eq!( Flow::campaign_state(id), FlowState::Active );
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 12,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![],
+				CampaignTerms { start: 8, ..terms() },
+			),
+			Error::<Test>::EndTooEarly
+		);
+	});
+}
+
+#[test]
+fn contributions_follow_rules() {
+	new_test_ext().execute_with(|| {
+		let rules = |min_contribution, max_contribution| CampaignTerms {
+			rules: ContributionRules { min_contribution, max_contribution, allowlist: false },
+			..terms()
+		};
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![], rules(1, None),
+			),
+			Error::<Test>::ContributionTooSmall
+		);
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![], rules(20, Some(10)),
+			),
+			Error::<Test>::ContributionRulesInvalid
+		);
+
+		let id = create_campaign(rules(20, Some(50)));
+		assert_noop!( Flow::contribute( Origin::signed(ALICE), id, 20 ), Error::<Test>::NoContributionToOwnCampaign );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 10 ), Error::<Test>::ContributionTooSmall );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 30 ) );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 30 ), Error::<Test>::ContributionTooLarge );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 20 ) );
+		assert_eq!( Flow::campaign_contribution((id, BOB)), 50 );
+		assert_eq!( Balances::reserved_balance(BOB), 50 );
+		assert_ok!( Flow::check_invariants() );
+	});
+}
 
-			// check that there are now 1 Campaigns in storage
-			assert_eq!(Campaigns::all_campaigns_count(), 1);
+#[test]
+fn allowlist_admits_body_members() {
+	new_test_ext().execute_with(|| {
+		let open = create_campaign(terms());
+		assert_noop!( Flow::allow( Origin::signed(ALICE), open, vec![ BOB ] ), Error::<Test>::NoAllowlist );
 
-			// check that account #6 owns 1 Campaign
-			assert_eq!(Campaigns::owned_campaigns_count(5), 1);
+		let id = create_campaign(CampaignTerms {
+			rules: ContributionRules { min_contribution: 5, max_contribution: None, allowlist: true },
+			..terms()
+		});
+		assert_noop!( Flow::allow( Origin::signed(BOB), id, vec![ BOB ] ), Error::<Test>::AuthorizationError );
+		assert_noop!( Flow::allow( Origin::signed(ALICE), id, vec![ BOB, DAVE ] ), Error::<Test>::NotBodyMember );
+		assert_ok!( Flow::allow( Origin::signed(ALICE), id, vec![ BOB ] ) );
 
-			// check that some random account #3 does not own a Campaign
-			assert_eq!(Campaigns::owned_campaigns_count(3), 0);
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 10 ) );
+		assert_noop!( Flow::contribute( Origin::signed(CHARLIE), id, 10 ), Error::<Test>::NotAllowlisted );
 
-			// check that this Campaign is specifically owned by account #6
-			let hash = Campaigns::campaign_by_id(0);
-			assert_eq!(Campaigns::owner_of(hash), Some(5));
+		assert_ok!( Flow::disallow( Origin::signed(ALICE), id, vec![ BOB ] ) );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 10 ), Error::<Test>::NotAllowlisted );
+	});
+}
+
+#[test]
+fn contributions_per_block_are_limited() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 10 ) );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 10 ) );
+		assert_ok!( Flow::contribute( Origin::signed(DAVE), id, 10 ) );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 10 ), Error::<Test>::ContributionsPerBlockExceeded );
+
+		run_to_block(2);
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 10 ) );
+	});
+}
+
+#[test]
+fn open_campaigns_per_owner_are_limited() {
+	new_test_ext().execute_with(|| {
+		let first = create_campaign(terms());
+		create_campaign(terms());
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![], terms(),
+			),
+			Error::<Test>::CampaignsPerAddressExceeded
+		);
+
+		// cancelling frees the slot,
+		// creations per block stay limited
+		assert_ok!( Flow::cancel( Origin::signed(ALICE), first ) );
+		assert_eq!( Flow::campaigns_open_by_owner(ALICE), 1 );
+		assert_noop!(
+			Flow::create(
+				Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+				FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![], terms(),
+			),
+			Error::<Test>::CampaignsPerBlockExceeded
+		);
+		run_to_block(2);
+		create_campaign(terms());
+	});
+}
+
+#[test]
+fn extension_applies_at_expiry() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 30 ) );
+
+		assert_noop!( Flow::extend( Origin::signed(BOB), id, 20 ), Error::<Test>::AuthorizationError );
+		assert_noop!( Flow::extend( Origin::signed(ALICE), id, 11 ), Error::<Test>::EndTooEarly );
+		assert_noop!( Flow::extend( Origin::signed(ALICE), id, 102 ), Error::<Test>::EndTooLate );
+		assert_ok!( Flow::extend( Origin::signed(ALICE), id, 20 ) );
+		assert_noop!( Flow::extend( Origin::signed(ALICE), id, 30 ), Error::<Test>::ExtensionExists );
+
+		run_to_block(12);
+		assert_eq!( Flow::campaign_state(id), FlowState::Active );
+		assert_eq!( Flow::campaign_by_id(id).expiry, 20 );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 10 ) );
+
+		run_to_block(21);
+		assert_eq!( Flow::campaign_state(id), FlowState::Failed );
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+	});
+}
 
-			let other_hash = Campaigns::owned_campaigns_by_index(5, 0));
-			assert_eq!(hash, other_hash);
+#[test]
+fn majority_vetoes_extension() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 60 ) );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 30 ) );
+		assert_noop!( Flow::veto_extension( Origin::signed(BOB), id ), Error::<Test>::ExtensionUnknown );
+		assert_ok!( Flow::extend( Origin::signed(ALICE), id, 20 ) );
 
-		})
+		assert_noop!( Flow::veto_extension( Origin::signed(DAVE), id ), Error::<Test>::NoContribution );
+		assert_ok!( Flow::veto_extension( Origin::signed(BOB), id ) );
+		assert_noop!( Flow::veto_extension( Origin::signed(BOB), id ), Error::<Test>::AlreadyVetoed );
+		assert_eq!( Flow::campaign_extension(id).unwrap().vetoed, 60 );
+
+		run_to_block(12);
+		assert_eq!( Flow::campaign_state(id), FlowState::Failed );
+		assert_eq!( Flow::campaign_by_id(id).expiry, 11 );
+	});
+}
+
+//
+//	rewards
+//
+
+fn tier(min_contribution: u64, class: u64, max_supply: u64) -> RewardTier<u64> {
+	RewardTier { min_contribution, class, max_supply, name: b"perk".to_vec(), cid: vec![] }
+}
+
+#[test]
+fn reward_tiers_are_validated() {
+	new_test_ext().execute_with(|| {
+		let create = |tiers: Vec<RewardTier<u64>>| Flow::create(
+			Origin::signed(ALICE), org(), ADMIN, b"campaign".to_vec(), 100, 10, 11,
+			FlowProtocol::Raise, FlowGovernance::No, vec![], vec![], vec![],
+			CampaignTerms { tiers, ..terms() },
+		);
+		assert_noop!(
+			create(vec![ tier(10, 0, 1), tier(20, 0, 1), tier(30, 0, 1) ]),
+			Error::<Test>::TooManyRewardTiers
+		);
+		assert_noop!( create(vec![ tier(10, 1, 1) ]), Error::<Test>::ClassUnknown );
+		assert_noop!( create(vec![ tier(10, 0, 0) ]), Error::<Test>::RewardTierInvalid );
+		assert_noop!( create(vec![ tier(20, 0, 1), tier(10, 0, 1) ]), Error::<Test>::RewardTierInvalid );
+		assert_ok!( create(vec![ tier(10, 0, 1), tier(20, 0, 1) ]) );
+	});
+}
+
+#[test]
+fn rewards_are_minted_over_blocks() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { tiers: vec![ tier(10, 0, 5), tier(50, 0, 1) ], ..terms() });
+		assert_eq!( Flow::campaign_realm(id), 0 );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 50 ) );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 50 ) );
+		assert_ok!( Flow::contribute( Origin::signed(DAVE), id, 5 ) );
+
+		// two contributors per block
+		run_to_block(12);
+		assert_eq!( Flow::reward_queue(), vec![ (id, 2) ] );
+
+		// the top tier is exhausted, charlie falls back
+		assert_eq!( Flow::reward_tier_minted((id, 1)), 1 );
+		assert_eq!( Flow::reward_tier_minted((id, 0)), 1 );
+		assert_eq!( Tangram::items_for_account(BOB).len(), 1 );
+		assert_eq!( Tangram::items_for_account(CHARLIE).len(), 1 );
+
+		// below the lowest tier
+		run_to_block(13);
+		assert!( Flow::reward_queue().is_empty() );
+		assert!( Tangram::items_for_account(DAVE).is_empty() );
+	});
+}
+
+#[test]
+fn failed_campaign_mints_no_rewards() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(CampaignTerms { tiers: vec![ tier(10, 0, 5) ], ..terms() });
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 50 ) );
+
+		run_to_block(13);
+		assert_eq!( Flow::campaign_state(id), FlowState::Failed );
+		assert!( Flow::reward_queue().is_empty() );
+		assert!( Tangram::items_for_account(BOB).is_empty() );
+	});
+}
+
+//
+//	states
+//
+
+#[test]
+fn transitions_follow_the_table() {
+	use FlowState::*;
+	assert!( Active.can_transition(&Paused, FlowRole::Owner) );
+	assert!( Paused.can_transition(&Active, FlowRole::Owner) );
+	assert!( !Active.can_transition(&Locked, FlowRole::Owner) );
+	assert!( Active.can_transition(&Locked, FlowRole::Admin) );
+	assert!( Locked.can_transition(&Active, FlowRole::Admin) );
+	assert!( !Paused.can_transition(&Active, FlowRole::Admin) );
+	for role in [ FlowRole::Owner, FlowRole::Admin ] {
+		for from in [ Init, Active, Paused, Success, Failed, Locked ] {
+			assert!( !from.can_transition(&Success, role) );
+			assert!( !from.can_transition(&Failed, role) );
+			assert!( !Success.can_transition(&from, role) );
+			assert!( !Failed.can_transition(&from, role) );
+		}
 	}
+	assert!( Locked.is_open() );
+	assert!( !Success.is_open() );
+}
+
+#[test]
+fn owner_pauses_and_admin_locks() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_noop!( Flow::update_state( Origin::signed(BOB), id, FlowState::Paused ), Error::<Test>::AuthorizationError );
+		assert_noop!( Flow::update_state( Origin::signed(ALICE), id, FlowState::Locked ), Error::<Test>::InvalidTransition );
+		assert_noop!( Flow::update_state( Origin::signed(ALICE), id, FlowState::Success ), Error::<Test>::InvalidTransition );
+
+		assert_ok!( Flow::update_state( Origin::signed(ALICE), id, FlowState::Paused ) );
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 10 ), Error::<Test>::NoContributionsAllowed );
+		assert_ok!( Flow::update_state( Origin::signed(ALICE), id, FlowState::Active ) );
+
+		assert_ok!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Locked ) );
+		assert_eq!( Flow::campaigns_by_state(FlowState::Locked), vec![ id ] );
+		assert!( Flow::campaigns_by_state(FlowState::Active).is_empty() );
+
+		// only scheduled campaigns go back to init
+		assert_noop!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Init ), Error::<Test>::InvalidTransition );
+		assert_ok!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Active ) );
+
+		run_to_block(11);
+		assert_noop!( Flow::update_state( Origin::signed(ALICE), id, FlowState::Paused ), Error::<Test>::CampaignExpired );
+	});
+}
+
+#[test]
+fn locked_campaign_fails_at_expiry() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 100 ) );
+		assert_ok!( Flow::update_state( Origin::signed(ADMIN), id, FlowState::Locked ) );
+
+		run_to_block(12);
+		assert_eq!( Flow::campaign_state(id), FlowState::Failed );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Balances::reserved_balance(Control::body_treasury(org())), 0 );
+		assert_eq!( Flow::campaigns_open_by_owner(ALICE), 0 );
+	});
+}
+
+#[test]
+fn cancel_refunds_contributors_over_blocks() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_noop!( Flow::cancel( Origin::signed(BOB), id ), Error::<Test>::AuthorizationError );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 10 ) );
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), id, 10 ) );
+		assert_ok!( Flow::contribute( Origin::signed(DAVE), id, 10 ) );
+
+		assert_ok!( Flow::cancel( Origin::signed(ALICE), id ) );
+		assert!( !Campaigns::<Test>::contains_key(id) );
+		assert_eq!( Flow::campaigns_count(), 0 );
+		assert!( Flow::campaigns_by_body(org()).is_empty() );
+		assert_eq!( Balances::reserved_balance(Control::body_treasury(org())), 0 );
+		assert_eq!( Flow::cleanup_queue(), vec![ (id, true) ] );
+
+		// two contributors per block
+		run_to_block(2);
+		assert_eq!( Balances::reserved_balance(DAVE) + Balances::reserved_balance(CHARLIE), 0 );
+		assert_eq!( Balances::reserved_balance(BOB), 10 );
+		run_to_block(3);
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert!( Flow::cleanup_queue().is_empty() );
+		assert!( Flow::campaigns_contributed(BOB).is_empty() );
+		assert_eq!( Flow::campaigns_contributed_count(BOB), 0 );
+		assert_eq!( Flow::campaign_contribution((id, BOB)), 0 );
+		assert_ok!( Flow::check_invariants() );
+	});
+}
+
+#[test]
+fn settled_campaigns_are_not_removed() {
+	new_test_ext().execute_with(|| {
+		let id = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 100 ) );
+		run_to_block(12);
+
+		assert_noop!( Flow::cancel( Origin::signed(ALICE), id ), Error::<Test>::InvalidTransition );
+		assert_noop!( Flow::destroy( Origin::signed(ALICE), id ), sp_runtime::traits::BadOrigin );
+		assert_noop!( Flow::destroy( Origin::root(), id ), Error::<Test>::CampaignSettled );
+
+		// failed campaigns were refunded on finalization
+		let failed = create_campaign(terms());
+		assert_ok!( Flow::contribute( Origin::signed(CHARLIE), failed, 10 ) );
+		run_to_block(23);
+		assert_eq!( Flow::campaign_state(failed), FlowState::Failed );
+		assert_ok!( Flow::destroy( Origin::root(), failed ) );
+		assert_eq!( Flow::cleanup_queue(), vec![ (failed, false) ] );
+		run_to_block(24);
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+		assert_eq!( Flow::campaigns_count(), 1 );
+	});
+}
+
+//
+//	genesis
+//
+
+#[test]
+fn genesis_creates_campaigns_with_runtime_minimum() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> {
+		balances: vec![ (ALICE, 1_000), (BOB, 1_000), (GAMEDAO, 1), (Control::treasury_account(0), 1_000) ],
+	}.assimilate_storage(&mut t).unwrap();
+	control::GenesisConfig::<Test> {
+		bodies: vec![(
+			ALICE, ALICE, b"body".to_vec(), vec![], control::ControlType::Dao,
+			control::ControlAccessModel::Open, control::ControlFeeModel::NoFees, 0, 0, vec![],
+		)],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> {
+		campaigns: vec![ (0, b"genesis".to_vec(), 100, 10, 20, vec![]) ],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		System::set_block_number(1);
+		let id = Flow::campaigns_index(0);
+		assert_eq!( Flow::campaign_owner(id), Some(ALICE) );
+		assert_eq!( Flow::campaign_org(id), org() );
+		assert_eq!( Flow::campaign_state(id), FlowState::Active );
+		assert_eq!( Flow::campaign_by_id(id).expiry, 20 );
+		assert_eq!( Flow::campaign_rules(id).min_contribution, 5 );
+		assert_eq!( Balances::reserved_balance(Control::treasury_account(0)), 10 );
+		assert_eq!( Flow::storage_version(), Releases::V1 );
+
+		assert_noop!( Flow::contribute( Origin::signed(BOB), id, 4 ), Error::<Test>::ContributionTooSmall );
+		assert_ok!( Flow::contribute( Origin::signed(BOB), id, 5 ) );
+		assert_ok!( Flow::check_invariants() );
+	});
+}
+
+//
+//	migrations
+//
+
+type V0Campaign = OldCampaign<H256, u128, u64, u64, u64, FlowProtocol, FlowGovernance>;
+
+// a campaign as a V0 chain stored it
+fn put_v0_campaign(id: H256, expiry: u64, state: FlowState) {
+	let old = V0Campaign {
+		id,
+		org: org(),
+		name: b"legacy".to_vec(),
+		owner: ALICE,
+		admin: ADMIN,
+		deposit: 10,
+		expiry,
+		cap: 100,
+		protocol: FlowProtocol::Grant,
+		governance: FlowGovernance::Yes,
+		cid: b"cid".to_vec(),
+		token_symbol: b"OLD".to_vec(),
+		token_name: b"old".to_vec(),
+		created: 7,
+	};
+	frame_support::storage::unhashed::put( &Campaigns::<Test>::hashed_key_for(id), &old );
+	CampaignOrg::<Test>::insert( id, org() );
+	CampaignState::<Test>::insert( id, state );
+}
+
+#[test]
+fn v1_migration_adds_start_and_counts_open_campaigns() {
+	new_test_ext().execute_with(|| {
+		let open = H256::repeat_byte(1);
+		let settled = H256::repeat_byte(2);
+		put_v0_campaign(open, 30, FlowState::Active);
+		put_v0_campaign(settled, 40, FlowState::Success);
+		CampaignsOpenByOwner::<Test>::insert( BOB, 3 );
+		StorageVersion::put(Releases::V0);
+
+		Flow::on_runtime_upgrade();
+		assert_eq!( Flow::storage_version(), Releases::V1 );
+
+		let campaign = Flow::campaign_by_id(open);
+		assert_eq!( campaign.start, 0 );
+		assert_eq!( campaign.expiry, 30 );
+		assert_eq!( campaign.owner, ALICE );
+		assert_eq!( campaign.admin, ADMIN );
+		assert_eq!( campaign.deposit, 10 );
+		assert_eq!( campaign.cap, 100 );
+		assert_eq!( campaign.protocol, FlowProtocol::Grant );
+		assert_eq!( campaign.governance, FlowGovernance::Yes );
+		assert_eq!( campaign.cid, b"cid".to_vec() );
+		assert_eq!( campaign.token_symbol, b"OLD".to_vec() );
+		assert_eq!( campaign.created, 7 );
+		assert_eq!( Flow::campaign_by_id(settled).expiry, 40 );
+
+		// open campaigns are recounted
+		assert_eq!( Flow::campaigns_open_by_owner(ALICE), 1 );
+		assert_eq!( Flow::campaigns_open_by_owner(BOB), 0 );
+		assert_ok!( Flow::check_invariants() );
 
+		// migrated once
+		assert_eq!( Flow::on_runtime_upgrade(), 0 );
+	});
 }
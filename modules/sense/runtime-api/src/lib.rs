@@ -0,0 +1,31 @@
+//
+//           _______________________________ ________
+//           \____    /\_   _____/\______   \\_____  \
+//             /     /  |    __)_  |       _/ /   |   \
+//            /     /_  |        \ |    |   \/    |    \
+//           /_______ \/_______  / |____|_  /\_______  /
+//                   \/        \/         \/         \/
+//           Z  E  R  O  .  I  O     N  E  T  W  O  R  K
+//           © C O P Y R I O T   2 0 7 5 @ Z E R O . I O
+
+// This file is part of ZERO Network.
+// Copyright (C) 2010-2020 ZERO Labs.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime API definition for the sense module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use primitives::SenseProps;
+
+sp_api::decl_runtime_apis! {
+	pub trait SenseApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// weighted score across xp, rep and trust
+		fn score(account: AccountId) -> u64;
+		/// current value of a property, decayed up to the current block
+		fn value(account: AccountId, property: SenseProps) -> u64;
+	}
+}
@@ -15,14 +15,36 @@
 //! SENSE
 //!
 //! This pallet aggregates datapoints to reflect user experience and behaviour.
+//!
+//! Properties are mutated by authorised reporters. Each property (XP, REP, Trust)
+//! keeps its own registry of reporters, which can optionally be scoped to a
+//! tangram realm, and then only report on accounts owning an item of that realm.
+//! Every mutation is recorded together with its reporter.
+//!
+//! Properties decay over time according to a governance set model. Decay is
+//! applied lazily whenever a property is read or written. XP, REP and Trust
+//! combine into a weighted sense score, which other pallets can use to gate
+//! actions through `primitives::SenseScore`.
+//!
+//! Anyone can open a judgement on an entity by reserving a deposit. Accepted
+//! judgements apply their delta and refund the deposit, rejected judgements
+//! slash it. Entities can register themselves against a deposit, which
+//! governance or the entity itself, once trusted, can release.
+//!
+//! GameDAO pallets report meaningful actions through `primitives::SenseHooks`.
+//! Each action earns runtime configured XP and REP, capped per block.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
 // TODO:
 // mod default_weight;
-// mod mock;
-// mod tests;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
 
 pub use module::*;
 
@@ -32,13 +54,20 @@ pub mod module {
 		decl_error, decl_event, decl_module, decl_storage,
 		ensure,
 		dispatch::DispatchResult,
-		traits::{ EnsureOrigin },
+		traits::{ Currency, EnsureOrigin, Get, OnUnbalanced, ReservableCurrency },
+		weights::Weight,
+		storage::IterableStorageMap,
 	};
-	use frame_system::{ self as system, ensure_root };
+	use frame_system::{ self as system, ensure_root, ensure_signed };
 	use codec::{ Encode, Decode };
-	use sp_runtime::{ ModuleId };
+	use sp_runtime::{
+		traits::{ Saturating, SaturatedConversion, Zero },
+		ModuleId, Permill,
+	};
 	use sp_std::prelude::*;
 	use pallet_balances::{ self as balances };
+	use primitives::{ RealmProvider, SenseAction, SenseHooks, SenseProps, SenseScore };
+	use tangram::RealmIndex;
 
 	//
 	//
@@ -47,6 +76,28 @@ pub mod module {
 	pub const MODULE_ID: ModuleId = ModuleId(*b"sensenet");
 	pub const MODULE_VERSION: &str = "1.0";
 
+	/// Storage version
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+	#[derive(Debug)]
+	pub enum Releases {
+		/// unversioned
+		V0,
+		/// versioned storage
+		V1,
+	}
+
+	impl Default for Releases {
+		fn default() -> Self { Releases::V0 }
+	}
+
+	/// signed delta applied to a property
+	pub type SenseDelta = i64;
+	/// index of a judgement
+	pub type JudgementIndex = u64;
+
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as system::Config>::AccountId>>::NegativeImbalance;
+
 	//
 	//
 	//
@@ -56,17 +107,93 @@ pub mod module {
 		// type ModuleId: Get<ModuleId>;
 		/// the goode olde event
 		type Event: From<Event<Self>> + Into<<Self as system::Config>::Event>;
-		///
+		/// governance, manages reporters, decay and score weights
 		type ForceOrigin: EnsureOrigin<Self::Origin>;
+		/// max number of entities mutated in one batch
+		type MaxBatchSize: Get<u32>;
+		/// participants of a realm, realm reporters only report on them
+		type Realms: RealmProvider<Self::AccountId>;
+
+		/// deposits
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// deposit to register an entity by itself
+		type EntityDeposit: Get<BalanceOf<Self>>;
+		/// trust of an entity to release its deposit by itself
+		type ReleaseTrust: Get<u64>;
+		/// deposit to open a judgement
+		type JudgementDeposit: Get<BalanceOf<Self>>;
+		/// max open judgements per entity
+		type MaxOpenJudgements: Get<u32>;
+		/// governance, accepts or rejects judgements
+		type JudgementOrigin: EnsureOrigin<Self::Origin>;
+		/// receiver of slashed judgement deposits
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// xp and rep earned by gamedao actions
+		type Rewards: RewardSchedule;
+		/// max rewarded actions per account and block
+		type MaxRewardsPerBlock: Get<u32>;
 		// TODO:
 		// Weight information for extrinsics in this module.
 		// type WeightInfo: WeightInfo;
 	}
 
+	/// Rewards for GameDAO actions, defined by the runtime
+	pub trait RewardSchedule {
+		/// xp and rep awarded for an action
+		fn reward(action: SenseAction) -> (SenseDelta, SenseDelta);
+	}
+
+	impl RewardSchedule for () {
+		fn reward(_: SenseAction) -> (SenseDelta, SenseDelta) { (0, 0) }
+	}
+
 	//
 	//
 	//
 
+	/// Scope of an authorised reporter
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub enum ReporterScope {
+		/// may report across the metaverse
+		Global,
+		/// reports on behalf of a tangram realm
+		Realm(RealmIndex),
+	}
+
+	/// Decay of a property over time
+	#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub enum DecayModel<BlockNumber> {
+		/// values do not decay
+		None,
+		/// subtract `amount` every `period` blocks
+		Linear { amount: u64, period: BlockNumber },
+		/// remove `rate` of the value every `period` blocks
+		Exponential { rate: Permill, period: BlockNumber },
+	}
+
+	impl<BlockNumber> Default for DecayModel<BlockNumber> {
+		fn default() -> Self { Self::None }
+	}
+
+	/// Weights of the properties in the sense score
+	#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct SenseWeights {
+		pub xp:    Permill,
+		pub rep:   Permill,
+		pub trust: Permill,
+	}
+
+	impl Default for SenseWeights {
+		fn default() -> Self {
+			let third = Permill::from_rational_approximation(1u32, 3u32);
+			Self { xp: third, rep: third, trust: third }
+		}
+	}
+
 	/// SenseNet Entity
 	#[derive(Encode, Decode, Default, PartialEq, Eq)]
 	#[cfg_attr(feature = "std", derive(Debug))]
@@ -79,13 +206,44 @@ pub mod module {
 	}
 
 	/// SenseNet Entity Property
-	#[derive(Encode, Decode, Default, PartialEq, Eq)]
+	/// when the property decays, `mutated` marks the last applied decay step
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
 	#[cfg_attr(feature = "std", derive(Debug))]
 	pub struct EntityProperty<BlockNumber> {
 		value:   u64,
 		mutated: BlockNumber,
 	}
 
+	/// SenseNet Mutation Record
+	#[derive(Encode, Decode, Default, PartialEq, Eq)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct SenseRecord<AccountId, BlockNumber> {
+		pub reporter: Option<AccountId>, // none when applied by root
+		pub realm:    Option<RealmIndex>,
+		pub property: SenseProps,
+		pub delta:    SenseDelta,
+		pub value:    u64,               // value after mutation
+		pub block:    BlockNumber,
+	}
+
+	/// SenseNet Judgement
+	/// proposes to apply `delta` to a property of `entity`
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct Judgement<AccountId, Balance, BlockNumber> {
+		proposer: AccountId,
+		entity:   AccountId,
+		property: SenseProps,
+		delta:    SenseDelta,
+		deposit:  Balance,
+		cid:      Vec<u8>,
+		created:  BlockNumber,
+	}
+
+	impl<AccountId, Balance, BlockNumber> Judgement<AccountId, Balance, BlockNumber> {
+		pub fn property(&self) -> SenseProps { self.property }
+	}
+
 	//
 	//
 	//
@@ -101,12 +259,59 @@ pub mod module {
 			/// Trust score across the metaverse
 			SenseTrust get(fn trust): map hasher(blake2_128_concat) T::AccountId => EntityProperty<T::BlockNumber>;
 
-			// propose judgements account, value, deposit
-			// if judgement successful, value will be applied and deposit returned
-			// SenseOpenJudgements get(fn judgements): map hasher(blake2_128_concat) => Vec<Judgement<T::AccountId, u64, T::Balance>>;
+			/// Decay model per property
+			PropertyDecay get(fn decay): map hasher(blake2_128_concat) SenseProps => DecayModel<T::BlockNumber>;
+			/// Block the decay model of a property was set in, no decay applies before it
+			PropertyDecaySince get(fn decay_since): map hasher(blake2_128_concat) SenseProps => T::BlockNumber;
+			/// Weights of XP, REP and Trust in the sense score
+			ScoreWeights get(fn score_weights): SenseWeights;
+
+			/// Reporters authorised to mutate a property
+			Reporters get(fn reporter): map hasher(blake2_128_concat) (SenseProps, T::AccountId) => Option<ReporterScope>;
+
+			/// Mutation records by entity and index
+			SenseRecords get(fn record_by_index): map hasher(blake2_128_concat) (T::AccountId, u64) => SenseRecord<T::AccountId, T::BlockNumber>;
+			/// Number of mutation records by entity
+			SenseRecordsCount get(fn records_count): map hasher(blake2_128_concat) T::AccountId => u64;
+
+			/// Deposits of self registered entities
+			EntityDeposit get(fn entity_deposit): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+			/// Open judgements by index
+			/// if a judgement is accepted, its delta is applied and the deposit returned
+			SenseOpenJudgements get(fn judgement): map hasher(blake2_128_concat) JudgementIndex => Option<Judgement<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+			/// Open judgements on an entity
+			JudgementsForEntity get(fn judgements_for): map hasher(blake2_128_concat) T::AccountId => Vec<JudgementIndex>;
+			/// Next judgement index
+			JudgementNonce get(fn judgement_nonce): JudgementIndex;
+
+			/// Rewarded actions of an account and the block they were counted in,
+			/// a count from an earlier block no longer applies
+			RewardsInBlock: map hasher(blake2_128_concat) T::AccountId => (T::BlockNumber, u32);
 
 			/// the goode olde nonce
 			Nonce: u128;
+
+			/// Storage version, new chains start at the latest
+			StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V1): Releases;
+		}
+		add_extra_genesis {
+			/// entities created at genesis: (account, cid, xp, rep, trust)
+			config(entities): Vec<(T::AccountId, Vec<u8>, u64, u64, u64)>;
+			/// global reporters at genesis
+			config(reporters): Vec<(SenseProps, T::AccountId)>;
+			build(|config: &GenesisConfig<T>| {
+				for (account, cid, xp, rep, trust) in config.entities.iter() {
+					Module::<T>::create_entity( system::RawOrigin::Root.into(), account.clone(), cid.clone() )
+						.expect("genesis entity is valid");
+					<SenseXP<T>>::mutate( account, |property| property.value = *xp );
+					<SenseREP<T>>::mutate( account, |property| property.value = *rep );
+					<SenseTrust<T>>::mutate( account, |property| property.value = *trust );
+				}
+				for (property, reporter) in config.reporters.iter() {
+					<Reporters<T>>::insert( (property, reporter), ReporterScope::Global );
+				}
+			});
 		}
 	}
 
@@ -118,11 +323,22 @@ pub mod module {
 		pub enum Event<T> where
 			<T as system::Config>::AccountId,
 			<T as system::Config>::BlockNumber,
+			Balance = BalanceOf<T>,
+			Decay = DecayModel<<T as system::Config>::BlockNumber>,
 		{
 			EntityInit(AccountId, BlockNumber),
+			DepositReleased(AccountId, Balance),
 			EntityMutateXP(AccountId, BlockNumber),
 			EntityMutateREP(AccountId, BlockNumber),
 			EntityMutateTrust(AccountId, BlockNumber),
+			ReporterAdded(SenseProps, AccountId, ReporterScope),
+			ReporterRemoved(SenseProps, AccountId),
+			DecaySet(SenseProps, Decay),
+			WeightsSet(SenseWeights),
+			JudgementOpened(JudgementIndex, AccountId, AccountId, Balance),
+			JudgementAccepted(JudgementIndex),
+			JudgementRejected(JudgementIndex, Balance),
+			ActionRewarded(AccountId, SenseAction),
 		}
 	}
 
@@ -136,8 +352,32 @@ pub mod module {
 			EntityExists,
 			/// Entity Unknown
 			EntityUnknown,
+			/// Reporter Exists
+			ReporterExists,
+			/// Reporter Unknown
+			ReporterUnknown,
+			/// Not an authorised reporter for this property
+			NotAuthorized,
+			/// Realm Unknown
+			RealmUnknown,
+			/// Entity is not a participant of the reporter's realm
+			OutsideRealm,
+			/// Batch is empty or exceeds the batch limit
+			BatchSizeInvalid,
+			/// Decay period must not be zero
+			DecayPeriodZero,
+			/// Judgement Unknown
+			JudgementUnknown,
+			/// Too many open judgements for the entity
+			TooManyJudgements,
+			/// Overflow
+			Overflow,
 			/// Guru Meditation
 			GuruMeditation,
+			/// Entity has no deposit
+			NoDeposit,
+			/// Trust too low to release the deposit
+			TrustTooLow,
 		}
 	}
 
@@ -151,17 +391,42 @@ pub mod module {
 			fn deposit_event() = default;
 			type Error = Error<T>;
 
+			const MaxBatchSize: u32 = T::MaxBatchSize::get();
+
+			fn on_runtime_upgrade() -> Weight {
+				match Self::storage_version() {
+					Releases::V0 => Self::migrate_to_v1(),
+					_ => 0,
+				}
+			}
+
 			// Create Entity
+			// root creates any entity for free,
+			// an account can register itself against a deposit
 			#[weight = 1_000_000]
-			fn create_entity(
+			pub fn create_entity(
 				origin,
 				account: T::AccountId,
 				cid:     Vec<u8>,
 			) -> DispatchResult {
 
-				ensure_root(origin)?;
+				let deposit = match ensure_signed(origin.clone()) {
+					Ok(sender) => {
+						ensure!( sender == account, Error::<T>::NotAuthorized );
+						T::EntityDeposit::get()
+					},
+					Err(_) => {
+						ensure_root(origin)?;
+						Zero::zero()
+					},
+				};
 				ensure!( !<Sense<T>>::contains_key(&account), Error::<T>::EntityExists );
 
+				if !deposit.is_zero() {
+					T::Currency::reserve(&account, deposit)?;
+					<EntityDeposit<T>>::insert( account.clone(), deposit );
+				}
+
 				let now   = <system::Module<T>>::block_number();
 				let index = Nonce::get();
 				let data  = Entity {
@@ -190,9 +455,35 @@ pub mod module {
 
 			}
 
-			// TODO:
-			// mutation of values should be restricted
-			// certain roles are allowed to mutate values
+			// Release Deposit
+			// governance, or a self registered entity once trusted,
+			// returns the deposit of the entity
+			#[weight = 1_000_000]
+			pub fn release_deposit(
+				origin,
+				account: T::AccountId,
+			) -> DispatchResult {
+
+				if T::ForceOrigin::try_origin(origin.clone()).is_err() {
+					let sender = ensure_signed(origin)?;
+					ensure!( sender == account, Error::<T>::NotAuthorized );
+					ensure!( Self::value(SenseProps::TRUST, &account) >= T::ReleaseTrust::get(), Error::<T>::TrustTooLow );
+				}
+
+				let deposit = Self::entity_deposit(&account);
+				ensure!( !deposit.is_zero(), Error::<T>::NoDeposit );
+				<EntityDeposit<T>>::remove(&account);
+				T::Currency::unreserve(&account, deposit);
+
+				Self::deposit_event(
+					RawEvent::DepositReleased(account, deposit)
+				);
+				Ok(())
+
+			}
+
+			// mutation of values is restricted
+			// to reporters authorised per property
 			// xp:    realm
 			// rep:   social
 			// trust: id
@@ -200,114 +491,474 @@ pub mod module {
 			//        sudo ( until its removal )
 
 			#[weight = 1_000_000]
-			fn mod_xp(
+			pub fn mod_xp(
+				origin,
+				account: T::AccountId,
+				value:   u8, // allow for a maximum of u8 to change
+			) -> DispatchResult {
+				ensure_root(origin)?;
+				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				Self::mutate( None, None, SenseProps::XP, account, SenseDelta::from(value) );
+				Ok(())
+			}
+
+			#[weight = 1_000_000]
+			pub fn mod_rep(
 				origin,
 				account: T::AccountId,
 				value:   u8, // allow for a maximum of u8 to change
 			) -> DispatchResult {
+				ensure_root(origin)?;
+				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				Self::mutate( None, None, SenseProps::REP, account, SenseDelta::from(value) );
+				Ok(())
+			}
 
+			#[weight = 1_000_000]
+			pub fn mod_trust(
+				origin,
+				account: T::AccountId,
+				value:   u8, // allow for a maximum of u8 to change
+			) -> DispatchResult {
 				ensure_root(origin)?;
 				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				Self::mutate( None, None, SenseProps::TRUST, account, SenseDelta::from(value) );
+				Ok(())
+			}
 
-				let now = <system::Module<T>>::block_number();
+			// Authorise a reporter for a property
+			// scope: global or a tangram realm
+			#[weight = 1_000_000]
+			pub fn add_reporter(
+				origin,
+				property: SenseProps,
+				reporter: T::AccountId,
+				scope:    ReporterScope,
+			) -> DispatchResult {
 
-				let v = u64::from(value);
-				let current = Self::xp(&account);
-				let updated = EntityProperty {
-					value: current.value.checked_add(v).ok_or(Error::<T>::GuruMeditation)?,
-					mutated: now
-				};
+				T::ForceOrigin::ensure_origin(origin)?;
+				ensure!( !<Reporters<T>>::contains_key((&property, &reporter)), Error::<T>::ReporterExists );
+				if let ReporterScope::Realm(realm) = scope {
+					ensure!( realm < tangram::NextRealmIndex::get(), Error::<T>::RealmUnknown );
+				}
 
-				<SenseXP<T>>::insert( account.clone(), updated );
+				<Reporters<T>>::insert( (property.clone(), reporter.clone()), scope.clone() );
 
 				Self::deposit_event(
-					RawEvent::EntityMutateXP(account, now)
+					RawEvent::ReporterAdded(property, reporter, scope)
 				);
 				Ok(())
 
 			}
 
+			// Revoke a reporter for a property
 			#[weight = 1_000_000]
-			fn mod_rep(
+			pub fn remove_reporter(
 				origin,
-				account: T::AccountId,
-				value:   u8, // allow for a maximum of u8 to change
+				property: SenseProps,
+				reporter: T::AccountId,
 			) -> DispatchResult {
 
-				ensure_root(origin)?;
-				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				T::ForceOrigin::ensure_origin(origin)?;
+				ensure!( <Reporters<T>>::contains_key((&property, &reporter)), Error::<T>::ReporterUnknown );
 
-				let now = <system::Module<T>>::block_number();
-				let v = u64::from(value);
-				let current = Self::rep(&account);
-				let updated = EntityProperty {
-					value: current.value.checked_add(v).ok_or(Error::<T>::GuruMeditation)?,
-					mutated: now
-				};
+				<Reporters<T>>::remove( (property.clone(), reporter.clone()) );
 
-				<SenseREP<T>>::insert( account.clone(), updated );
+				Self::deposit_event(
+					RawEvent::ReporterRemoved(property, reporter)
+				);
+				Ok(())
+
+			}
+
+			// Set the decay model of a property
+			// values decay lazily from their last decay step,
+			// but not before the model was set
+			#[weight = 1_000_000]
+			pub fn set_decay(
+				origin,
+				property: SenseProps,
+				model:    DecayModel<T::BlockNumber>,
+			) -> DispatchResult {
+
+				T::ForceOrigin::ensure_origin(origin)?;
+				match &model {
+					DecayModel::Linear { period, .. } |
+					DecayModel::Exponential { period, .. } => ensure!( !period.is_zero(), Error::<T>::DecayPeriodZero ),
+					DecayModel::None => {},
+				}
+
+				<PropertyDecay<T>>::insert( property.clone(), model.clone() );
+				<PropertyDecaySince<T>>::insert( property.clone(), <system::Module<T>>::block_number() );
 
 				Self::deposit_event(
-					RawEvent::EntityMutateREP(account, now)
+					RawEvent::DecaySet(property, model)
 				);
 				Ok(())
 
 			}
 
+			// Set the weights of the sense score
 			#[weight = 1_000_000]
-			fn mod_trust(
+			pub fn set_weights(
 				origin,
-				account: T::AccountId,
-				value:   u8, // allow for a maximum of u8 to change
+				weights: SenseWeights,
 			) -> DispatchResult {
 
-				ensure_root(origin)?;
-				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				T::ForceOrigin::ensure_origin(origin)?;
+				ScoreWeights::put( weights.clone() );
+
+				Self::deposit_event(
+					RawEvent::WeightsSet(weights)
+				);
+				Ok(())
+
+			}
+
+			// Open a judgement on an entity
+			// reserves the judgement deposit of the proposer
+			#[weight = 1_000_000]
+			pub fn open_judgement(
+				origin,
+				entity:   T::AccountId,
+				property: SenseProps,
+				delta:    SenseDelta,
+				cid:      Vec<u8>,
+			) -> DispatchResult {
 
-				let now = <system::Module<T>>::block_number();
-				let v = u64::from(value);
-				let current = Self::trust(&account);
-				let updated = EntityProperty {
-					value: current.value.checked_add(v).ok_or(Error::<T>::GuruMeditation)?,
-					mutated: now
+				let proposer = ensure_signed(origin)?;
+				ensure!( <Sense<T>>::contains_key(&entity), Error::<T>::EntityUnknown );
+
+				let mut open = Self::judgements_for(&entity);
+				ensure!( open.len() < T::MaxOpenJudgements::get() as usize, Error::<T>::TooManyJudgements );
+
+				let index = Self::judgement_nonce();
+				let next = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+
+				let deposit = T::JudgementDeposit::get();
+				T::Currency::reserve(&proposer, deposit)?;
+
+				let judgement = Judgement {
+					proposer: proposer.clone(),
+					entity: entity.clone(),
+					property,
+					delta,
+					deposit,
+					cid,
+					created: <system::Module<T>>::block_number(),
 				};
 
-				<SenseTrust<T>>::insert( account.clone(), updated );
+				<SenseOpenJudgements<T>>::insert( index, judgement );
+				open.push( index );
+				<JudgementsForEntity<T>>::insert( entity.clone(), open );
+				JudgementNonce::put( next );
 
 				Self::deposit_event(
-					RawEvent::EntityMutateTrust(account, now)
+					RawEvent::JudgementOpened(index, proposer, entity, deposit)
 				);
 				Ok(())
 
 			}
 
-			// TODO:
-			// generic mod for all properties
+			// Accept or reject a judgement
+			#[weight = 1_000_000]
+			pub fn resolve_judgement(
+				origin,
+				index:  JudgementIndex,
+				accept: bool,
+			) -> DispatchResult {
+				T::JudgementOrigin::ensure_origin(origin)?;
+				Self::do_resolve_judgement(index, accept)
+			}
+
+			// Report a signed delta for an entity
+			#[weight = 1_000_000]
+			pub fn report(
+				origin,
+				property: SenseProps,
+				account:  T::AccountId,
+				delta:    SenseDelta,
+			) -> DispatchResult {
+
+				let reporter = ensure_signed(origin)?;
+				let scope = Self::reporter((&property, &reporter)).ok_or(Error::<T>::NotAuthorized)?;
+				ensure!( <Sense<T>>::contains_key(&account), Error::<T>::EntityUnknown );
+				Self::ensure_in_scope(&scope, &account)?;
+
+				Self::mutate( Some(reporter), Self::realm_of(&scope), property, account, delta );
+				Ok(())
+
+			}
 
-			// #[weight = 1_000]
-			// fn mod(
-			// 	origin,
-			// 	account: T::AccountId,
-			// 	property: SenseProps,
-			// 	value:   u8, // allow for a maximum of u8 to change
-			// ) -> DispatchResult {
+			// Report signed deltas for many entities at once
+			// either all updates are applied or none
+			#[weight = 1_000_000 * (updates.len() as Weight).max(1)]
+			pub fn report_batch(
+				origin,
+				property: SenseProps,
+				updates:  Vec<(T::AccountId, SenseDelta)>,
+			) -> DispatchResult {
 
-			// 	let creator = ensure_signed(origin)?;
-			// 	let now = <system::Module<T>>::block_number();
+				let reporter = ensure_signed(origin)?;
+				let scope = Self::reporter((&property, &reporter)).ok_or(Error::<T>::NotAuthorized)?;
 
-			// 	let v = u64::from(v);
-			// 	let current_xp = Self::xp(&account);
-			// 	let updated_xp = current_xp.checked_add(v).ok_or(Error::<T>::GuruMeditation)?;
-			// 	<SenseXP<T>>::insert( account.clone(), updated_xp );
+				ensure!( !updates.is_empty(), Error::<T>::BatchSizeInvalid );
+				ensure!( updates.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchSizeInvalid );
+				for (account, _) in &updates {
+					ensure!( <Sense<T>>::contains_key(account), Error::<T>::EntityUnknown );
+					Self::ensure_in_scope(&scope, account)?;
+				}
 
-			// 	Self::deposit_event(
-			// 		RawEvent::EntityMutate(account, now)
-			// 	);
-			// 	Ok(())
+				let realm = Self::realm_of(&scope);
+				for (account, delta) in updates {
+					Self::mutate( Some(reporter.clone()), realm, property, account, delta );
+				}
+				Ok(())
 
-			// }
+			}
 
 		}
 	}
 
+	impl<T: Config> Module<T> {
+
+		// layout is unchanged, only the version is recorded
+		fn migrate_to_v1() -> Weight {
+			StorageVersion::put(Releases::V1);
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		/// storage invariants, checked against state snapshots after migrations
+		#[cfg(feature = "std")]
+		pub fn check_invariants() -> Result<(), &'static str> {
+			let mut entities: u128 = 0;
+			for (account, _) in <Sense<T>>::iter() {
+				entities = entities.saturating_add(1);
+				for index in 0..Self::records_count(&account) {
+					if !<SenseRecords<T>>::contains_key(( account.clone(), index )) {
+						return Err("missing sense record")
+					}
+				}
+			}
+			if entities != Nonce::get() {
+				return Err("entity count does not match nonce")
+			}
+			if Self::storage_version() != Releases::V1 {
+				return Err("storage version not migrated")
+			}
+			Ok(())
+		}
+
+		/// get a property of an entity, decayed up to now
+		pub fn property(
+			property: SenseProps,
+			account: &T::AccountId,
+		) -> EntityProperty<T::BlockNumber> {
+			let stored = match property {
+				SenseProps::XP => Self::xp(account),
+				SenseProps::REP => Self::rep(account),
+				SenseProps::TRUST => Self::trust(account),
+			};
+			let now = <system::Module<T>>::block_number();
+			Self::decayed(property, stored, now)
+		}
+
+		/// current value of a property
+		pub fn value(
+			property: SenseProps,
+			account: &T::AccountId,
+		) -> u64 {
+			Self::property(property, account).value
+		}
+
+		/// rewarded actions of an account in the current block
+		pub fn rewards_in_block(
+			account: &T::AccountId,
+		) -> u32 {
+			let (block, count) = <RewardsInBlock<T>>::get(account);
+			if block == <system::Module<T>>::block_number() { count } else { 0 }
+		}
+
+		/// weighted score across xp, rep and trust
+		pub fn score(
+			account: &T::AccountId,
+		) -> u64 {
+			let weights = Self::score_weights();
+			weights.xp.mul_floor( Self::value(SenseProps::XP, account) )
+				.saturating_add( weights.rep.mul_floor( Self::value(SenseProps::REP, account) ) )
+				.saturating_add( weights.trust.mul_floor( Self::value(SenseProps::TRUST, account) ) )
+		}
+
+		// apply all decay steps completed between
+		// the last decay step, at the earliest the decay model change, and now
+		fn decayed(
+			property: SenseProps,
+			stored: EntityProperty<T::BlockNumber>,
+			now: T::BlockNumber,
+		) -> EntityProperty<T::BlockNumber> {
+
+			let period = match Self::decay(property) {
+				DecayModel::None => return stored,
+				DecayModel::Linear { period, .. } => period,
+				DecayModel::Exponential { period, .. } => period,
+			};
+			let from = stored.mutated.clone().max( Self::decay_since(property) );
+			if period.is_zero() || now <= from { return stored }
+
+			let steps = (now - from.clone()) / period.clone();
+			if steps.is_zero() { return stored }
+			let mutated = from + steps.clone() * period;
+
+			let value = match Self::decay(property) {
+				DecayModel::Linear { amount, .. } => {
+					let steps: u64 = steps.saturated_into();
+					stored.value.saturating_sub( amount.saturating_mul(steps) )
+				},
+				DecayModel::Exponential { rate, .. } => {
+					let steps: u32 = steps.saturated_into();
+					let retained = Permill::one().saturating_sub(rate).saturating_pow(steps as usize);
+					retained.mul_floor(stored.value)
+				},
+				DecayModel::None => stored.value,
+			};
+
+			EntityProperty { value, mutated }
+
+		}
+
+		/// accept or reject an open judgement
+		/// accepted: apply the delta, refund the deposit
+		/// rejected: slash the deposit
+		pub fn do_resolve_judgement(
+			index: JudgementIndex,
+			accept: bool,
+		) -> DispatchResult {
+
+			let judgement = Self::judgement(index).ok_or(Error::<T>::JudgementUnknown)?;
+
+			<SenseOpenJudgements<T>>::remove( index );
+			<JudgementsForEntity<T>>::mutate( &judgement.entity, |open| open.retain(|i| *i != index) );
+
+			if accept {
+				T::Currency::unreserve( &judgement.proposer, judgement.deposit );
+				if <Sense<T>>::contains_key(&judgement.entity) {
+					Self::mutate(
+						Some(judgement.proposer),
+						None,
+						judgement.property,
+						judgement.entity,
+						judgement.delta
+					);
+				}
+				Self::deposit_event( RawEvent::JudgementAccepted(index) );
+			} else {
+				let (imbalance, _) = T::Currency::slash_reserved( &judgement.proposer, judgement.deposit );
+				T::Slash::on_unbalanced( imbalance );
+				Self::deposit_event( RawEvent::JudgementRejected(index, judgement.deposit) );
+			}
+
+			Ok(())
+
+		}
+
+		fn realm_of( scope: &ReporterScope ) -> Option<RealmIndex> {
+			match scope {
+				ReporterScope::Global => None,
+				ReporterScope::Realm(realm) => Some(*realm),
+			}
+		}
+
+		// realm reporters only report on participants of their realm
+		fn ensure_in_scope( scope: &ReporterScope, account: &T::AccountId ) -> DispatchResult {
+			if let ReporterScope::Realm(realm) = scope {
+				ensure!( T::Realms::is_participant(*realm, account), Error::<T>::OutsideRealm );
+			}
+			Ok(())
+		}
+
+		// apply a saturating delta to a property,
+		// record the mutation and notify
+		fn mutate(
+			reporter: Option<T::AccountId>,
+			realm: Option<RealmIndex>,
+			property: SenseProps,
+			account: T::AccountId,
+			delta: SenseDelta,
+		) {
+
+			let now = <system::Module<T>>::block_number();
+
+			// decay first, keep the decay step when decaying
+			let current = Self::property(property, &account);
+			let value = if delta >= 0 {
+				current.value.saturating_add(delta as u64)
+			} else {
+				current.value.saturating_sub(delta.unsigned_abs())
+			};
+			let mutated = match Self::decay(property) {
+				DecayModel::None => now.clone(),
+				_ => current.mutated,
+			};
+			let updated = EntityProperty { value, mutated };
+
+			match property {
+				SenseProps::XP => <SenseXP<T>>::insert( account.clone(), updated ),
+				SenseProps::REP => <SenseREP<T>>::insert( account.clone(), updated ),
+				SenseProps::TRUST => <SenseTrust<T>>::insert( account.clone(), updated ),
+			}
+
+			let record = SenseRecord {
+				reporter,
+				realm,
+				property,
+				delta,
+				value,
+				block: now.clone(),
+			};
+			let index = Self::records_count(&account);
+			<SenseRecords<T>>::insert( (account.clone(), index), record );
+			<SenseRecordsCount<T>>::insert( account.clone(), index.saturating_add(1) );
+
+			let event = match property {
+				SenseProps::XP => RawEvent::EntityMutateXP(account, now),
+				SenseProps::REP => RawEvent::EntityMutateREP(account, now),
+				SenseProps::TRUST => RawEvent::EntityMutateTrust(account, now),
+			};
+			Self::deposit_event(event);
+
+		}
+
+	}
+
+	impl<T: Config> SenseHooks<T::AccountId> for Module<T> {
+		// reward registered entities for an action,
+		// silently ignore everything beyond the block cap
+		fn on_action(action: SenseAction, who: &T::AccountId) {
+
+			if !<Sense<T>>::contains_key(who) { return }
+
+			let (xp, rep) = T::Rewards::reward(action);
+			if xp == 0 && rep == 0 { return }
+
+			let count = Self::rewards_in_block(who);
+			if count >= T::MaxRewardsPerBlock::get() { return }
+			<RewardsInBlock<T>>::insert( who, (<system::Module<T>>::block_number(), count + 1) );
+
+			if xp != 0 { Self::mutate( None, None, SenseProps::XP, who.clone(), xp ); }
+			if rep != 0 { Self::mutate( None, None, SenseProps::REP, who.clone(), rep ); }
+
+			Self::deposit_event( RawEvent::ActionRewarded(who.clone(), action) );
+
+		}
+	}
+
+	impl<T: Config> SenseScore<T::AccountId> for Module<T> {
+		fn score(who: &T::AccountId) -> u64 {
+			Self::score(who)
+		}
+		fn value(property: SenseProps, who: &T::AccountId) -> u64 {
+			Self::value(property, who)
+		}
+	}
+
 }
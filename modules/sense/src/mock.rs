@@ -0,0 +1,138 @@
+// mock runtime for the sense tests
+
+#![cfg(test)]
+
+use crate as sense;
+use crate::RewardSchedule;
+use frame_support::{ parameter_types, traits::{ OnFinalize, OnInitialize } };
+use frame_system::EnsureRoot;
+use primitives::{ RealmProvider, SenseAction };
+use sp_core::H256;
+use sp_runtime::{ testing::Header, traits::{ BlakeTwo256, IdentityLookup } };
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Sense: sense::{Module, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const ORACLE: u64 = 10;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 50;
+}
+impl pallet_balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = MaxLocks;
+	type WeightInfo = ();
+}
+
+// alice and bob own items of realm 0
+pub struct Realms;
+impl RealmProvider<u64> for Realms {
+	fn is_participant(realm: u64, who: &u64) -> bool {
+		realm == 0 && ( *who == ALICE || *who == BOB )
+	}
+}
+
+pub struct Rewards;
+impl RewardSchedule for Rewards {
+	fn reward(action: SenseAction) -> (i64, i64) {
+		match action {
+			SenseAction::Contribute => (10, 1),
+			SenseAction::Vote => (5, 0),
+			_ => (0, 0),
+		}
+	}
+}
+
+parameter_types! {
+	pub const MaxBatchSize: u32 = 3;
+	pub const EntityDeposit: u64 = 100;
+	pub const ReleaseTrust: u64 = 10;
+	pub const JudgementDeposit: u64 = 50;
+	pub const MaxOpenJudgements: u32 = 2;
+	pub const MaxRewardsPerBlock: u32 = 2;
+}
+impl sense::Config for Test {
+	type Event = Event;
+	type ForceOrigin = EnsureRoot<u64>;
+	type MaxBatchSize = MaxBatchSize;
+	type Realms = Realms;
+	type Currency = Balances;
+	type EntityDeposit = EntityDeposit;
+	type ReleaseTrust = ReleaseTrust;
+	type JudgementDeposit = JudgementDeposit;
+	type MaxOpenJudgements = MaxOpenJudgements;
+	type JudgementOrigin = EnsureRoot<u64>;
+	type Slash = ();
+	type Rewards = Rewards;
+	type MaxRewardsPerBlock = MaxRewardsPerBlock;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![ (ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000), (ORACLE, 1_000) ],
+	}.assimilate_storage(&mut t).unwrap();
+	sense::GenesisConfig::<Test> {
+		entities: vec![ (ALICE, vec![], 0, 0, 0), (BOB, vec![], 0, 0, 0) ],
+		reporters: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		Sense::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		Sense::on_initialize(System::block_number());
+	}
+}
@@ -0,0 +1,393 @@
+use super::*;
+use primitives::{ SenseAction, SenseHooks, SenseProps, SenseScore };
+use crate::mock::*;
+use frame_support::{ assert_noop, assert_ok, migration::put_storage_value, traits::OnRuntimeUpgrade, StorageValue };
+use sp_runtime::{ traits::BadOrigin, Permill };
+
+//
+//	reporters
+//
+
+#[test]
+fn only_authorised_reporters_report() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sense::report( Origin::signed(ORACLE), SenseProps::XP, ALICE, 5 ),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			Sense::add_reporter( Origin::signed(ORACLE), SenseProps::XP, ORACLE, ReporterScope::Global ),
+			BadOrigin
+		);
+
+		assert_ok!( Sense::add_reporter( Origin::root(), SenseProps::XP, ORACLE, ReporterScope::Global ) );
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::XP, ALICE, 5 ) );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 5 );
+
+		// reporters are authorised per property
+		assert_noop!(
+			Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, 5 ),
+			Error::<Test>::NotAuthorized
+		);
+
+		assert_ok!( Sense::remove_reporter( Origin::root(), SenseProps::XP, ORACLE ) );
+		assert_noop!(
+			Sense::report( Origin::signed(ORACLE), SenseProps::XP, ALICE, 5 ),
+			Error::<Test>::NotAuthorized
+		);
+	});
+}
+
+#[test]
+fn deltas_saturate_and_are_recorded() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::add_reporter( Origin::root(), SenseProps::REP, ORACLE, ReporterScope::Global ) );
+
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, 7 ) );
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, -10 ) );
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 0 );
+
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, i64::MAX ) );
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, i64::MAX ) );
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, i64::MAX ) );
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), u64::MAX );
+
+		assert_eq!( Sense::records_count(ALICE), 5 );
+		let record = Sense::record_by_index((ALICE, 1));
+		assert_eq!( record.reporter, Some(ORACLE) );
+		assert_eq!( record.delta, -10 );
+		assert_eq!( record.value, 0 );
+		assert_ok!( Sense::check_invariants() );
+	});
+}
+
+#[test]
+fn batches_apply_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::add_reporter( Origin::root(), SenseProps::XP, ORACLE, ReporterScope::Global ) );
+
+		assert_noop!(
+			Sense::report_batch( Origin::signed(ORACLE), SenseProps::XP, vec![] ),
+			Error::<Test>::BatchSizeInvalid
+		);
+		assert_noop!(
+			Sense::report_batch( Origin::signed(ORACLE), SenseProps::XP, vec![ (ALICE, 1); 4 ] ),
+			Error::<Test>::BatchSizeInvalid
+		);
+		// charlie is no entity, alice is not updated either
+		assert_noop!(
+			Sense::report_batch( Origin::signed(ORACLE), SenseProps::XP, vec![ (ALICE, 1), (CHARLIE, 1) ] ),
+			Error::<Test>::EntityUnknown
+		);
+
+		assert_ok!( Sense::report_batch( Origin::signed(ORACLE), SenseProps::XP, vec![ (ALICE, 3), (BOB, 4) ] ) );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 3 );
+		assert_eq!( Sense::value(SenseProps::XP, &BOB), 4 );
+	});
+}
+
+#[test]
+fn realm_reporters_stay_in_their_realm() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sense::add_reporter( Origin::root(), SenseProps::XP, ORACLE, ReporterScope::Realm(0) ),
+			Error::<Test>::RealmUnknown
+		);
+		tangram::NextRealmIndex::put(2);
+		assert_ok!( Sense::add_reporter( Origin::root(), SenseProps::XP, ORACLE, ReporterScope::Realm(0) ) );
+		assert_ok!( Sense::create_entity( Origin::root(), CHARLIE, vec![] ) );
+
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::XP, ALICE, 5 ) );
+		assert_eq!( Sense::record_by_index((ALICE, 0)).realm, Some(0) );
+
+		// charlie owns nothing in realm 0
+		assert_noop!(
+			Sense::report( Origin::signed(ORACLE), SenseProps::XP, CHARLIE, 5 ),
+			Error::<Test>::OutsideRealm
+		);
+		assert_noop!(
+			Sense::report_batch( Origin::signed(ORACLE), SenseProps::XP, vec![ (BOB, 1), (CHARLIE, 1) ] ),
+			Error::<Test>::OutsideRealm
+		);
+	});
+}
+
+//
+//	decay and score
+//
+
+#[test]
+fn decay_periods_must_not_be_zero() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sense::set_decay( Origin::root(), SenseProps::XP, DecayModel::Linear { amount: 1, period: 0 } ),
+			Error::<Test>::DecayPeriodZero
+		);
+		assert_noop!(
+			Sense::set_decay( Origin::signed(ALICE), SenseProps::XP, DecayModel::None ),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn linear_decay_is_applied_per_completed_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::set_decay( Origin::root(), SenseProps::XP, DecayModel::Linear { amount: 10, period: 5 } ) );
+		assert_ok!( Sense::mod_xp( Origin::root(), ALICE, 100 ) );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 100 );
+
+		// decay was set at block 1, the first step completes at 6
+		run_to_block(5);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 100 );
+		run_to_block(6);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 90 );
+		run_to_block(12);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 80 );
+
+		// writes keep the decay step, a partial period is not lost
+		assert_ok!( Sense::mod_xp( Origin::root(), ALICE, 20 ) );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 100 );
+		run_to_block(16);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 90 );
+
+		run_to_block(100);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 0 );
+	});
+}
+
+#[test]
+fn exponential_decay_compounds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::set_decay(
+			Origin::root(),
+			SenseProps::REP,
+			DecayModel::Exponential { rate: Permill::from_percent(10), period: 10 }
+		) );
+		assert_ok!( Sense::add_reporter( Origin::root(), SenseProps::REP, ORACLE, ReporterScope::Global ) );
+		assert_ok!( Sense::report( Origin::signed(ORACLE), SenseProps::REP, ALICE, 1_000 ) );
+
+		run_to_block(11);
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 900 );
+		run_to_block(30);
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 810 );
+		run_to_block(31);
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 729 );
+
+		// properties without a decay model keep their value
+		assert_ok!( Sense::mod_trust( Origin::root(), ALICE, 50 ) );
+		run_to_block(1_000);
+		assert_eq!( Sense::value(SenseProps::TRUST, &ALICE), 50 );
+	});
+}
+
+#[test]
+fn decay_is_not_applied_before_the_model_is_set() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::mod_xp( Origin::root(), ALICE, 100 ) );
+		run_to_block(50);
+		assert_ok!( Sense::set_decay( Origin::root(), SenseProps::XP, DecayModel::Linear { amount: 10, period: 5 } ) );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 100 );
+		run_to_block(55);
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 90 );
+	});
+}
+
+#[test]
+fn score_weighs_decayed_properties() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::mod_xp( Origin::root(), ALICE, 200 ) );
+		assert_ok!( Sense::mod_rep( Origin::root(), ALICE, 100 ) );
+		assert_ok!( Sense::mod_trust( Origin::root(), ALICE, 50 ) );
+
+		// thirds by default
+		assert_eq!( Sense::score(&ALICE), 66 + 33 + 16 );
+
+		assert_ok!( Sense::set_weights( Origin::root(), SenseWeights {
+			xp: Permill::from_percent(50),
+			rep: Permill::from_percent(50),
+			trust: Permill::zero(),
+		} ) );
+		assert_eq!( Sense::score(&ALICE), 150 );
+
+		assert_ok!( Sense::set_decay( Origin::root(), SenseProps::XP, DecayModel::Linear { amount: 100, period: 10 } ) );
+		// without decay, writes moved the decay step to block 1
+		run_to_block(11);
+		assert_eq!( Sense::score(&ALICE), 100 );
+		assert_eq!( <Sense as SenseScore<u64>>::score(&ALICE), 100 );
+	});
+}
+
+//
+//	deposits and judgements
+//
+
+#[test]
+fn self_registration_reserves_a_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sense::create_entity( Origin::signed(ALICE), CHARLIE, vec![] ),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!( Sense::create_entity( Origin::signed(CHARLIE), CHARLIE, vec![] ) );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 100 );
+		assert_eq!( Sense::entity_deposit(CHARLIE), 100 );
+		assert_noop!(
+			Sense::create_entity( Origin::signed(CHARLIE), CHARLIE, vec![] ),
+			Error::<Test>::EntityExists
+		);
+		assert_ok!( Sense::check_invariants() );
+	});
+}
+
+#[test]
+fn trusted_entities_release_their_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::create_entity( Origin::signed(CHARLIE), CHARLIE, vec![] ) );
+
+		assert_noop!(
+			Sense::release_deposit( Origin::signed(ALICE), CHARLIE ),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			Sense::release_deposit( Origin::signed(CHARLIE), CHARLIE ),
+			Error::<Test>::TrustTooLow
+		);
+
+		assert_ok!( Sense::mod_trust( Origin::root(), CHARLIE, 10 ) );
+		assert_ok!( Sense::release_deposit( Origin::signed(CHARLIE), CHARLIE ) );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 0 );
+		assert_noop!(
+			Sense::release_deposit( Origin::signed(CHARLIE), CHARLIE ),
+			Error::<Test>::NoDeposit
+		);
+	});
+}
+
+#[test]
+fn governance_releases_any_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::create_entity( Origin::signed(CHARLIE), CHARLIE, vec![] ) );
+		assert_ok!( Sense::release_deposit( Origin::root(), CHARLIE ) );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 0 );
+		assert_eq!( Balances::free_balance(CHARLIE), 1_000 );
+		// root created entities have no deposit
+		assert_noop!(
+			Sense::release_deposit( Origin::root(), ALICE ),
+			Error::<Test>::NoDeposit
+		);
+	});
+}
+
+#[test]
+fn accepted_judgements_apply_and_refund() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sense::open_judgement( Origin::signed(BOB), CHARLIE, SenseProps::REP, 5, vec![] ),
+			Error::<Test>::EntityUnknown
+		);
+		assert_ok!( Sense::open_judgement( Origin::signed(BOB), ALICE, SenseProps::REP, 5, vec![] ) );
+		assert_eq!( Balances::reserved_balance(BOB), 50 );
+		assert_eq!( Sense::judgements_for(ALICE), vec![0] );
+
+		assert_noop!( Sense::resolve_judgement( Origin::signed(BOB), 0, true ), BadOrigin );
+		assert_ok!( Sense::resolve_judgement( Origin::root(), 0, true ) );
+
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 5 );
+		assert_eq!( Sense::record_by_index((ALICE, 0)).reporter, Some(BOB) );
+		assert!( Sense::judgement(0).is_none() );
+		assert!( Sense::judgements_for(ALICE).is_empty() );
+		assert_noop!(
+			Sense::resolve_judgement( Origin::root(), 0, true ),
+			Error::<Test>::JudgementUnknown
+		);
+	});
+}
+
+#[test]
+fn rejected_judgements_slash_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::open_judgement( Origin::signed(BOB), ALICE, SenseProps::TRUST, -5, vec![] ) );
+		assert_ok!( Sense::resolve_judgement( Origin::root(), 0, false ) );
+
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert_eq!( Balances::free_balance(BOB), 950 );
+		assert_eq!( Sense::records_count(ALICE), 0 );
+	});
+}
+
+#[test]
+fn open_judgements_per_entity_are_capped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Sense::open_judgement( Origin::signed(BOB), ALICE, SenseProps::REP, 1, vec![] ) );
+		assert_ok!( Sense::open_judgement( Origin::signed(CHARLIE), ALICE, SenseProps::REP, 1, vec![] ) );
+		assert_noop!(
+			Sense::open_judgement( Origin::signed(ORACLE), ALICE, SenseProps::REP, 1, vec![] ),
+			Error::<Test>::TooManyJudgements
+		);
+
+		// resolving frees a slot
+		assert_ok!( Sense::resolve_judgement( Origin::root(), 1, false ) );
+		assert_ok!( Sense::open_judgement( Origin::signed(ORACLE), ALICE, SenseProps::REP, 1, vec![] ) );
+		assert_eq!( Sense::judgements_for(ALICE), vec![0, 2] );
+	});
+}
+
+//
+//	hooks
+//
+
+#[test]
+fn actions_reward_registered_entities() {
+	new_test_ext().execute_with(|| {
+		Sense::on_action( SenseAction::Contribute, &ALICE );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 10 );
+		assert_eq!( Sense::value(SenseProps::REP, &ALICE), 1 );
+
+		// unrewarded actions and unknown accounts are ignored
+		Sense::on_action( SenseAction::JoinBody, &ALICE );
+		Sense::on_action( SenseAction::Contribute, &CHARLIE );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 10 );
+		assert_eq!( Sense::records_count(CHARLIE), 0 );
+		assert_eq!( Sense::rewards_in_block(&ALICE), 1 );
+	});
+}
+
+#[test]
+fn rewards_are_capped_per_block() {
+	new_test_ext().execute_with(|| {
+		Sense::on_action( SenseAction::Vote, &ALICE );
+		Sense::on_action( SenseAction::Vote, &ALICE );
+		Sense::on_action( SenseAction::Vote, &ALICE );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 10 );
+
+		// the cap is per account
+		Sense::on_action( SenseAction::Vote, &BOB );
+		assert_eq!( Sense::value(SenseProps::XP, &BOB), 5 );
+
+		// and starts over next block
+		run_to_block(2);
+		assert_eq!( Sense::rewards_in_block(&ALICE), 0 );
+		Sense::on_action( SenseAction::Vote, &ALICE );
+		assert_eq!( Sense::value(SenseProps::XP, &ALICE), 15 );
+	});
+}
+
+//
+//	migrations
+//
+
+#[test]
+fn upgrade_records_the_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!( Sense::storage_version(), Releases::V1 );
+
+		// a chain from before versioning
+		put_storage_value( b"Sense48", b"StorageVersion", &[], Releases::V0 );
+		Sense::on_runtime_upgrade();
+		assert_eq!( Sense::storage_version(), Releases::V1 );
+		assert_ok!( Sense::check_invariants() );
+	});
+}
@@ -22,17 +22,21 @@
 
 use flow;
 use control;
+use sense;
 
 use frame_system::{ self as system, ensure_signed };
 use frame_support::{
 	decl_storage, decl_module, decl_event, decl_error,
-	StorageValue, StorageMap,
+	StorageValue, StorageMap, IterableStorageMap,
 	dispatch::DispatchResult, ensure,
+	weights::Weight,
+	transactional,
 	traits::{
 		Currency,
 		ReservableCurrency,
 		Get,
 		Randomness,
+		BalanceStatus,
 	}
 };
 use sp_core::{ Hasher, H256 };
@@ -43,9 +47,15 @@ use sp_runtime::traits::{ Hash, Zero };
 #[cfg(feature = "std")]
 use serde::{ Deserialize, Serialize };
 
-use primitives::{ Balance, BlockNumber, Index, Moment};
+use primitives::{ Balance, BlockNumber, Index, Moment, SenseAction, SenseHooks, SenseScore };
 use scale_info::TypeInfo;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 //
 //
 //
@@ -62,6 +72,7 @@ pub enum ProposalState {
 	Expired = 4,	// ended without votes
 	Aborted = 5,	// sudo abort
 	Finalized = 6,	// accepted withdrawal proposal is processed
+	Tallying = 7,	// ended, ranked ballots are being counted
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
@@ -72,7 +83,9 @@ pub enum ProposalType {
 	Multiple = 1,
 	Member = 2,
 	Withdrawal = 3,
-	Spending = 4
+	Spending = 4,
+	Judgement = 5,
+	Milestone = 6
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
@@ -87,6 +100,39 @@ pub enum VotingType {
 	Conviction = 5
 }
 
+/// Tally of a multiple choice proposal
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Default, Eq, PartialOrd, Ord, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+#[repr(u8)]
+pub enum ChoiceMethod {
+	#[default]
+	Plurality = 0,		// one option per ballot, most votes win
+	InstantRunoff = 1,	// ranked, weakest option eliminated until one holds a majority
+	Borda = 2,			// ranked, options score by rank
+}
+
+/// Instant runoff counted over several blocks
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct RunoffTally {
+	/// preferences per option in the current round
+	counts: Vec<u64>,
+	/// options not yet eliminated
+	remaining: Vec<bool>,
+	/// next ballot to count in the current round
+	cursor: u32,
+}
+
+/// Vote of a revealed commitment
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[derive(Debug)]
+pub enum SecretVote {
+	/// yes / no
+	Simple(bool),
+	/// option indexes by preference
+	Choice(Vec<u8>),
+}
+
 type TitleText = Vec<u8>;
 type CID = Vec<u8>;
 // type ProposalType = u8;
@@ -115,15 +161,41 @@ pub struct ProposalMetadata<Balance> {
 //
 //
 
-pub trait Config: system::Config + balances::Config + timestamp::Config + flow::Config + control::Config {
+pub trait Config: system::Config + balances::Config + timestamp::Config + flow::Config + control::Config + sense::Config {
 	type Currency: ReservableCurrency<Self::AccountId>;
 	type Event: From<Event<Self>> + Into<<Self as system::Config>::Event>;
 	type Nonce: Get<u64>;
 	type Randomness: Randomness<Self::Hash>;
 	type MaxProposalsPerBlock: Get<usize>;
+	/// minimum sense score to vote
+	type MinScoreToVote: Get<u64>;
+	/// maximum options of a multiple choice proposal
+	type MaxOptions: Get<u32>;
+	/// ranked ballots counted per block for instant runoffs
+	type MaxRunoffBallots: Get<u32>;
+	/// reserved per commitment, forfeited when not revealed
+	type CommitDeposit: Get<BalanceOf<Self>>;
+	/// maximum reveal window of a commit reveal proposal
+	type MaxRevealPeriod: Get<Self::BlockNumber>;
+	/// experience for votes
+	type SenseHooks: SenseHooks<Self::AccountId>;
 	// type MaxDuration: Get<usize>;
 }
 
+/// Storage version
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo)]
+#[derive(Debug)]
+pub enum Releases {
+	/// unversioned
+	V0,
+	/// versioned storage
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self { Releases::V0 }
+}
+
 // TODO: replace with config
 const MAX_PROPOSALS_PER_BLOCK: usize = 3;
 const MAX_PROPOSAL_DURATION: u32 = 864000; // 60 * 60 * 24 * 30 / 3
@@ -170,6 +242,30 @@ decl_storage! {
 		/// Total proposals voted on by voter
 		ProposalsByVoterCount get(fn proposals_by_voter_index): map hasher(blake2_128_concat) T::AccountId => u64;
 
+		/// Options of a multiple choice proposal
+		ProposalOptions get(fn proposal_options): map hasher(blake2_128_concat) T::Hash => Vec<Vec<u8>>;
+		/// Tally method of a multiple choice proposal
+		ProposalChoiceMethod get(fn choice_method): map hasher(blake2_128_concat) T::Hash => ChoiceMethod;
+		/// Votes (plurality) or points (borda) per option
+		ProposalChoiceVotes get(fn choice_votes): map hasher(blake2_128_concat) T::Hash => Vec<u64>;
+		/// Ballots of a multiple choice proposal, options by preference
+		Ballots get(fn ballot): map hasher(blake2_128_concat) (T::Hash, u32) => Vec<u8>;
+		BallotCount get(fn ballot_count): map hasher(blake2_128_concat) T::Hash => u32 = 0;
+		/// Instant runoffs of ended proposals, counted in on_initialize
+		PendingRunoffs get(fn pending_runoffs): Vec<T::Hash>;
+		Runoffs get(fn runoff): map hasher(blake2_128_concat) T::Hash => Option<RunoffTally>;
+		/// Winning option of a decided multiple choice proposal
+		ProposalWinner get(fn proposal_winner): map hasher(blake2_128_concat) T::Hash => Option<u8>;
+
+		/// Reveal window after expiry of a commit reveal proposal
+		RevealPeriod get(fn reveal_period): map hasher(blake2_128_concat) T::Hash => Option<T::BlockNumber>;
+		/// Unrevealed commitments and their deposit
+		Commits get(fn commit): map hasher(blake2_128_concat) (T::Hash, T::AccountId) => Option<(T::Hash, BalanceOf<T>)>;
+		CommitCount get(fn commit_count): map hasher(blake2_128_concat) T::Hash => u32 = 0;
+
+		/// Sense judgement decided by a proposal
+		ProposalJudgement get(fn proposal_judgement): map hasher(blake2_128_concat) T::Hash => Option<sense::JudgementIndex>;
+
 		/// Proposals ending in a block
 		ProposalsByBlock get(fn proposals_by_block): map hasher(blake2_128_concat) T::BlockNumber => Vec<T::Hash>;
 
@@ -197,6 +293,9 @@ decl_storage! {
 
 		/// The total number of proposals
 		Nonce: u64;
+
+		/// Storage version, new chains start at the latest
+		StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V1): Releases;
 	}
 }
 
@@ -227,8 +326,7 @@ decl_module! {
 			ensure!( <control::Module<T>>::body_state(&context_id) == control::ControlState::Active, Error::<T>::DAOInactive );
 
 			// member of body?
-			let member = <control::Module<T>>::body_member_state((&context_id,&sender));
-			ensure!( member == control::ControlMemberState::Active, Error::<T>::AuthorizationError );
+			ensure!( <control::Module<T>>::is_active_member(&context_id,&sender), Error::<T>::AuthorizationError );
 
 			// ensure that start and expiry are in bounds
 			let current_block = <system::Module<T>>::block_number();
@@ -381,6 +479,8 @@ decl_module! {
 			// ensure!( flow::Module::<T>::campaign_by_id(&context_id), Error::<T>::CampaignUnknown );
 			let state = flow::Module::<T>::campaign_state(&context_id);
 			ensure!( state == flow::FlowState::Success, Error::<T>::CampaignFailed );
+			// campaigns with milestones release funds through milestone proposals
+			ensure!( flow::Module::<T>::campaign_milestones(&context_id).is_empty(), Error::<T>::MilestonesDeclared );
 			// let owner = flow::Module::<T>::campaign_owner(&context_id);
 			// ensure!( sender == owner, Error::<T>::AuthorizationError );
 
@@ -475,6 +575,245 @@ decl_module! {
 
 		}
 
+		//	create a judgement proposal
+		//	members of a body vote on an open sense judgement
+		//	the body treasury must be an authorised reporter
+		//	for the property the judgement mutates
+
+		#[weight = 5_000_000]
+		fn judgement_proposal(
+			origin,
+			context_id: T::Hash,
+			judgement: sense::JudgementIndex,
+			title: Vec<u8>,
+			cid: Vec<u8>,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			//	A C C E S S
+
+			ensure!( <control::Module<T>>::body_state(&context_id) == control::ControlState::Active, Error::<T>::DAOInactive );
+			ensure!( <control::Module<T>>::is_active_member(&context_id,&sender), Error::<T>::AuthorizationError );
+
+			let open = sense::Module::<T>::judgement(judgement).ok_or(Error::<T>::JudgementUnknown)?;
+			let treasury = <control::Module<T>>::body_treasury(&context_id);
+			ensure!( sense::Module::<T>::reporter((open.property(), &treasury)).is_some(), Error::<T>::AuthorizationError );
+
+			//	B O U N D S
+
+			let current_block = <system::Module<T>>::block_number();
+			ensure!(expiry > current_block, Error::<T>::OutOfBounds );
+			ensure!(expiry <= current_block + Self::proposal_time_limit(), Error::<T>::OutOfBounds );
+
+			//	T R A F F I C
+
+			let proposals = Self::proposals_by_block(expiry);
+			ensure!(proposals.len() < MAX_PROPOSALS_PER_BLOCK, Error::<T>::TooManyProposals );
+
+			//	C O N F I G
+
+			// the nonce separates proposals within a block
+			let seed = (b"just another judgement", Nonce::get()).encode();
+			let proposal_id = <T as Config>::Randomness::random(&seed);
+			ensure!(!Proposals::<T>::contains_key(&proposal_id), Error::<T>::HashCollision );
+
+			let proposal = Proposal {
+				proposal_id: proposal_id.clone(),
+				context_id: context_id.clone(),
+				proposal_type: ProposalType::Judgement,
+				voting_type: VotingType::Simple,
+				start: current_block,
+				expiry,
+			};
+
+			let metadata = ProposalMetadata {
+				title,
+				cid,
+				amount: T::Balance::zero(),
+			};
+
+			//	W R I T E
+
+			Self::insert_proposal( &sender, proposal, metadata )?;
+			ProposalJudgement::<T>::insert( &proposal_id, judgement );
+
+			//	E V E N T
+
+			Self::deposit_event(
+				RawEvent::Proposal(
+					sender,
+					proposal_id
+				)
+			);
+			Ok(())
+
+		}
+
+		//	create a milestone proposal
+		//	contributors vote on releasing the next tranche
+		//	of a successful campaign with milestones
+
+		#[weight = 5_000_000]
+		#[transactional]
+		fn milestone_proposal(
+			origin,
+			context_id: T::Hash,
+			title: Vec<u8>,
+			cid: Vec<u8>,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			//	A C C E S S
+
+			let owner = flow::Module::<T>::campaign_owner(&context_id).ok_or(Error::<T>::CampaignUnknown)?;
+			ensure!( sender == owner, Error::<T>::AuthorizationError );
+
+			//	B O U N D S
+
+			let current_block = <system::Module<T>>::block_number();
+			ensure!(expiry > current_block, Error::<T>::OutOfBounds );
+			ensure!(expiry <= current_block + Self::proposal_time_limit(), Error::<T>::OutOfBounds );
+
+			let milestones = flow::Module::<T>::campaign_milestones(&context_id);
+			let index = flow::Module::<T>::current_milestone(&context_id);
+			let milestone = milestones.get(index as usize).ok_or(Error::<T>::MilestoneUnknown)?;
+			ensure!(expiry < milestone.deadline, Error::<T>::OutOfBounds );
+
+			//	T R A F F I C
+
+			let proposals = Self::proposals_by_block(expiry);
+			ensure!(proposals.len() < MAX_PROPOSALS_PER_BLOCK, Error::<T>::TooManyProposals );
+
+			//	C O N F I G
+
+			// the nonce separates proposals within a block
+			let seed = (b"just another milestone", Nonce::get()).encode();
+			let proposal_id = <T as Config>::Randomness::random(&seed);
+			ensure!(!Proposals::<T>::contains_key(&proposal_id), Error::<T>::HashCollision );
+
+			let proposal = Proposal {
+				proposal_id: proposal_id.clone(),
+				context_id: context_id.clone(),
+				proposal_type: ProposalType::Milestone,
+				voting_type: VotingType::Simple,
+				start: current_block,
+				expiry,
+			};
+
+			let metadata = ProposalMetadata {
+				title,
+				cid,
+				amount: milestone.amount,
+			};
+
+			//	W R I T E
+
+			flow::Module::<T>::submit_milestone( &context_id )?;
+			Self::insert_proposal( &sender, proposal, metadata )?;
+
+			//	E V E N T
+
+			Self::deposit_event(
+				RawEvent::Proposal(
+					sender,
+					proposal_id
+				)
+			);
+			Ok(())
+
+		}
+
+		//	create a multiple choice proposal
+		//	members of a body choose between 2 and MaxOptions options,
+		//	with single choice or ranked ballots
+
+		#[weight = 5_000_000]
+		fn multiple_proposal(
+			origin,
+			context_id: T::Hash,
+			title: Vec<u8>,
+			cid: Vec<u8>,
+			options: Vec<Vec<u8>>,
+			method: ChoiceMethod,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			//	A C C E S S
+
+			ensure!( <control::Module<T>>::body_state(&context_id) == control::ControlState::Active, Error::<T>::DAOInactive );
+			ensure!( <control::Module<T>>::is_active_member(&context_id,&sender), Error::<T>::AuthorizationError );
+
+			//	B O U N D S
+
+			let current_block = <system::Module<T>>::block_number();
+			ensure!(expiry > current_block, Error::<T>::OutOfBounds );
+			ensure!(expiry <= current_block + Self::proposal_time_limit(), Error::<T>::OutOfBounds );
+
+			// options are referenced by u8 index on ballots
+			ensure!( options.len() >= 2, Error::<T>::TooFewOptions );
+			ensure!(
+				options.len() <= T::MaxOptions::get() as usize && options.len() <= u8::MAX as usize,
+				Error::<T>::TooManyOptions
+			);
+
+			//	T R A F F I C
+
+			let proposals = Self::proposals_by_block(expiry);
+			ensure!(proposals.len() < MAX_PROPOSALS_PER_BLOCK, Error::<T>::TooManyProposals );
+
+			//	C O N F I G
+
+			// the nonce separates proposals within a block
+			let seed = (b"just another choice", Nonce::get()).encode();
+			let proposal_id = <T as Config>::Randomness::random(&seed);
+			ensure!(!Proposals::<T>::contains_key(&proposal_id), Error::<T>::HashCollision );
+
+			let voting_type = match method {
+				ChoiceMethod::Plurality => VotingType::Simple,
+				_ => VotingType::Ranked,
+			};
+
+			let proposal = Proposal {
+				proposal_id: proposal_id.clone(),
+				context_id: context_id.clone(),
+				proposal_type: ProposalType::Multiple,
+				voting_type,
+				start: current_block,
+				expiry,
+			};
+
+			let metadata = ProposalMetadata {
+				title,
+				cid,
+				amount: T::Balance::zero(),
+			};
+
+			//	W R I T E
+
+			let count = options.len();
+			Self::insert_proposal( &sender, proposal, metadata )?;
+			ProposalOptions::<T>::insert( &proposal_id, options );
+			ProposalChoiceMethod::<T>::insert( &proposal_id, method );
+			ProposalChoiceVotes::<T>::insert( &proposal_id, vec![0u64; count] );
+
+			//	E V E N T
+
+			Self::deposit_event(
+				RawEvent::Proposal(
+					sender,
+					proposal_id
+				)
+			);
+			Ok(())
+
+		}
+
 		// TODO:
 		// voting vs staking, e.g.
 		// 1. token weighted and democratic voting require yes/no
@@ -497,6 +836,12 @@ decl_module! {
 			let proposal_state = Self::proposal_states(&proposal_id);
 			ensure!(proposal_state == ProposalState::Active, Error::<T>::ProposalEnded);
 
+			// Ensure the voter is eligible
+			ensure!(
+				<T as control::Config>::Sense::score(&sender) >= T::MinScoreToVote::get(),
+				Error::<T>::ScoreTooLow
+			);
+
 			// Ensure the contributor did not vote before
 			ensure!(!<VotedBefore<T>>::get((sender.clone(), proposal_id.clone())), Error::<T>::AlreadyVoted);
 
@@ -505,142 +850,234 @@ decl_module! {
 			// Ensure the proposal is not expired
 			ensure!(<system::Module<T>>::block_number() < proposal.expiry, Error::<T>::ProposalExpired);
 
-			// TODO:
-			// ensure origin is one of:
-			// a. member when the proposal is general
-			// b. contributor when the proposal is a withdrawal request
-			// let sender_balance = <campaign::Module<T>>::campaign_contribution(proposal.campaign_id, sender.clone());
-			// ensure!( sender_balance > T::Balance::from(0), "You are not a contributor of this Campaign");
-
-			match &proposal.proposal_type {
-				// DAO Democratic Proposal
-				// simply one member one vote yes / no,
-				// TODO: ratio definable, now > 50% majority wins
-				ProposalType::General => {
-
-					let (mut yes, mut no) = Self::proposal_simple_votes(&proposal_id);
-
-					match vote {
-						true => {
-							yes = yes.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							let proposal_approvers = Self::proposal_approvers(&proposal_id);
-							let updated_proposal_approvers = proposal_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							ProposalApprovers::<T>::insert(
-								proposal_id.clone(),
-								updated_proposal_approvers.clone()
-							);
-						},
-						false => {
-							no = no.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							let proposal_deniers = Self::proposal_deniers(&proposal_id);
-							let updated_proposal_deniers = proposal_deniers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							ProposalDeniers::<T>::insert(
-								proposal_id.clone(),
-								updated_proposal_deniers.clone()
-							);
-						}
-					}
+			// Multiple choice proposals are voted by ranking
+			ensure!(proposal.proposal_type != ProposalType::Multiple, Error::<T>::NotMultipleChoice);
 
-					ProposalSimpleVotes::<T>::insert(
-						proposal_id.clone(),
-						(yes,no)
-					);
+			// Milestones are decided by the campaign contributors
+			if proposal.proposal_type == ProposalType::Milestone {
+				let contribution = flow::Module::<T>::campaign_contribution((&proposal.context_id, &sender));
+				ensure!( contribution > T::Balance::zero(), Error::<T>::AuthorizationError );
+			}
 
-				},
-				// 50% majority over total number of campaign contributors
-				ProposalType::Withdrawal => {
-
-					let (mut yes, mut no) = Self::proposal_simple_votes(&proposal_id);
-
-					match vote {
-						true => {
-							yes = yes.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-
-							let current_approvers = Self::proposal_approvers(&proposal_id);
-							let updated_approvers = current_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							ProposalApprovers::<T>::insert(proposal_id.clone(), updated_approvers.clone());
-
-							// TODO: make this variable
-							let contributors = flow::Module::<T>::campaign_contributors_count(proposal.context_id);
-							let threshold = contributors.checked_div(2).ok_or(Error::<T>::DivisionError)?;
-							if updated_approvers > threshold {
-								Self::unlock_balance(proposal_id, updated_approvers)?;
-							}
-							// remove
-							let proposal_approvers = Self::proposal_approvers(&proposal_id);
-							let updated_proposal_approvers = proposal_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							ProposalApprovers::<T>::insert(
-								proposal_id.clone(),
-								updated_proposal_approvers.clone()
-							);
-
-						},
-						false => {
-							no = no.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							// remove
-							let proposal_deniers = Self::proposal_deniers(&proposal_id);
-							let updated_proposal_deniers = proposal_deniers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
-							ProposalDeniers::<T>::insert(
-								proposal_id.clone(),
-								updated_proposal_deniers.clone()
-							);
-						}
-					}
+			// Judgements are decided by the members of the body
+			if proposal.proposal_type == ProposalType::Judgement {
+				ensure!( <control::Module<T>>::is_active_member(&proposal.context_id,&sender), Error::<T>::AuthorizationError );
+			}
 
-					ProposalSimpleVotes::<T>::insert(
-						proposal_id.clone(),
-						(yes,no)
-					);
+			// Secret ballots are committed and revealed instead
+			ensure!(Self::reveal_period(&proposal_id).is_none(), Error::<T>::SecretBallot);
 
+			Self::record_simple_vote( sender, proposal_id, proposal, vote )
 
-				},
+		}
 
-				// Campaign Token Weighted Proposal
-				// total token balance yes vs no
-				// TODO: ratio definable, now > 50% majority wins
-				// ProposalType:: => {
-				// },
-
-				// Membership Voting
-				// simply one token one vote yes / no,
-				// TODO: ratio definable, now simple majority wins
-				ProposalType::Member => {
-					// approve
-					// deny
-					// kick
-					// ban
-				},
-				// default
-				_ => {
-				},
-			}
+		//	vote on a multiple choice proposal
+		//	ranking: option indexes by preference,
+		//	exactly one option for plurality proposals
 
-			VotedBefore::<T>::insert( ( &sender, proposal_id.clone() ), true );
-			ProposalsByVoterCount::<T>::mutate( &sender, |v| *v +=1 );
-			ProposalVotesByVoters::<T>::mutate(&proposal_id, |votings| votings.push(( sender.clone(), vote.clone() )) );
-			ProposalsByVoter::<T>::mutate( &sender, |votings| votings.push((proposal_id.clone(), vote)));
-
-			let mut voters = ProposalVoters::<T>::get(&proposal_id);
-			match voters.binary_search(&sender) {
-				Ok(_) => {}, // should never happen
-				Err(index) => {
-					voters.insert(index, sender.clone());
-					ProposalVoters::<T>::insert( &proposal_id, voters );
-				}
-			}
+		#[weight = 5_000_000]
+		fn choice_vote(
+			origin,
+			proposal_id: T::Hash,
+			ranking: Vec<u8>
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Proposals<T>>::contains_key(&proposal_id), Error::<T>::ProposalUnknown);
+			ensure!(Self::proposal_states(&proposal_id) == ProposalState::Active, Error::<T>::ProposalEnded);
+
+			let proposal = Self::proposals(&proposal_id);
+			ensure!(proposal.proposal_type == ProposalType::Multiple, Error::<T>::NotMultipleChoice);
+			ensure!(<system::Module<T>>::block_number() < proposal.expiry, Error::<T>::ProposalExpired);
+			ensure!( <control::Module<T>>::is_active_member(&proposal.context_id,&sender), Error::<T>::AuthorizationError );
+			ensure!(
+				<T as control::Config>::Sense::score(&sender) >= T::MinScoreToVote::get(),
+				Error::<T>::ScoreTooLow
+			);
+			ensure!(!<VotedBefore<T>>::get((sender.clone(), proposal_id.clone())), Error::<T>::AlreadyVoted);
+			ensure!(Self::reveal_period(&proposal_id).is_none(), Error::<T>::SecretBallot);
+
+			Self::cast_ballot( &proposal_id, ranking )?;
+			Self::note_voter( &sender, &proposal_id );
 
-			// dispatch vote event
 			Self::deposit_event(
-				RawEvent::ProposalVoted(
+				RawEvent::ChoiceVoted(
 					sender,
-					proposal_id.clone(),
-					vote
+					proposal_id
 				)
 			);
 			Ok(())
 
 		}
 
+		//	make a proposal commit reveal before any vote
+		//	votes are committed until expiry and revealed in the
+		//	following window, the proposal is finalized after it
+
+		#[weight = 1_000_000]
+		fn set_commit_reveal(
+			origin,
+			proposal_id: T::Hash,
+			reveal: T::BlockNumber
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Proposals<T>>::contains_key(&proposal_id), Error::<T>::ProposalUnknown);
+			ensure!(Self::owners(&proposal_id) == Some(sender), Error::<T>::AuthorizationError);
+			ensure!(Self::proposal_states(&proposal_id) == ProposalState::Active, Error::<T>::ProposalEnded);
+			ensure!(Self::reveal_period(&proposal_id).is_none(), Error::<T>::SecretBallot);
+
+			// decisions bound to other deadlines can not wait for reveals
+			let proposal = Self::proposals(&proposal_id);
+			ensure!(
+				matches!(proposal.proposal_type, ProposalType::General | ProposalType::Multiple),
+				Error::<T>::SecretNotSupported
+			);
+			ensure!(
+				Self::proposal_voters(&proposal_id).is_empty() && Self::ballot_count(&proposal_id) == 0,
+				Error::<T>::AlreadyVoted
+			);
+			ensure!(reveal > Zero::zero() && reveal <= T::MaxRevealPeriod::get(), Error::<T>::OutOfBounds);
+
+			// finalize after the reveal window
+			let end = proposal.expiry + reveal;
+			let proposals = Self::proposals_by_block(end);
+			ensure!(proposals.len() < MAX_PROPOSALS_PER_BLOCK, Error::<T>::TooManyProposals );
+
+			ProposalsByBlock::<T>::mutate(proposal.expiry, |proposals| proposals.retain(|p| p != &proposal_id));
+			ProposalsByBlock::<T>::mutate(end, |proposals| proposals.push(proposal_id.clone()));
+			RevealPeriod::<T>::insert( &proposal_id, reveal );
+
+			Self::deposit_event( RawEvent::CommitRevealSet( proposal_id, end ) );
+			Ok(())
+
+		}
+
+		//	commit to a vote of a commit reveal proposal
+		//	commitment: hash of (proposal, voter, vote, salt)
+
+		#[weight = 5_000_000]
+		fn commit_vote(
+			origin,
+			proposal_id: T::Hash,
+			commitment: T::Hash
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Proposals<T>>::contains_key(&proposal_id), Error::<T>::ProposalUnknown);
+			ensure!(Self::proposal_states(&proposal_id) == ProposalState::Active, Error::<T>::ProposalEnded);
+			ensure!(Self::reveal_period(&proposal_id).is_some(), Error::<T>::NotSecretBallot);
+
+			let proposal = Self::proposals(&proposal_id);
+			ensure!(<system::Module<T>>::block_number() < proposal.expiry, Error::<T>::ProposalExpired);
+			if proposal.proposal_type == ProposalType::Multiple || proposal.proposal_type == ProposalType::Judgement {
+				ensure!( <control::Module<T>>::is_active_member(&proposal.context_id,&sender), Error::<T>::AuthorizationError );
+			}
+			ensure!(
+				<T as control::Config>::Sense::score(&sender) >= T::MinScoreToVote::get(),
+				Error::<T>::ScoreTooLow
+			);
+			ensure!(!<VotedBefore<T>>::get((sender.clone(), proposal_id.clone())), Error::<T>::AlreadyVoted);
+			ensure!(!Commits::<T>::contains_key((&proposal_id, &sender)), Error::<T>::AlreadyVoted);
+
+			let count = Self::commit_count(&proposal_id).checked_add(1).ok_or(Error::<T>::OverflowError)?;
+			let deposit = T::CommitDeposit::get();
+			<T as Config>::Currency::reserve(&sender, deposit)?;
+			Commits::<T>::insert( (&proposal_id, &sender), (commitment, deposit) );
+			CommitCount::<T>::insert( &proposal_id, count );
+
+			Self::deposit_event( RawEvent::VoteCommitted( sender, proposal_id ) );
+			Ok(())
+
+		}
+
+		//	reveal a committed vote in the reveal window,
+		//	the vote is counted and the deposit returned
+
+		#[weight = 5_000_000]
+		#[transactional]
+		fn reveal_vote(
+			origin,
+			proposal_id: T::Hash,
+			vote: SecretVote,
+			salt: T::Hash
+		) -> DispatchResult {
+
+			let sender = ensure_signed(origin)?;
+
+			let reveal = Self::reveal_period(&proposal_id).ok_or(Error::<T>::NotSecretBallot)?;
+			ensure!(Self::proposal_states(&proposal_id) == ProposalState::Active, Error::<T>::ProposalEnded);
+
+			let proposal = Self::proposals(&proposal_id);
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= proposal.expiry, Error::<T>::RevealNotStarted);
+			ensure!(now < proposal.expiry + reveal, Error::<T>::RevealEnded);
+
+			let (commitment, deposit) = Self::commit((&proposal_id, &sender)).ok_or(Error::<T>::CommitUnknown)?;
+			ensure!(T::Hashing::hash_of(&(&proposal_id, &sender, &vote, &salt)) == commitment, Error::<T>::CommitMismatch);
+
+			Commits::<T>::remove( (&proposal_id, &sender) );
+			CommitCount::<T>::mutate( &proposal_id, |count| *count = count.saturating_sub(1) );
+			<T as Config>::Currency::unreserve(&sender, deposit);
+
+			let proposal_type = proposal.proposal_type.clone();
+			match (proposal_type, vote) {
+				(ProposalType::General, SecretVote::Simple(vote)) => {
+					Self::record_simple_vote( sender, proposal_id, proposal, vote )
+				},
+				(ProposalType::Multiple, SecretVote::Choice(ranking)) => {
+					Self::cast_ballot( &proposal_id, ranking )?;
+					Self::note_voter( &sender, &proposal_id );
+					Self::deposit_event( RawEvent::ChoiceVoted( sender, proposal_id ) );
+					Ok(())
+				},
+				_ => Err(Error::<T>::InvalidBallot.into()),
+			}
+
+		}
+
+		//	forfeit the deposit of a commitment not revealed
+		//	in time into the treasury of the body
+
+		#[weight = 1_000_000]
+		fn forfeit_commit(
+			origin,
+			proposal_id: T::Hash,
+			voter: T::AccountId
+		) -> DispatchResult {
+
+			let _ = ensure_signed(origin)?;
+
+			let reveal = Self::reveal_period(&proposal_id).ok_or(Error::<T>::NotSecretBallot)?;
+			let proposal = Self::proposals(&proposal_id);
+			ensure!(<system::Module<T>>::block_number() >= proposal.expiry + reveal, Error::<T>::RevealNotEnded);
+
+			let (_, deposit) = Self::commit((&proposal_id, &voter)).ok_or(Error::<T>::CommitUnknown)?;
+			let treasury = <control::Module<T>>::body_treasury(&proposal.context_id);
+			<T as Config>::Currency::repatriate_reserved(&voter, &treasury, deposit, BalanceStatus::Free)?;
+
+			Commits::<T>::remove( (&proposal_id, &voter) );
+			CommitCount::<T>::mutate( &proposal_id, |count| *count = count.saturating_sub(1) );
+
+			Self::deposit_event( RawEvent::CommitForfeited( proposal_id, voter ) );
+			Ok(())
+
+		}
+
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			Self::count_runoffs( T::MaxRunoffBallots::get() )
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			match Self::storage_version() {
+				Releases::V0 => Self::migrate_to_v1(),
+				_ => 0,
+			}
+		}
+
 		fn on_finalize() {
 
 			// i'm still jenny from the block
@@ -689,6 +1126,50 @@ decl_module! {
 							Err(err) => {  }
 						}
 					},
+					ProposalType::Judgement => {
+						// simple vote, decides the judgement
+						// without votes it stays open for governance
+						let (yes,no) = Self::proposal_simple_votes(&proposal_id);
+						let accept = yes > no;
+						if yes > no { proposal_state = ProposalState::Accepted; }
+						if yes <= no { proposal_state = ProposalState::Rejected; }
+						if yes == 0 && no == 0 {
+							proposal_state = ProposalState::Expired;
+						} else if let Some(judgement) = Self::proposal_judgement(&proposal_id) {
+							// judgement may have been resolved by governance in the meantime
+							let _ = sense::Module::<T>::do_resolve_judgement( judgement, accept );
+						}
+					},
+					ProposalType::Milestone => {
+						// simple vote of contributors
+						// without votes the milestone can be proposed again
+						let (yes,no) = Self::proposal_simple_votes(&proposal_id);
+						let decision = if yes == 0 && no == 0 { None } else { Some(yes > no) };
+						proposal_state = match decision {
+							Some(true) => ProposalState::Accepted,
+							Some(false) => ProposalState::Rejected,
+							None => ProposalState::Expired,
+						};
+						// milestone may have failed on its deadline in the meantime
+						let _ = flow::Module::<T>::resolve_milestone( &proposal.context_id, decision );
+					},
+					ProposalType::Multiple => {
+						// instant runoffs are counted in rounds over the next blocks
+						let ranked = Self::choice_method(&proposal_id) == ChoiceMethod::InstantRunoff;
+						if ranked && Self::ballot_count(&proposal_id) > 0 {
+							let options = Self::proposal_options(&proposal_id).len();
+							Runoffs::<T>::insert( &proposal_id, RunoffTally {
+								counts: vec![0; options],
+								remaining: vec![true; options],
+								cursor: 0,
+							});
+							PendingRunoffs::<T>::mutate( |pending| pending.push(proposal_id.clone()) );
+							proposal_state = ProposalState::Tallying;
+						} else {
+							let winner = Self::leader( &Self::choice_votes(&proposal_id) );
+							proposal_state = Self::decide( &proposal_id, winner );
+						}
+					},
 					ProposalType::Member => {
 						// membership
 						//
@@ -735,6 +1216,403 @@ decl_module! {
 
 impl<T:Config> Module<T> {
 
+	/// body deciding a proposal,
+	/// the organisation of the campaign for withdrawals and milestones
+	pub fn proposal_body( proposal_id: &T::Hash ) -> Option<T::Hash> {
+		if !Proposals::<T>::contains_key(proposal_id) { return None }
+		let proposal = Self::proposals(proposal_id);
+		match proposal.proposal_type {
+			ProposalType::Withdrawal | ProposalType::Milestone =>
+				Some( flow::Module::<T>::campaign_org(&proposal.context_id) ),
+			_ => Some( proposal.context_id ),
+		}
+	}
+
+	// layout is unchanged, only the version is recorded
+	fn migrate_to_v1() -> Weight {
+		StorageVersion::put(Releases::V1);
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	/// storage invariants, checked against state snapshots after migrations
+	#[cfg(feature = "std")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for (proposal_id, _) in Proposals::<T>::iter() {
+			let voters = Self::proposal_voters(&proposal_id);
+			let votes = Self::proposal_votes_by_voters(&proposal_id).len() + Self::ballot_count(&proposal_id) as usize;
+			if voters.len() != votes {
+				return Err("voter list does not match votes")
+			}
+			for voter in &voters {
+				if !Self::has_voted(( voter.clone(), proposal_id.clone() )) {
+					return Err("voter without vote record")
+				}
+			}
+			if Self::proposals_by_index(ProposalsIndex::<T>::get(&proposal_id)) != proposal_id {
+				return Err("proposal index does not resolve to proposal")
+			}
+			if Self::commit_count(&proposal_id) > 0 && Self::reveal_period(&proposal_id).is_none() {
+				return Err("commitments on a public proposal")
+			}
+		}
+		for proposal_id in Self::pending_runoffs() {
+			if Self::proposal_states(&proposal_id) != ProposalState::Tallying || Self::runoff(&proposal_id).is_none() {
+				return Err("pending runoff is not tallying")
+			}
+		}
+		if Self::storage_version() != Releases::V1 {
+			return Err("storage version not migrated")
+		}
+		Ok(())
+	}
+
+	// write a new proposal and update all indexes
+	fn insert_proposal(
+		sender: &T::AccountId,
+		proposal: Proposal<T::Hash, T::BlockNumber, ProposalType, VotingType>,
+		metadata: ProposalMetadata<T::Balance>,
+	) -> DispatchResult {
+
+		let proposal_id = proposal.proposal_id.clone();
+		let context_id = proposal.context_id.clone();
+		let expiry = proposal.expiry.clone();
+
+		//	C O U N T S
+
+		let proposals_count = Self::proposals_count();
+		let updated_proposals_count = proposals_count.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+		let proposals_by_campaign_count = Self::proposals_by_campaign_count(&context_id);
+		let updated_proposals_by_campaign_count = proposals_by_campaign_count.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+		let proposals_by_owner_count = Self::proposals_by_owner_count(sender);
+		let updated_proposals_by_owner_count = proposals_by_owner_count.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+
+		//	W R I T E
+
+		Proposals::<T>::insert(&proposal_id, proposal);
+		Metadata::<T>::insert(&proposal_id, metadata);
+		Owners::<T>::insert(&proposal_id, sender.clone());
+		ProposalStates::<T>::insert(&proposal_id, ProposalState::Active);
+
+		ProposalsByBlock::<T>::mutate(expiry, |proposals| proposals.push(proposal_id.clone()));
+		ProposalsArray::<T>::insert(&proposals_count, proposal_id.clone());
+		ProposalsCount::put(updated_proposals_count);
+		ProposalsIndex::<T>::insert(proposal_id.clone(), proposals_count);
+		ProposalsByContextArray::<T>::insert((context_id.clone(), proposals_by_campaign_count), proposal_id.clone());
+		ProposalsByContextCount::<T>::insert(context_id.clone(), updated_proposals_by_campaign_count);
+		ProposalsByContextIndex::<T>::insert((context_id.clone(), proposal_id.clone()), proposals_by_campaign_count);
+		ProposalsByContext::<T>::mutate(context_id.clone(), |proposals| proposals.push(proposal_id.clone()));
+		ProposalsByOwnerArray::<T>::insert((sender.clone(), proposals_by_owner_count), proposal_id.clone());
+		ProposalsByOwnerCount::<T>::insert(sender.clone(), updated_proposals_by_owner_count);
+		ProposalsByOwnerIndex::<T>::insert((sender.clone(), proposal_id.clone()), proposals_by_owner_count);
+
+		// ++
+
+		Nonce::mutate(|n| *n += 1);
+
+		Ok(())
+
+	}
+
+	// count a yes / no vote and record the voter
+	fn record_simple_vote(
+		sender: T::AccountId,
+		proposal_id: T::Hash,
+		proposal: Proposal<T::Hash, T::BlockNumber, ProposalType, VotingType>,
+		vote: bool
+	) -> DispatchResult {
+
+		// TODO:
+		// ensure origin is one of:
+		// a. member when the proposal is general
+		// b. contributor when the proposal is a withdrawal request
+		// let sender_balance = <campaign::Module<T>>::campaign_contribution(proposal.campaign_id, sender.clone());
+		// ensure!( sender_balance > T::Balance::from(0), "You are not a contributor of this Campaign");
+
+		match &proposal.proposal_type {
+			// DAO Democratic Proposal
+			// simply one member one vote yes / no,
+			// TODO: ratio definable, now > 50% majority wins
+			ProposalType::General | ProposalType::Judgement | ProposalType::Milestone => {
+
+				let (mut yes, mut no) = Self::proposal_simple_votes(&proposal_id);
+
+				match vote {
+					true => {
+						yes = yes.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						let proposal_approvers = Self::proposal_approvers(&proposal_id);
+						let updated_proposal_approvers = proposal_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						ProposalApprovers::<T>::insert(
+							proposal_id.clone(),
+							updated_proposal_approvers.clone()
+						);
+					},
+					false => {
+						no = no.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						let proposal_deniers = Self::proposal_deniers(&proposal_id);
+						let updated_proposal_deniers = proposal_deniers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						ProposalDeniers::<T>::insert(
+							proposal_id.clone(),
+							updated_proposal_deniers.clone()
+						);
+					}
+				}
+
+				ProposalSimpleVotes::<T>::insert(
+					proposal_id.clone(),
+					(yes,no)
+				);
+
+			},
+			// 50% majority over total number of campaign contributors
+			ProposalType::Withdrawal => {
+
+				let (mut yes, mut no) = Self::proposal_simple_votes(&proposal_id);
+
+				match vote {
+					true => {
+						yes = yes.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+
+						let current_approvers = Self::proposal_approvers(&proposal_id);
+						let updated_approvers = current_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						ProposalApprovers::<T>::insert(proposal_id.clone(), updated_approvers.clone());
+
+						// TODO: make this variable
+						let contributors = flow::Module::<T>::campaign_contributors_count(proposal.context_id);
+						let threshold = contributors.checked_div(2).ok_or(Error::<T>::DivisionError)?;
+						if updated_approvers > threshold {
+							Self::unlock_balance(proposal_id, updated_approvers)?;
+						}
+						// remove
+						let proposal_approvers = Self::proposal_approvers(&proposal_id);
+						let updated_proposal_approvers = proposal_approvers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						ProposalApprovers::<T>::insert(
+							proposal_id.clone(),
+							updated_proposal_approvers.clone()
+						);
+
+					},
+					false => {
+						no = no.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						// remove
+						let proposal_deniers = Self::proposal_deniers(&proposal_id);
+						let updated_proposal_deniers = proposal_deniers.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+						ProposalDeniers::<T>::insert(
+							proposal_id.clone(),
+							updated_proposal_deniers.clone()
+						);
+					}
+				}
+
+				ProposalSimpleVotes::<T>::insert(
+					proposal_id.clone(),
+					(yes,no)
+				);
+
+
+			},
+
+			// Campaign Token Weighted Proposal
+			// total token balance yes vs no
+			// TODO: ratio definable, now > 50% majority wins
+			// ProposalType:: => {
+			// },
+
+			// Membership Voting
+			// simply one token one vote yes / no,
+			// TODO: ratio definable, now simple majority wins
+			ProposalType::Member => {
+				// approve
+				// deny
+				// kick
+				// ban
+			},
+			// default
+			_ => {
+			},
+		}
+
+		VotedBefore::<T>::insert( ( &sender, proposal_id.clone() ), true );
+		ProposalsByVoterCount::<T>::mutate( &sender, |v| *v +=1 );
+		ProposalVotesByVoters::<T>::mutate(&proposal_id, |votings| votings.push(( sender.clone(), vote.clone() )) );
+		ProposalsByVoter::<T>::mutate( &sender, |votings| votings.push((proposal_id.clone(), vote)));
+		<T as Config>::SenseHooks::on_action( SenseAction::Vote, &sender );
+
+		let mut voters = ProposalVoters::<T>::get(&proposal_id);
+		match voters.binary_search(&sender) {
+			Ok(_) => {}, // should never happen
+			Err(index) => {
+				voters.insert(index, sender.clone());
+				ProposalVoters::<T>::insert( &proposal_id, voters );
+			}
+		}
+
+		// dispatch vote event
+		Self::deposit_event(
+			RawEvent::ProposalVoted(
+				sender,
+				proposal_id.clone(),
+				vote
+			)
+		);
+		Ok(())
+
+	}
+
+	// validate and store a ballot, plurality and borda are tallied right away
+	fn cast_ballot( proposal_id: &T::Hash, ranking: Vec<u8> ) -> DispatchResult {
+
+		let options = Self::proposal_options(proposal_id).len();
+		let method = Self::choice_method(proposal_id);
+
+		ensure!( !ranking.is_empty() && ranking.len() <= options, Error::<T>::InvalidBallot );
+		ensure!( method != ChoiceMethod::Plurality || ranking.len() == 1, Error::<T>::InvalidBallot );
+		for (rank, option) in ranking.iter().enumerate() {
+			ensure!( (*option as usize) < options, Error::<T>::InvalidBallot );
+			ensure!( !ranking[..rank].contains(option), Error::<T>::InvalidBallot );
+		}
+
+		match method {
+			ChoiceMethod::Plurality | ChoiceMethod::Borda => {
+				let mut votes = Self::choice_votes(proposal_id);
+				for (rank, option) in ranking.iter().enumerate() {
+					// an option ranked first scores one point per option ranked below it
+					let points = match method {
+						ChoiceMethod::Plurality => 1,
+						_ => (options - 1 - rank) as u64,
+					};
+					let total = &mut votes[*option as usize];
+					*total = total.checked_add(points).ok_or(Error::<T>::OverflowError)?;
+				}
+				ProposalChoiceVotes::<T>::insert( proposal_id, votes );
+			},
+			ChoiceMethod::InstantRunoff => {},
+		}
+
+		let index = Self::ballot_count(proposal_id);
+		let next = index.checked_add(1).ok_or(Error::<T>::OverflowError)?;
+		Ballots::<T>::insert( (proposal_id, index), ranking );
+		BallotCount::<T>::insert( proposal_id, next );
+		Ok(())
+
+	}
+
+	// record a voter who cast a ballot
+	fn note_voter( sender: &T::AccountId, proposal_id: &T::Hash ) {
+		VotedBefore::<T>::insert( ( sender, proposal_id.clone() ), true );
+		ProposalsByVoterCount::<T>::mutate( sender, |v| *v +=1 );
+		<T as Config>::SenseHooks::on_action( SenseAction::Vote, sender );
+		ProposalVoters::<T>::mutate( proposal_id, |voters| {
+			if let Err(index) = voters.binary_search(sender) {
+				voters.insert(index, sender.clone());
+			}
+		});
+	}
+
+	// option with the most votes, ties go to the earlier option
+	fn leader( votes: &[u64] ) -> Option<u8> {
+		let mut leader: Option<(usize, u64)> = None;
+		for (option, count) in votes.iter().enumerate() {
+			if *count > leader.map_or(0, |(_, c)| c) {
+				leader = Some((option, *count));
+			}
+		}
+		leader.map(|(option, _)| option as u8)
+	}
+
+	// record the winner of a multiple choice proposal
+	fn decide( proposal_id: &T::Hash, winner: Option<u8> ) -> ProposalState {
+		match winner {
+			Some(option) => {
+				ProposalWinner::<T>::insert( proposal_id, option );
+				Self::deposit_event( RawEvent::ChoiceWinner( proposal_id.clone(), option ) );
+				ProposalState::Accepted
+			},
+			None => ProposalState::Expired,
+		}
+	}
+
+	// count up to `budget` ranked ballots of pending instant runoffs
+	fn count_runoffs( budget: u32 ) -> Weight {
+
+		let mut pending = Self::pending_runoffs();
+		if pending.is_empty() { return T::DbWeight::get().reads(1) }
+
+		let mut used: u32 = 0;
+		let mut writes: u64 = 1;
+		while used < budget && !pending.is_empty() {
+
+			let proposal_id = pending[0].clone();
+			let mut tally = match Self::runoff(&proposal_id) {
+				Some(tally) => tally,
+				None => { pending.remove(0); continue },
+			};
+			let count = Self::ballot_count(&proposal_id);
+
+			// every ballot counts for its highest ranked remaining option
+			while tally.cursor < count && used < budget {
+				let ballot = Self::ballot((&proposal_id, tally.cursor));
+				let choice = ballot.iter().find(|o| tally.remaining.get(**o as usize) == Some(&true));
+				if let Some(option) = choice {
+					tally.counts[*option as usize] = tally.counts[*option as usize].saturating_add(1);
+				}
+				tally.cursor += 1;
+				used += 1;
+			}
+			writes += 1;
+			if tally.cursor < count {
+				Runoffs::<T>::insert( &proposal_id, tally );
+				break
+			}
+
+			// closing a round uses budget too, rounds are bounded by the options
+			used += 1;
+			match Self::runoff_round(&mut tally) {
+				Some(winner) => {
+					let state = Self::decide( &proposal_id, winner );
+					ProposalStates::<T>::insert( &proposal_id, state.clone() );
+					match state {
+						ProposalState::Accepted => Self::deposit_event( RawEvent::ProposalApproved(proposal_id.clone()) ),
+						_ => Self::deposit_event( RawEvent::ProposalExpired(proposal_id.clone()) ),
+					}
+					Runoffs::<T>::remove( &proposal_id );
+					pending.remove(0);
+					writes += 2;
+				},
+				None => Runoffs::<T>::insert( &proposal_id, tally ),
+			}
+		}
+		PendingRunoffs::<T>::put( pending );
+
+		T::DbWeight::get().reads_writes( used as u64 + 1, writes )
+
+	}
+
+	// close a counted round: a majority wins, else the weakest
+	// option is eliminated, ties eliminate the later option
+	fn runoff_round( tally: &mut RunoffTally ) -> Option<Option<u8>> {
+
+		// exhausted ballots no longer count
+		let active = tally.counts.iter().fold(0u64, |total, c| total.saturating_add(*c));
+		let leader = match Self::leader(&tally.counts) {
+			Some(leader) => leader,
+			None => return Some(None),
+		};
+		if tally.counts[leader as usize].saturating_mul(2) > active { return Some(Some(leader)) }
+
+		let weakest = (0..tally.remaining.len()).rev()
+			.filter(|o| tally.remaining[*o])
+			.min_by_key(|o| tally.counts[*o]);
+		if let Some(option) = weakest { tally.remaining[option] = false; }
+
+		let remaining: Vec<usize> = (0..tally.remaining.len()).filter(|o| tally.remaining[*o]).collect();
+		if remaining.len() == 1 { return Some(Some(remaining[0] as u8)) }
+
+		tally.counts = vec![0; tally.remaining.len()];
+		tally.cursor = 0;
+		None
+
+	}
+
 	// TODO: DISCUSSION
 	// withdrawal proposals are accepted
 	// when the number of approvals is higher
@@ -764,6 +1642,7 @@ impl<T:Config> Module<T> {
 		// get treasury account for related body and unlock balance
 		let body = flow::Module::<T>::campaign_org(&proposal.context_id);
 		let treasury_account = control::Module::<T>::body_treasury(&body);
+		control::Module::<T>::spend(&body, proposal_balance)?;
 		let _ = <balances::Module<T>>::unreserve(&treasury_account, proposal_balance);
 
 		// Change the used amount
@@ -809,6 +1688,16 @@ decl_event!(
 		ProposalAborted(Hash),
 		ProposalError(Hash, Vec<u8>),
 		WithdrawalGranted(Hash,Hash,Hash),
+		/// voter, proposal
+		ChoiceVoted(AccountId, Hash),
+		/// proposal, winning option
+		ChoiceWinner(Hash, u8),
+		/// proposal, end of the reveal window
+		CommitRevealSet(Hash, BlockNumber),
+		/// voter, proposal
+		VoteCommitted(AccountId, Hash),
+		/// proposal, voter
+		CommitForfeited(Hash, AccountId),
 	}
 );
 
@@ -855,5 +1744,37 @@ decl_error! {
 		OverflowError,
 		/// Division Error
 		DivisionError,
+		/// Sense Score Too Low
+		ScoreTooLow,
+		/// Unknown Judgement
+		JudgementUnknown,
+		/// Unknown Milestone
+		MilestoneUnknown,
+		/// Campaign declared milestones
+		MilestonesDeclared,
+		/// Less than two options
+		TooFewOptions,
+		/// Too many options
+		TooManyOptions,
+		/// Not a multiple choice proposal
+		NotMultipleChoice,
+		/// Ballot ranks unknown or repeated options
+		InvalidBallot,
+		/// Votes are committed and revealed
+		SecretBallot,
+		/// Votes are public
+		NotSecretBallot,
+		/// Proposal type can not be commit reveal
+		SecretNotSupported,
+		/// Unknown Commitment
+		CommitUnknown,
+		/// Vote and salt do not match the commitment
+		CommitMismatch,
+		/// Reveal window not started
+		RevealNotStarted,
+		/// Reveal window ended
+		RevealEnded,
+		/// Reveal window not ended
+		RevealNotEnded,
 	}
 }
@@ -1,415 +1,497 @@
 use super::*;
-
-use frame_support::{
-	assert_noop, assert_ok, impl_outer_origin, parameter_types,
-	traits::{OnFinalize, OnInitialize},
-};
-use sp_core::H256;
-// The testing primitives are very useful for avoiding having to work with signatures
-// or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
-use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-	Perbill, Percent, Permill,
-};
-
-impl_outer_origin! {
-	pub enum Origin for Test {}
+use crate::mock::*;
+use flow::{ Milestone, MilestoneState };
+use frame_support::{ assert_noop, assert_ok, migration::put_storage_value, traits::OnRuntimeUpgrade };
+use primitives::SenseProps;
+use sp_runtime::traits::BlakeTwo256;
+
+// id of the latest proposal
+fn last_proposal() -> H256 {
+	Signal::proposals_by_index(Signal::proposals_count() - 1)
 }
 
-// For testing the module, we construct most of a mock runtime. This means
-// first constructing a configuration type (`Test`) which `impl`s each of the
-// configuration traits of modules we want to use.
-#[derive(Clone, Eq, PartialEq)]
-pub struct Test;
-parameter_types! {
-	pub const BlockHashCount: u32 = 250;
-	pub const MaximumBlockWeight: u32 = 4 * 1024 * 1024;
-	pub const MaximumBlockLength: u32 = 4 * 1024 * 1024;
-	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-}
-impl system::Trait for Test {
-	type BaseCallFilter = ();
-	type Origin = Origin;
-	type Index = u64;
-	type Call = ();
-	type BlockNumber = u64;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = u64;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = ();
-	type BlockHashCount = BlockHashCount;
-	type MaximumBlockWeight = MaximumBlockWeight;
-	type DbWeight = ();
-	type BlockExecutionWeight = ();
-	type ExtrinsicBaseWeight = ();
-	type MaximumExtrinsicWeight = MaximumBlockWeight;
-	type MaximumBlockLength = MaximumBlockLength;
-	type AvailableBlockRatio = AvailableBlockRatio;
-	type Version = ();
-	type ModuleToIndex = ();
-	type AccountData = balances::AccountData<u64>;
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-}
-parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
-}
-impl balances::Trait for Test {
-	type Balance = u64;
-	type Event = ();
-	type DustRemoval = ();
-	type ExistentialDeposit = ExistentialDeposit;
-	type AccountStore = System;
-	type WeightInfo = ();
+// general proposal of alice ending at block 5
+fn general() -> H256 {
+	assert_ok!( Signal::general_proposal( Origin::signed(ALICE), org(), b"general".to_vec(), vec![], 1, 5 ) );
+	last_proposal()
 }
 
-parameter_types! {
-	pub const ProposalBond: Permill = Permill::from_percent(5);
-	pub const ProposalBondMinimum: u64 = 1;
-	pub const SpendPeriod: u64 = 2;
-	pub const Burn: Permill = Permill::from_percent(50);
-	pub const TipCountdown: u64 = 1;
-	pub const TipFindersFee: Percent = Percent::from_percent(20);
-	pub const TipReportDepositBase: u64 = 1;
-	pub const TipReportDepositPerByte: u64 = 1;
-	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+//
+//	score
+//
+
+#[test]
+fn votes_need_minimum_score() {
+	new_test_ext().execute_with(|| {
+		let id = general();
+		assert_noop!( Signal::simple_vote( Origin::signed(DAVE), id, true ), Error::<Test>::ScoreTooLow );
+
+		// the body treasury reports reputation
+		assert_ok!( Sense::report( Origin::signed(Control::treasury_account(0)), SenseProps::REP, DAVE, 30 ) );
+		assert_ok!( Signal::simple_vote( Origin::signed(DAVE), id, true ) );
+		assert_eq!( Signal::proposal_simple_votes(id), (1, 0) );
+		assert_ok!( Signal::check_invariants() );
+	});
 }
 
-parameter_types! {
-	pub const SubmissionDeposit: u64 = 1;
-	pub const MinContribution: u64 = 10;
-	pub const RetirementPeriod: u64 = 5;
+//
+//	judgements
+//
+
+// charlie asks for a reputation of 6 for dave
+fn open_judgement(property: SenseProps) -> sense::JudgementIndex {
+	let index = Sense::judgement_nonce();
+	assert_ok!( Sense::open_judgement( Origin::signed(CHARLIE), DAVE, property, 6, vec![] ) );
+	index
 }
-impl Trait for Test {
-	type Event = ();
-	type Currency = Balances;
-	type SubmissionDeposit = SubmissionDeposit;
-	type MinContribution = MinContribution;
-	type RetirementPeriod = RetirementPeriod;
+
+#[test]
+fn judgement_proposal_needs_a_body_reporter() {
+	new_test_ext().execute_with(|| {
+		let trust = open_judgement(SenseProps::TRUST);
+		let propose = |origin: u128, judgement: sense::JudgementIndex| Signal::judgement_proposal(
+			Origin::signed(origin), org(), judgement, b"judgement".to_vec(), vec![], 5,
+		);
+		assert_noop!( propose(ALICE, 7), Error::<Test>::JudgementUnknown );
+		assert_noop!( propose(DAVE, trust), Error::<Test>::AuthorizationError );
+		// the treasury reports reputation only
+		assert_noop!( propose(ALICE, trust), Error::<Test>::AuthorizationError );
+		assert_ok!( propose(ALICE, open_judgement(SenseProps::REP)) );
+	});
 }
 
-type System = system::Module<Test>;
-type Balances = balances::Module<Test>;
-type Crowdfund = Module<Test>;
-use balances::Error as BalancesError;
-
-// This function basically just builds a genesis storage key/value store according to
-// our desired mockup.
-fn new_test_ext() -> sp_io::TestExternalities {
-	let mut t = system::GenesisConfig::default()
-		.build_storage::<Test>()
-		.unwrap();
-	balances::GenesisConfig::<Test> {
-		balances: vec![(1, 1000), (2, 2000), (3, 3000), (4, 4000)],
-	}
-	.assimilate_storage(&mut t)
-	.unwrap();
-	t.into()
+#[test]
+fn accepted_judgement_proposal_applies_the_judgement() {
+	new_test_ext().execute_with(|| {
+		let index = open_judgement(SenseProps::REP);
+		assert_eq!( Balances::reserved_balance(CHARLIE), 50 );
+		assert_ok!( Signal::judgement_proposal( Origin::signed(ALICE), org(), index, b"judgement".to_vec(), vec![], 5 ) );
+		let id = last_proposal();
+		assert_eq!( Signal::proposal_judgement(id), Some(index) );
+
+		assert_ok!( Signal::simple_vote( Origin::signed(BOB), id, true ) );
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert!( Sense::judgement(index).is_none() );
+		assert_eq!( Sense::value(SenseProps::REP, &DAVE), 6 );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 0 );
+	});
 }
 
-fn run_to_block(n: u64) {
-	while System::block_number() < n {
-		Crowdfund::on_finalize(System::block_number());
-		Balances::on_finalize(System::block_number());
-		System::on_finalize(System::block_number());
-		System::set_block_number(System::block_number() + 1);
-		System::on_initialize(System::block_number());
-		Balances::on_initialize(System::block_number());
-		Crowdfund::on_initialize(System::block_number());
-	}
+#[test]
+fn judgement_is_voted_by_body_members() {
+	new_test_ext().execute_with(|| {
+		let index = open_judgement(SenseProps::REP);
+		assert_ok!( Signal::judgement_proposal( Origin::signed(ALICE), org(), index, b"judgement".to_vec(), vec![], 5 ) );
+		let id = last_proposal();
+
+		assert_ok!( Control::remove_member( Origin::signed(BOB), org(), BOB ) );
+		assert_noop!( Signal::simple_vote( Origin::signed(BOB), id, true ), Error::<Test>::AuthorizationError );
+		assert_ok!( Signal::simple_vote( Origin::signed(CHARLIE), id, true ) );
+	});
 }
 
 #[test]
-fn basic_setup_works() {
+fn rejected_judgement_proposal_slashes_the_deposit() {
 	new_test_ext().execute_with(|| {
-		assert_eq!(System::block_number(), 0);
-		assert_eq!(Crowdfund::fund_count(), 0);
-		assert_eq!(Crowdfund::funds(0), None);
-		assert_eq!(Crowdfund::contribution_get(0, &1), 0);
+		let index = open_judgement(SenseProps::REP);
+		assert_ok!( Signal::judgement_proposal( Origin::signed(ALICE), org(), index, b"judgement".to_vec(), vec![], 5 ) );
+		let id = last_proposal();
+
+		assert_ok!( Signal::simple_vote( Origin::signed(BOB), id, false ) );
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Rejected );
+		assert!( Sense::judgement(index).is_none() );
+		assert_eq!( Sense::value(SenseProps::REP, &DAVE), 0 );
+		assert_eq!( Balances::total_balance(&CHARLIE), 1_000 - 50 );
 	});
 }
 
 #[test]
-fn create_works() {
+fn judgement_without_votes_stays_open() {
 	new_test_ext().execute_with(|| {
-		// Now try to create a crowdfund campaign
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		assert_eq!(Crowdfund::fund_count(), 1);
-		// This is what the initial `fund_info` should look like
-		let fund_info = FundInfo {
-			beneficiary: 2,
-			deposit: 1,
-			raised: 0,
-			// 5 blocks length + 3 block ending period + 1 starting block
-			end: 9,
-			goal: 1000,
-		};
-		assert_eq!(Crowdfund::funds(0), Some(fund_info));
-		// User has deposit removed from their free balance
-		assert_eq!(Balances::free_balance(1), 999);
-		// Deposit is placed in crowdfund free balance
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 1);
+		let index = open_judgement(SenseProps::REP);
+		assert_ok!( Signal::judgement_proposal( Origin::signed(ALICE), org(), index, b"judgement".to_vec(), vec![], 5 ) );
+		let id = last_proposal();
+
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Expired );
+		assert!( Sense::judgement(index).is_some() );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 50 );
 	});
 }
 
+//
+//	milestones
+//
+
+fn milestone(amount: u64, deadline: u64) -> Milestone<u64, u64> {
+	Milestone { amount, cid: vec![], deadline }
+}
+
 #[test]
-fn create_handles_insufficient_balance() {
+fn milestone_proposal_is_bound_to_the_deadline() {
 	new_test_ext().execute_with(|| {
-		assert_noop!(
-			Crowdfund::create(Origin::signed(1337), 2, 1000, 9),
-			BalancesError::<Test, _>::InsufficientBalance
+		let id = create_funded_campaign(vec![ milestone(50, 20), milestone(30, 30) ]);
+		let propose = |origin: u128, expiry: u64| Signal::milestone_proposal(
+			Origin::signed(origin), id, b"milestone".to_vec(), vec![], expiry,
 		);
+		assert_noop!( propose(BOB, 15), Error::<Test>::AuthorizationError );
+		assert_noop!( propose(ALICE, 20), Error::<Test>::OutOfBounds );
+		assert_ok!( propose(ALICE, 15) );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Voting );
+		assert_eq!( Signal::metadata(last_proposal()).amount, 50 );
+
+		// the milestone is voted once
+		assert_noop!( propose(ALICE, 16), flow::Error::<Test>::MilestoneNotPending );
 	});
 }
 
 #[test]
-fn contribute_works() {
+fn contributors_release_milestones() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		assert_eq!(Balances::free_balance(1), 999);
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 1);
-
-		// No contributions yet
-		assert_eq!(Crowdfund::contribution_get(0, &1), 0);
-
-		// User 1 contributes to their own crowdfund
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 49));
-		// User 1 has spent some funds to do this, transfer fees **are** taken
-		assert_eq!(Balances::free_balance(1), 950);
-		// Contributions are stored in the trie
-		assert_eq!(Crowdfund::contribution_get(0, &1), 49);
-		// Contributions appear in free balance of crowdfund
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 50);
-		// Last contribution time recorded
-		assert_eq!(Crowdfund::funds(0).unwrap().raised, 49);
+		let id = create_funded_campaign(vec![ milestone(50, 20), milestone(30, 30) ]);
+		assert_ok!( Signal::milestone_proposal( Origin::signed(ALICE), id, b"milestone".to_vec(), vec![], 15 ) );
+		let proposal = last_proposal();
+
+		// alice did not contribute
+		assert_noop!( Signal::simple_vote( Origin::signed(ALICE), proposal, true ), Error::<Test>::AuthorizationError );
+		assert_ok!( Signal::simple_vote( Origin::signed(BOB), proposal, true ) );
+		run_to_block(16);
+		assert!( Signal::proposal_states(proposal) == ProposalState::Accepted );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Released );
+		assert_eq!( Flow::released_balance(id), 50 );
+		assert_eq!( Flow::current_milestone(id), 1 );
 	});
 }
 
 #[test]
-fn contribute_handles_basic_errors() {
+fn undecided_milestone_can_be_proposed_again() {
 	new_test_ext().execute_with(|| {
-		// Cannot contribute to non-existing fund
-		assert_noop!(
-			Crowdfund::contribute(Origin::signed(1), 0, 49),
-			Error::<Test>::InvalidIndex
-		);
-		// Cannot contribute below minimum contribution
+		let id = create_funded_campaign(vec![ milestone(50, 20) ]);
+		assert_ok!( Signal::milestone_proposal( Origin::signed(ALICE), id, b"milestone".to_vec(), vec![], 15 ) );
+		let proposal = last_proposal();
+
+		run_to_block(16);
+		assert!( Signal::proposal_states(proposal) == ProposalState::Expired );
+		assert_eq!( Flow::milestone_state((id, 0)), MilestoneState::Pending );
+		assert_ok!( Signal::milestone_proposal( Origin::signed(ALICE), id, b"milestone".to_vec(), vec![], 19 ) );
+	});
+}
+
+#[test]
+fn milestone_campaigns_do_not_withdraw() {
+	new_test_ext().execute_with(|| {
+		let id = create_funded_campaign(vec![ milestone(50, 20) ]);
 		assert_noop!(
-			Crowdfund::contribute(Origin::signed(1), 0, 9),
-			Error::<Test>::ContributionTooSmall
+			Signal::withdraw_proposal( Origin::signed(ALICE), id, b"withdraw".to_vec(), vec![], 50, 12, 20 ),
+			Error::<Test>::MilestonesDeclared
 		);
+	});
+}
 
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 101));
+//
+//	multiple choice
+//
 
-		// Move past end date
-		run_to_block(10);
+fn options(count: u8) -> Vec<Vec<u8>> {
+	(0..count).map(|o| vec![o]).collect()
+}
 
-		// Cannot contribute to ended fund
-		assert_noop!(
-			Crowdfund::contribute(Origin::signed(1), 0, 49),
-			Error::<Test>::ContributionPeriodOver
+// multiple choice proposal of alice ending at block 5
+fn multiple(count: u8, method: ChoiceMethod) -> H256 {
+	assert_ok!( Signal::multiple_proposal( Origin::signed(ALICE), org(), b"multiple".to_vec(), vec![], options(count), method, 5 ) );
+	last_proposal()
+}
+
+#[test]
+fn multiple_proposal_is_validated() {
+	new_test_ext().execute_with(|| {
+		let propose = |origin: u128, count: u8| Signal::multiple_proposal(
+			Origin::signed(origin), org(), b"multiple".to_vec(), vec![], options(count), ChoiceMethod::Plurality, 5,
 		);
+		assert_noop!( propose(DAVE, 2), Error::<Test>::AuthorizationError );
+		assert_noop!( propose(ALICE, 1), Error::<Test>::TooFewOptions );
+		assert_noop!( propose(ALICE, 5), Error::<Test>::TooManyOptions );
+		assert_ok!( propose(ALICE, 4) );
+		assert_eq!( Signal::choice_votes(last_proposal()), vec![0; 4] );
+
+		// proposals of the same block get their own id
+		assert_ok!( propose(ALICE, 2) );
+		assert_eq!( Signal::proposals_count(), 2 );
+		assert_ne!( Signal::proposals_by_index(0), Signal::proposals_by_index(1) );
 	});
 }
 
 #[test]
-fn withdraw_works() {
+fn plurality_counts_one_option_per_ballot() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		// Transfer fees are taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 100));
-		assert_ok!(Crowdfund::contribute(Origin::signed(2), 0, 200));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 300));
-
-		// Skip all the way to the end
-		// Crowdfund is unsuccessful 100 + 200 + 300 < 1000
-		run_to_block(50);
-
-		// User can withdraw their full balance without fees
-		assert_ok!(Crowdfund::withdraw(Origin::signed(1), 0));
-		assert_eq!(Balances::free_balance(1), 999);
-
-		assert_ok!(Crowdfund::withdraw(Origin::signed(2), 0));
-		assert_eq!(Balances::free_balance(2), 2000);
-
-		assert_ok!(Crowdfund::withdraw(Origin::signed(3), 0));
-		assert_eq!(Balances::free_balance(3), 3000);
+		let id = multiple(3, ChoiceMethod::Plurality);
+		assert_noop!( Signal::simple_vote( Origin::signed(BOB), id, true ), Error::<Test>::NotMultipleChoice );
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 1, 0 ] ), Error::<Test>::InvalidBallot );
+		assert_noop!( Signal::choice_vote( Origin::signed(DAVE), id, vec![ 1 ] ), Error::<Test>::AuthorizationError );
+
+		assert_ok!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 1 ] ) );
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 0 ] ), Error::<Test>::AlreadyVoted );
+		assert_ok!( Signal::choice_vote( Origin::signed(CHARLIE), id, vec![ 1 ] ) );
+		assert_ok!( Signal::choice_vote( Origin::signed(ALICE), id, vec![ 0 ] ) );
+		assert_eq!( Signal::choice_votes(id), vec![ 1, 2, 0 ] );
+		assert_ok!( Signal::check_invariants() );
+
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert_eq!( Signal::proposal_winner(id), Some(1) );
 	});
 }
 
 #[test]
-fn withdraw_handles_basic_errors() {
+fn simple_proposals_reject_ballots() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		// Transfer fee is taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 49));
-		assert_eq!(Balances::free_balance(1), 950);
+		let id = general();
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 0 ] ), Error::<Test>::NotMultipleChoice );
+	});
+}
 
-		run_to_block(5);
+#[test]
+fn borda_scores_options_by_rank() {
+	new_test_ext().execute_with(|| {
+		let id = multiple(3, ChoiceMethod::Borda);
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![] ), Error::<Test>::InvalidBallot );
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 0, 0 ] ), Error::<Test>::InvalidBallot );
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 3 ] ), Error::<Test>::InvalidBallot );
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 0, 1, 2, 0 ] ), Error::<Test>::InvalidBallot );
+
+		assert_ok!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 0, 1, 2 ] ) );
+		assert_ok!( Signal::choice_vote( Origin::signed(CHARLIE), id, vec![ 1, 2, 0 ] ) );
+		// options not ranked score nothing
+		assert_ok!( Signal::choice_vote( Origin::signed(ALICE), id, vec![ 2, 1 ] ) );
+		assert_eq!( Signal::choice_votes(id), vec![ 2, 4, 3 ] );
+		assert_eq!( Signal::ballot_count(id), 3 );
+
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert_eq!( Signal::proposal_winner(id), Some(1) );
+	});
+}
 
-		// Cannot withdraw before fund ends
-		assert_noop!(
-			Crowdfund::withdraw(Origin::signed(1), 0),
-			Error::<Test>::FundStillActive
-		);
+#[test]
+fn instant_runoff_is_counted_over_blocks() {
+	new_test_ext().execute_with(|| {
+		let id = multiple(3, ChoiceMethod::InstantRunoff);
+		assert_ok!( Signal::choice_vote( Origin::signed(ALICE), id, vec![ 0, 2 ] ) );
+		assert_ok!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 1, 2 ] ) );
+		assert_ok!( Signal::choice_vote( Origin::signed(CHARLIE), id, vec![ 2, 1 ] ) );
+		// ranked ballots are counted after expiry
+		assert_eq!( Signal::choice_votes(id), vec![ 0; 3 ] );
+
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Tallying );
+		assert_eq!( Signal::pending_runoffs(), vec![ id ] );
+		assert_ok!( Signal::check_invariants() );
+
+		// a three way tie eliminates the last option,
+		// two ballots are counted per block
+		run_to_block(8);
+		let tally = Signal::runoff(id).unwrap();
+		assert_eq!( tally.remaining, vec![ true, true, false ] );
+		assert!( Signal::proposal_states(id) == ProposalState::Tallying );
+
+		// charlie moves to option 1, which holds the majority
+		run_to_block(9);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert_eq!( Signal::proposal_winner(id), Some(1) );
+		assert!( Signal::runoff(id).is_none() );
+		assert!( Signal::pending_runoffs().is_empty() );
+		assert_ok!( Signal::check_invariants() );
+	});
+}
 
-		// Skip to the retirement period
-		// Crowdfund is unsuccessful 100 + 200 + 300 < 1000
-		run_to_block(10);
+#[test]
+fn instant_runoff_majority_wins_the_first_round() {
+	new_test_ext().execute_with(|| {
+		let id = multiple(3, ChoiceMethod::InstantRunoff);
+		assert_ok!( Signal::choice_vote( Origin::signed(ALICE), id, vec![ 2 ] ) );
+		assert_ok!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 2, 0 ] ) );
 
-		// Cannot withdraw if they did not contribute
-		assert_noop!(
-			Crowdfund::withdraw(Origin::signed(2), 0),
-			Error::<Test>::NoContribution
-		);
-		// Cannot withdraw from a non-existent fund
-		assert_noop!(
-			Crowdfund::withdraw(Origin::signed(1), 1),
-			Error::<Test>::InvalidIndex
-		);
+		run_to_block(7);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert_eq!( Signal::proposal_winner(id), Some(2) );
 	});
 }
 
 #[test]
-fn dissolve_works() {
+fn multiple_choice_without_ballots_expires() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		// Transfer fee is taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 100));
-		assert_ok!(Crowdfund::contribute(Origin::signed(2), 0, 200));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 300));
-
-		// Skip all the way to the end
-		// Crowdfund is unsuccessful 100 + 200 + 300 < 1000
-		run_to_block(50);
-
-		// Check initiator's balance.
-		assert_eq!(Balances::free_balance(1), 899);
-		// Check current funds (contributions + deposit)
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 601);
-
-		// Account 7 dissolves the crowdfund claiming the remaining funds
-		assert_ok!(Crowdfund::dissolve(Origin::signed(7), 0));
-
-		// Fund account is emptied
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 0);
-		// Dissolver account is rewarded
-		assert_eq!(Balances::free_balance(7), 601);
-
-		// Storage trie is removed
-		assert_eq!(Crowdfund::contribution_get(0, &0), 0);
-		// Fund storage is removed
-		assert_eq!(Crowdfund::funds(0), None);
+		let id = multiple(2, ChoiceMethod::InstantRunoff);
+		run_to_block(6);
+		assert!( Signal::proposal_states(id) == ProposalState::Expired );
+		assert_eq!( Signal::proposal_winner(id), None );
+		assert!( Signal::pending_runoffs().is_empty() );
 	});
 }
 
+//
+//	commit reveal
+//
+
+fn commitment(id: H256, voter: u128, vote: &SecretVote, salt: H256) -> H256 {
+	BlakeTwo256::hash_of(&(&id, &voter, vote, &salt))
+}
+
 #[test]
-fn dissolve_handles_basic_errors() {
+fn commit_reveal_is_set_before_votes() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		// Transfer fee is taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 100));
-		assert_ok!(Crowdfund::contribute(Origin::signed(2), 0, 200));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 300));
-
-		// Cannot dissolve an invalid fund index
+		let id = general();
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(BOB), id, 3 ), Error::<Test>::AuthorizationError );
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 0 ), Error::<Test>::OutOfBounds );
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 11 ), Error::<Test>::OutOfBounds );
+		assert_noop!( Signal::commit_vote( Origin::signed(BOB), id, H256::zero() ), Error::<Test>::NotSecretBallot );
+
+		// the proposal is finalized after the reveal window
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ) );
+		assert!( !Signal::proposals_by_block(5).contains(&id) );
+		assert!( Signal::proposals_by_block(8).contains(&id) );
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ), Error::<Test>::SecretBallot );
+		assert_noop!( Signal::simple_vote( Origin::signed(BOB), id, true ), Error::<Test>::SecretBallot );
+
+		// public votes stay public
+		let voted = general();
+		assert_ok!( Signal::simple_vote( Origin::signed(BOB), voted, true ) );
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(ALICE), voted, 3 ), Error::<Test>::AlreadyVoted );
+
+		// judgements are decided within their own deadlines
+		let index = Sense::judgement_nonce();
+		assert_ok!( Sense::open_judgement( Origin::signed(CHARLIE), DAVE, SenseProps::REP, 6, vec![] ) );
+		assert_ok!( Signal::judgement_proposal( Origin::signed(ALICE), org(), index, b"judgement".to_vec(), vec![], 6 ) );
+		assert_noop!( Signal::set_commit_reveal( Origin::signed(ALICE), last_proposal(), 3 ), Error::<Test>::SecretNotSupported );
+	});
+}
+
+#[test]
+fn revealed_votes_are_counted() {
+	new_test_ext().execute_with(|| {
+		let id = general();
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ) );
+		let salt = H256::repeat_byte(7);
+		let yes = SecretVote::Simple(true);
+
+		assert_noop!( Signal::commit_vote( Origin::signed(DAVE), id, H256::zero() ), Error::<Test>::ScoreTooLow );
+		assert_ok!( Signal::commit_vote( Origin::signed(BOB), id, commitment(id, BOB, &yes, salt) ) );
+		assert_noop!( Signal::commit_vote( Origin::signed(BOB), id, H256::zero() ), Error::<Test>::AlreadyVoted );
+		assert_eq!( Balances::reserved_balance(BOB), 5 );
+		assert_eq!( Signal::commit_count(id), 1 );
+		assert_noop!( Signal::reveal_vote( Origin::signed(BOB), id, yes.clone(), salt ), Error::<Test>::RevealNotStarted );
+
+		// votes are committed until expiry
+		run_to_block(5);
+		assert_noop!( Signal::commit_vote( Origin::signed(CHARLIE), id, H256::zero() ), Error::<Test>::ProposalExpired );
 		assert_noop!(
-			Crowdfund::dissolve(Origin::signed(1), 1),
-			Error::<Test>::InvalidIndex
+			Signal::reveal_vote( Origin::signed(BOB), id, yes.clone(), H256::zero() ),
+			Error::<Test>::CommitMismatch
 		);
-		// Cannot dissolve an active fund
 		assert_noop!(
-			Crowdfund::dissolve(Origin::signed(1), 0),
-			Error::<Test>::FundNotRetired
+			Signal::reveal_vote( Origin::signed(BOB), id, SecretVote::Simple(false), salt ),
+			Error::<Test>::CommitMismatch
 		);
+		assert_noop!( Signal::reveal_vote( Origin::signed(CHARLIE), id, yes.clone(), salt ), Error::<Test>::CommitUnknown );
 
-		run_to_block(10);
+		assert_ok!( Signal::reveal_vote( Origin::signed(BOB), id, yes.clone(), salt ) );
+		assert_eq!( Signal::proposal_simple_votes(id), (1, 0) );
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert_eq!( Signal::commit_count(id), 0 );
+		assert_ok!( Signal::check_invariants() );
 
-		// Cannot disolve an ended but not yet retired fund
-		assert_noop!(
-			Crowdfund::dissolve(Origin::signed(1), 0),
-			Error::<Test>::FundNotRetired
-		);
+		run_to_block(9);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
 	});
 }
 
 #[test]
-fn dispense_works() {
+fn commitments_are_bound_to_their_proposal() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 20, 1000, 9));
-		// Transfer fee is taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 100));
-		assert_ok!(Crowdfund::contribute(Origin::signed(2), 0, 200));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 300));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 400));
-
-		// Skip to the retirement period
-		// Crowdfund is successful 100 + 200 + 300 + 400  >= 1000
-		run_to_block(10);
-
-		// Check initiator's balance.
-		assert_eq!(Balances::free_balance(1), 899);
-		// Check current funds (contributions + deposit)
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 1001);
-
-		// Account 7 dispenses the crowdfund
-		assert_ok!(Crowdfund::dispense(Origin::signed(7), 0));
-
-		// Fund account is emptied
-		assert_eq!(Balances::free_balance(Crowdfund::fund_account_id(0)), 0);
-		// Beneficiary account is funded
-		assert_eq!(Balances::free_balance(20), 1000);
-		// Dispensor account is rewarded deposit
-		assert_eq!(Balances::free_balance(7), 1);
-
-		// Storage trie is removed
-		assert_eq!(Crowdfund::contribution_get(0, &0), 0);
-		// Fund storage is removed
-		assert_eq!(Crowdfund::funds(0), None);
+		let id = general();
+		let other = general();
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ) );
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), other, 3 ) );
+		let salt = H256::repeat_byte(7);
+		let yes = SecretVote::Simple(true);
+
+		// a commitment copied from another proposal does not reveal
+		assert_ok!( Signal::commit_vote( Origin::signed(BOB), id, commitment(id, BOB, &yes, salt) ) );
+		assert_ok!( Signal::commit_vote( Origin::signed(CHARLIE), other, commitment(id, BOB, &yes, salt) ) );
+		run_to_block(5);
+		assert_noop!( Signal::reveal_vote( Origin::signed(CHARLIE), other, yes.clone(), salt ), Error::<Test>::CommitMismatch );
+		assert_ok!( Signal::reveal_vote( Origin::signed(BOB), id, yes, salt ) );
 	});
 }
 
 #[test]
-fn dispense_handles_basic_errors() {
+fn unrevealed_commitments_are_forfeited() {
 	new_test_ext().execute_with(|| {
-		// Set up a crowdfund
-		assert_ok!(Crowdfund::create(Origin::signed(1), 2, 1000, 9));
-		// Transfer fee is taken here
-		assert_ok!(Crowdfund::contribute(Origin::signed(1), 0, 100));
-		assert_ok!(Crowdfund::contribute(Origin::signed(2), 0, 200));
-		assert_ok!(Crowdfund::contribute(Origin::signed(3), 0, 300));
-
-		// Cannot dispense an invalid fund index
-		assert_noop!(
-			Crowdfund::dispense(Origin::signed(1), 1),
-			Error::<Test>::InvalidIndex
-		);
-		// Cannot dispense an active fund
-		assert_noop!(
-			Crowdfund::dispense(Origin::signed(1), 0),
-			Error::<Test>::FundStillActive
-		);
+		let id = general();
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ) );
+		let salt = H256::repeat_byte(7);
+		let no = SecretVote::Simple(false);
+		let treasury = Control::body_treasury(org());
+		assert_ok!( Signal::commit_vote( Origin::signed(CHARLIE), id, commitment(id, CHARLIE, &no, salt) ) );
+
+		run_to_block(7);
+		assert_noop!( Signal::forfeit_commit( Origin::signed(BOB), id, CHARLIE ), Error::<Test>::RevealNotEnded );
+
+		run_to_block(8);
+		assert_noop!( Signal::reveal_vote( Origin::signed(CHARLIE), id, no, salt ), Error::<Test>::RevealEnded );
+		let before = Balances::free_balance(treasury);
+		assert_ok!( Signal::forfeit_commit( Origin::signed(BOB), id, CHARLIE ) );
+		assert_eq!( Balances::free_balance(treasury), before + 5 );
+		assert_eq!( Balances::total_balance(&CHARLIE), 1_000 - 5 );
+		assert_noop!( Signal::forfeit_commit( Origin::signed(BOB), id, CHARLIE ), Error::<Test>::CommitUnknown );
+
+		run_to_block(9);
+		assert!( Signal::proposal_states(id) == ProposalState::Expired );
+	});
+}
+
+#[test]
+fn secret_ballots_rank_options() {
+	new_test_ext().execute_with(|| {
+		let id = multiple(2, ChoiceMethod::Borda);
+		assert_ok!( Signal::set_commit_reveal( Origin::signed(ALICE), id, 3 ) );
+		let salt = H256::repeat_byte(7);
+		let ranking = SecretVote::Choice(vec![ 1, 0 ]);
+		let simple = SecretVote::Simple(true);
 
-		// Skip to the retirement period
-		// Crowdfund is unsuccessful 100 + 200 + 300 < 1000
-		run_to_block(10);
+		assert_noop!( Signal::choice_vote( Origin::signed(BOB), id, vec![ 1 ] ), Error::<Test>::SecretBallot );
+		assert_noop!( Signal::commit_vote( Origin::signed(DAVE), id, H256::zero() ), Error::<Test>::AuthorizationError );
+		assert_ok!( Signal::commit_vote( Origin::signed(BOB), id, commitment(id, BOB, &ranking, salt) ) );
+		assert_ok!( Signal::commit_vote( Origin::signed(CHARLIE), id, commitment(id, CHARLIE, &simple, salt) ) );
 
-		// Cannot disopens an ended but unsuccessful fund
-		assert_noop!(
-			Crowdfund::dispense(Origin::signed(1), 0),
-			Error::<Test>::UnsuccessfulFund
-		);
+		run_to_block(5);
+		// a yes / no vote does not count on options and keeps its deposit reserved
+		assert_noop!( Signal::reveal_vote( Origin::signed(CHARLIE), id, simple, salt ), Error::<Test>::InvalidBallot );
+		assert_ok!( Signal::reveal_vote( Origin::signed(BOB), id, ranking, salt ) );
+		assert_eq!( Signal::choice_votes(id), vec![ 0, 1 ] );
+		assert_eq!( Balances::reserved_balance(BOB), 0 );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 5 );
+
+		run_to_block(9);
+		assert!( Signal::proposal_states(id) == ProposalState::Accepted );
+		assert_eq!( Signal::proposal_winner(id), Some(1) );
+	});
+}
+
+//
+//	migrations
+//
+
+#[test]
+fn upgrade_records_the_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!( Signal::storage_version(), Releases::V1 );
+
+		// a chain from before versioning
+		put_storage_value( b"Signal50", b"StorageVersion", &[], Releases::V0 );
+		Signal::on_runtime_upgrade();
+		assert_eq!( Signal::storage_version(), Releases::V1 );
+		assert_ok!( Signal::check_invariants() );
 	});
 }
@@ -0,0 +1,39 @@
+//
+//           _______________________________ ________
+//           \____    /\_   _____/\______   \\_____  \
+//             /     /  |    __)_  |       _/ /   |   \
+//            /     /_  |        \ |    |   \/    |    \
+//           /_______ \/_______  / |____|_  /\_______  /
+//                   \/        \/         \/         \/
+//           Z  E  R  O  .  I  O     N  E  T  W  O  R  K
+//           © C O P Y R I O T   2 0 7 5 @ Z E R O . I O
+
+// This file is part of ZERO Network.
+// Copyright (C) 2010-2020 ZERO Labs.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime API definition for the tangram module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+use primitives::{ Rarity, RarityTable };
+
+sp_api::decl_runtime_apis! {
+	pub trait TangramApi<Hash, AccountId> where
+		Hash: Codec,
+		AccountId: Codec,
+	{
+		/// rarity tier of an item
+		fn rarity(item: Hash) -> Option<Rarity>;
+		/// rarity table of a class
+		fn rarity_table(realm: u64, class: u64) -> RarityTable;
+		/// dna of item `index` of a class for a seed, `age` blocks after the first mint
+		fn dna(seed: Hash, realm: u64, class: u64, index: u64, age: u32) -> (Rarity, Hash);
+		/// an item and the items it holds, depth first with their depth below the item
+		fn item_tree(item: Hash) -> Vec<(Hash, u32)>;
+		/// account using an item, the renter while rented, otherwise the owner
+		fn item_user(item: Hash) -> Option<AccountId>;
+	}
+}
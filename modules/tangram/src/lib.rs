@@ -4,6 +4,7 @@
 #![allow(unused_variables)]
 
 #![cfg_attr(not(feature = "std"), no_std)]
+#![recursion_limit = "256"]
 
 use codec::{Decode, Encode};
 use frame_support::{
@@ -21,14 +22,25 @@ use frame_support::{
 		LockableCurrency,
 		Randomness,
 		Time,
-		WithdrawReasons
+		WithdrawReasons,
+		ReservableCurrency,
+		ExistenceRequirement,
+		BalanceStatus,
 	},
+	storage::IterableStorageMap,
+	weights::Weight,
+	transactional,
 };
 use frame_system::{ self as system, ensure_root, ensure_signed };
-use sp_runtime::traits::{Hash, Member};
+use sp_runtime::traits::{Hash, Member, One, SaturatedConversion, Saturating, Zero};
+use sp_runtime::Permill;
 use sp_core::RuntimeDebug;
-use sp_std::vec::Vec;
-use primitives::{ Balance };
+use sp_std::{ vec, vec::Vec };
+use primitives::{ Balance, BodyProvider, RealmProvider, SenseAction, SenseHooks };
+pub use primitives::{ Rarity, RarityTable };
+
+#[cfg(feature = "std")]
+use serde::{ Deserialize, Serialize };
 
 // use control;
 // use signal;
@@ -39,11 +51,11 @@ use primitives::{ Balance };
 pub mod nft;
 pub use crate::nft::NFTItems;
 
-// #[cfg(test)]
-// mod mock;
+#[cfg(test)]
+mod mock;
 
-// #[cfg(test)]
-// mod tests;
+#[cfg(test)]
+mod tests;
 
 const MODULE_ID: LockIdentifier = *b"tangram ";
 
@@ -54,6 +66,8 @@ pub type ClassIndex = u64;
 pub type ItemIndex = u64;
 pub type TotalIndex = u128;
 pub type BurnedIndex = u128;
+pub type RedeemableIndex = u64;
+pub type RecipeIndex = u64;
 
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
@@ -63,6 +77,21 @@ pub type TangramId<T> = <T as system::Config>::Hash;
 pub type TangramItemOf<T> = TangramItem< TangramId<T>, MomentOf<T> >;
 pub type Tangram<T> = ( TangramId<T>, TangramItemOf<T>);
 
+/// Storage version
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum Releases {
+	/// unversioned
+	V0,
+	/// versioned storage
+	V1,
+	/// rarity tables in class metadata
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self { Releases::V0 }
+}
+
 /// TangramRealm
 /// RealmId, Controller Org, Index
 #[derive(Encode, Decode, Default, PartialEq, Eq)]
@@ -109,7 +138,20 @@ pub struct TangramClassMetadata<Hash, BlockNumber> {
 	cid: Vec<u8>,
 	created: BlockNumber,
 	mutated: BlockNumber,
-	f: Vec<u8>
+	f: Vec<u8>,
+	/// odds of the rarity tiers
+	rarity: RarityTable,
+}
+
+// class metadata before rarity tables, only decoded by the v2 migration
+#[derive(Encode, Decode)]
+struct OldClassMetadata<Hash, BlockNumber> {
+	id: Hash,
+	name: Vec<u8>,
+	cid: Vec<u8>,
+	created: BlockNumber,
+	mutated: BlockNumber,
+	f: Vec<u8>,
 }
 
 /// Tangram Immutable + Unique
@@ -128,6 +170,101 @@ pub struct TangramMetadata {
 	cid: Vec<u8>,
 }
 
+/// Who pays the mint cost of a redeemable
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Payable {
+	/// the issuer, reserved on issuance
+	SENDER,
+	/// the redeeming account
+	RECIPIENT,
+	/// half each
+	SPLIT,
+}
+
+impl Default for Payable {
+	fn default() -> Self { Payable::RECIPIENT }
+}
+
+/// Right to mint an item of a class, issued by the realm controller
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct TangramRedeemable<AccountId, Balance, BlockNumber> {
+	/// account allowed to redeem
+	recipient: AccountId,
+	/// issuer, pays its share of the cost
+	issuer: AccountId,
+	realm: RealmIndex,
+	class: ClassIndex,
+	/// name and cid of the minted item
+	name: Vec<u8>,
+	cid: Vec<u8>,
+	/// mint cost, paid into the treasury of the realm body
+	cost: Balance,
+	payable: Payable,
+	/// last block to redeem
+	expiry: BlockNumber,
+}
+
+/// Dna of a crafted item
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CraftDna {
+	/// segments and rarity of the first ingredient
+	Inherited,
+	/// segments of all ingredients combined, the highest rarity
+	Combined,
+}
+
+impl Default for CraftDna {
+	fn default() -> Self { CraftDna::Inherited }
+}
+
+/// Burn ingredients of a realm to mint an item of its output class
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct TangramRecipe<Balance> {
+	/// (class, count) of the burned items
+	inputs: Vec<(ClassIndex, u32)>,
+	/// class of the crafted item
+	output: ClassIndex,
+	/// name and cid of the crafted item
+	name: Vec<u8>,
+	cid: Vec<u8>,
+	/// paid into the treasury of the realm body
+	fee: Balance,
+	dna: CraftDna,
+	enabled: bool,
+	/// remaining uses, unlimited if none
+	uses: Option<u32>,
+}
+
+/// Why an item can not be transferred or burned
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ItemLock {
+	/// listed on the marketplace
+	Listed,
+	/// lent to another account
+	Rented,
+}
+
+/// How a listed item is sold
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum Sale<AccountId, Balance, BlockNumber> {
+	/// for a fixed price
+	Fixed,
+	/// english auction ending at a block, with the highest bid
+	Auction(BlockNumber, Option<(AccountId, Balance)>),
+}
+
+/// Item listed on the marketplace
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TangramListing<AccountId, Balance, BlockNumber> {
+	seller: AccountId,
+	/// fixed price or auction reserve
+	price: Balance,
+	sale: Sale<AccountId, Balance, BlockNumber>,
+}
+
 //
 //
 //
@@ -154,6 +291,43 @@ pub trait Config: frame_system::Config + balances::Config  {
 	type TotalIndex;
 	type BurnedIndex;
 
+	// sense
+	// experience for gamedao actions
+	type SenseHooks: SenseHooks<Self::AccountId>;
+
+	// control
+	// bodies owning the realms
+	type Control: BodyProvider<Self::AccountId, Self::Hash>;
+
+	/// max redeemables issued in one call
+	type MaxRedeemables: Get<u32>;
+	/// max outstanding redeemables of an account
+	type MaxRedeemablesPerAccount: Get<u32>;
+	/// max items burned by a recipe
+	type MaxIngredients: Get<u32>;
+
+	// market
+	/// max royalty a realm can take on sales
+	type MaxRoyalty: Get<Permill>;
+	/// max open offers per item
+	type MaxOffers: Get<u32>;
+
+	// nesting
+	/// max items held directly by an item
+	type MaxItemChildren: Get<u32>;
+	/// max levels of items below a root item
+	type MaxItemDepth: Get<u32>;
+	/// max items in a tree, including its root, moved by one transfer
+	type MaxTreeSize: Get<u32>;
+
+	// rentals
+	/// max blocks an item can be lent for
+	type MaxRentalDuration: Get<Self::BlockNumber>;
+	/// max rentals expired per block
+	type MaxRentalExpiries: Get<u32>;
+	/// max rentals ending in one block
+	type MaxRentalsPerBlock: Get<u32>;
+
 }
 
 decl_storage! {
@@ -172,6 +346,8 @@ decl_storage! {
 		// RealmsForOwner
 		pub RealmsForOwner get(fn realms_for_owner): map hasher(blake2_128_concat) T::Hash => Vec<T::Hash>;
 		pub RealmsForOwnerCount get(fn realms_for_owner_count): map hasher(blake2_128_concat) T::Hash => u64;
+		/// Archived realms, no new classes or items
+		pub RealmArchived get(fn realm_archived): map hasher(blake2_128_concat) RealmIndex => bool;
 		// ClassesForRealm
 
 		// class
@@ -193,12 +369,18 @@ decl_storage! {
 		pub NextItemIndex get(fn next_item_index): map hasher(blake2_128_concat) (RealmIndex, ClassIndex) => ItemIndex;
 		/// Max Items for respective Class
 		pub MaxItems get(fn max_items): map hasher(blake2_128_concat) (RealmIndex, ClassIndex) => u64;
+		/// Metadata and rarity table of a Class
+		pub ClassMetadata get(fn class_metadata): map hasher(blake2_128_concat) (RealmIndex, ClassIndex) => TangramClassMetadata<T::Hash, T::BlockNumber>;
+		/// Block of the first mint in a Class, rarity decays from here
+		pub FirstMinted get(fn first_minted): map hasher(blake2_128_concat) (RealmIndex, ClassIndex) => Option<T::BlockNumber>;
 
 		/// Tangram Item
 		pub Item get(fn item): map hasher(blake2_128_concat) T::Hash => TangramItem<T::Hash, MomentOf<T>>;
 
 		/// Metadata for an Item
 		pub ItemMetadata get(fn item_metadata): map hasher(identity) T::Hash => TangramMetadata;
+		/// Rarity of an Item
+		pub ItemRarity get(fn item_rarity): map hasher(blake2_128_concat) T::Hash => Option<Rarity>;
 		/// All Items associated with an account
 		pub ItemsForAccount get(fn items_for_account): map hasher(blake2_128_concat) T::AccountId => Vec<T::Hash>;
 		/// Owner of an Item
@@ -213,35 +395,93 @@ decl_storage! {
 		/// Burned Token in system
 		pub Burned get(fn burend_items): BurnedIndex;
 
-		// pub Redeemable get(fn redeemable): map hasher(blake2_128_concat) T::AccountId => Vec<(RealmId, ClassId)>;
-		// /// Total Redeemable
-		// pub Redeemable: u128 = 0;
-		// /// Total Redeemed
-		// pub Redeemed: u128 = 0;
+		// redeemables
+
+		/// Next Redeemable Index
+		pub NextRedeemableIndex get(fn next_redeemable_index): RedeemableIndex;
+		/// Open Redeemables by index
+		pub Redeemables get(fn redeemable): map hasher(blake2_128_concat) RedeemableIndex => Option<TangramRedeemable<T::AccountId, T::Balance, T::BlockNumber>>;
+		/// Open Redeemables of an account
+		pub RedeemablesForAccount get(fn redeemables_for_account): map hasher(blake2_128_concat) T::AccountId => Vec<RedeemableIndex>;
+		/// Open Redeemables per class, count against max items
+		pub OutstandingRedeemables get(fn outstanding_redeemables): map hasher(blake2_128_concat) (RealmIndex, ClassIndex) => u64;
+		/// Total Redeemable
+		pub Redeemable get(fn total_redeemable): u128;
+		/// Total Redeemed
+		pub Redeemed get(fn total_redeemed): u128;
+
+		// crafting
+
+		/// Next Recipe Index, individual per Realm
+		pub NextRecipeIndex get(fn next_recipe_index): map hasher(blake2_128_concat) RealmIndex => RecipeIndex;
+		/// Recipes of a Realm
+		pub Recipes get(fn recipe): map hasher(blake2_128_concat) (RealmIndex, RecipeIndex) => Option<TangramRecipe<T::Balance>>;
+
+		// nesting
+
+		/// Item holding an Item
+		pub ItemParent get(fn item_parent): map hasher(blake2_128_concat) T::Hash => Option<T::Hash>;
+		/// Items held by an Item
+		pub ItemChildren get(fn item_children): map hasher(blake2_128_concat) T::Hash => Vec<T::Hash>;
+
+		// rentals
+
+		/// Account using a rented Item until a block
+		pub ItemUser get(fn item_user): map hasher(blake2_128_concat) T::Hash => Option<(T::AccountId, T::BlockNumber)>;
+		/// Rentals offered for a fee: (user, until, fee)
+		pub PendingRentals get(fn pending_rental): map hasher(blake2_128_concat) T::Hash => Option<(T::AccountId, T::BlockNumber, T::Balance)>;
+		/// Rentals ending in a block, the one after their last block of use
+		pub RentalsEnding get(fn rentals_ending): map hasher(blake2_128_concat) T::BlockNumber => Vec<T::Hash>;
+		/// Next block to expire rentals for
+		pub RentalCursor get(fn rental_cursor): T::BlockNumber;
+
+		// market
+
+		/// Locked Items, not transferable
+		pub ItemLocks get(fn item_lock): map hasher(blake2_128_concat) T::Hash => Option<ItemLock>;
+		/// Royalty of a Realm on sales, paid into the body treasury
+		pub RealmRoyalty get(fn realm_royalty): map hasher(blake2_128_concat) RealmIndex => Permill;
+		/// Listed Items
+		pub Listings get(fn listing): map hasher(blake2_128_concat) T::Hash => Option<TangramListing<T::AccountId, T::Balance, T::BlockNumber>>;
+		/// Offers on an Item, reserved by the bidder
+		pub Offers get(fn offers): map hasher(blake2_128_concat) T::Hash => Vec<(T::AccountId, T::Balance)>;
+
+		// operators
+
+		/// Accounts, e.g. contracts, allowed to mint and move Items of a Realm
+		pub RealmOperators get(fn is_operator): map hasher(blake2_128_concat) (RealmIndex, T::AccountId) => bool;
 
 		// /// map strategies
 		// pub Strategy get(fn strategy): map hasher(blake2_128_concat) u64 => (BuyFn,SellFn,RarityFn);
 
+		/// Storage version, new chains start at the latest
+		pub StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V2): Releases;
+
+	}
+	add_extra_genesis {
+		/// realms created at genesis for an org, in addition to body realms
+		config(realms): Vec<T::Hash>;
+		/// classes created at genesis: (realm, name, max)
+		config(classes): Vec<(RealmIndex, Vec<u8>, u64)>;
+		/// items minted at genesis: (realm, class, name, cid, owner)
+		config(items): Vec<(RealmIndex, ClassIndex, Vec<u8>, Vec<u8>, T::AccountId)>;
+		build(|config: &GenesisConfig<T>| {
+			for org in config.realms.iter() {
+				Module::<T>::create_realm( system::RawOrigin::Root.into(), org.clone() )
+					.expect("genesis realm is valid");
+			}
+			for (realm, name, max) in config.classes.iter() {
+				Module::<T>::create_class( system::RawOrigin::Root.into(), *realm, name.clone(), *max, 0 )
+					.expect("genesis class is valid");
+			}
+			for (realm, class, name, cid, owner) in config.items.iter() {
+				Module::<T>::mint_item( *realm, *class, name.clone(), cid.clone(), owner )
+					.expect("genesis item is valid");
+			}
+		});
 	}
 }
 
-// pub enum Payable {
-// 	SENDER,
-// 	RECIPIENT,
-// 	SPLIT
-// }
-
-// pub struct Redeemable<T::AccountId, RealmId, ClassId> {
-// 	recipient: T::AccountId,
-// 	realm: RealmId,
-// 	class: ClassId
-// }
-
-// /// resolve account to list of hashes
-// T::AccountId => Vec<RedeemableHash>
-// /// resolve a hash to a struct
-// RedeemableHash => Redeemable
-
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 
@@ -257,63 +497,203 @@ decl_module! {
 		const MaxTokenPerClass: u128 = T::MaxTokenPerClass::get();
 		const MaxTotalToken: u128 = T::MaxTotalToken::get();
 
+		fn on_runtime_upgrade() -> Weight {
+			match Self::storage_version() {
+				Releases::V0 => Self::migrate_to_v1().saturating_add(Self::migrate_to_v2()),
+				Releases::V1 => Self::migrate_to_v2(),
+				_ => 0,
+			}
+		}
+
+		/// end rentals, at most `MaxRentalExpiries` blocks and rentals per block,
+		/// the rest is carried over to the next block
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut block = Self::rental_cursor();
+			if block.is_zero() { block = now; }
+			let mut budget = T::MaxRentalExpiries::get();
+			let mut expired: Weight = 0;
+			let mut scanned: Weight = 0;
+
+			while block <= now && budget > 0 {
+				budget -= 1;
+				scanned += 1;
+				let mut ending = RentalsEnding::<T>::take(&block);
+				while budget > 0 {
+					match ending.pop() {
+						Some(item) => {
+							Self::end_rental(&item);
+							budget -= 1;
+							expired += 1;
+						},
+						None => break,
+					}
+				}
+				if !ending.is_empty() {
+					RentalsEnding::<T>::insert( &block, ending );
+					break
+				}
+				block += One::one();
+			}
+			RentalCursor::<T>::put(block);
+
+			T::DbWeight::get().reads_writes(
+				scanned.saturating_add(expired.saturating_mul(3)).saturating_add(1),
+				scanned.saturating_add(expired.saturating_mul(4)).saturating_add(1)
+			)
+		}
+
+		const MaxRedeemables: u32 = T::MaxRedeemables::get();
+		const MaxRedeemablesPerAccount: u32 = T::MaxRedeemablesPerAccount::get();
+		const MaxIngredients: u32 = T::MaxIngredients::get();
+		const MaxRoyalty: Permill = T::MaxRoyalty::get();
+		const MaxOffers: u32 = T::MaxOffers::get();
+		const MaxItemChildren: u32 = T::MaxItemChildren::get();
+		const MaxItemDepth: u32 = T::MaxItemDepth::get();
+		const MaxTreeSize: u32 = T::MaxTreeSize::get();
+		const MaxRentalDuration: T::BlockNumber = T::MaxRentalDuration::get();
+		const MaxRentalExpiries: u32 = T::MaxRentalExpiries::get();
+		const MaxRentalsPerBlock: u32 = T::MaxRentalsPerBlock::get();
+
 		//
 		//	add redeemables
 		//	origin === controller
 		//
 
-		// #[weight = 50_000]
-		// pub fn add_redeemable(
-		// 	origin,
-		// 	realm: RealmId,
-		// 	class: ClassId,
-		// 	who: T::AccountId
-		// ) -> DispatchResult {
-
-		// 	// check authorised for realm, class (usually controller)
-		// 	// check for max items in class
-
-		// 	// insert realm, class for who
-		// 	// redeemable ++
-		// 	// AddRedeemable event
-		// }
+		/*
+			add_redeemable
+			realm, class: class to mint from
+			name, cid:    of the items to mint
+			recipients:   one redeemable each
+			cost:         mint cost per item, paid into the body treasury
+			payable:      who pays the cost
+			expiry:       last block to redeem
+		*/
+		#[weight = 1_000_000 + 500_000 * recipients.len() as u64]
+		#[transactional]
+		pub fn add_redeemable(
+			origin,
+			realm: RealmIndex,
+			class: ClassIndex,
+			name: Vec<u8>,
+			cid: Vec<u8>,
+			recipients: Vec<T::AccountId>,
+			cost: T::Balance,
+			payable: Payable,
+			expiry: T::BlockNumber
+		) -> DispatchResult {
 
-		// #[weight = 25_000]
-		// pub fn redeem(
-		// 	origin,
-		// 	who: T::Hash
-		// ) -> DispatchResult {
-		// 	ensure_signed(origin);
+			let sender = ensure_signed(origin)?;
+			ensure!( realm < NextRealmIndex::get(), Error::<T>::UnknownRealm );
+			ensure!( !Self::realm_archived(&realm), Error::<T>::RealmArchived );
+			ensure!( class < Self::next_class_index(&realm), Error::<T>::UnknownClass );
+			ensure!( Self::realm_controller(realm) == Some(sender.clone()), Error::<T>::Unauthorized );
+
+			let count = recipients.len();
+			ensure!( count > 0, Error::<T>::NoRecipients );
+			ensure!( count <= T::MaxRedeemables::get() as usize, Error::<T>::TooManyRedeemables );
+			ensure!( expiry > <system::Module<T>>::block_number(), Error::<T>::RedeemableExpired );
+
+			// redeemables reserve the supply of the class
+			let outstanding = Self::outstanding_redeemables((&realm,&class)).saturating_add(count as u64);
+			ensure!(
+				Self::next_item_index((&realm,&class)).saturating_add(outstanding) <= Self::max_items((&realm,&class)),
+				Error::<T>::MaxItemsReached
+			);
+
+			// the issuer share is reserved up front
+			let (issuer_share, _) = Self::shares(cost, payable);
+			<balances::Module<T>>::reserve( &sender, issuer_share.saturating_mul((count as u32).into()) )?;
+
+			let mut index = Self::next_redeemable_index();
+			for recipient in recipients {
+				let redeemable = TangramRedeemable {
+					recipient: recipient.clone(),
+					issuer: sender.clone(),
+					realm,
+					class,
+					name: name.clone(),
+					cid: cid.clone(),
+					cost,
+					payable,
+					expiry,
+				};
+				Redeemables::<T>::insert( index, redeemable );
+				RedeemablesForAccount::<T>::try_mutate( &recipient, |redeemables| -> DispatchResult {
+					ensure!(
+						(redeemables.len() as u32) < T::MaxRedeemablesPerAccount::get(),
+						Error::<T>::TooManyRedeemablesForAccount
+					);
+					redeemables.push(index);
+					Ok(())
+				})?;
+				Self::deposit_event( RawEvent::RedeemableAdded( index, realm, class, recipient ) );
+				index = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			}
+			NextRedeemableIndex::put(index);
+			OutstandingRedeemables::insert( (&realm,&class), outstanding );
+			Redeemable::mutate( |total| *total = total.saturating_add(count as u128) );
+			Ok(())
+		}
 
-		// 	// find redeemables for account
-		// 	let available_redeemables = Redeemable::<T>::get(&origin);
-		// 	ensure( available_redeemables > 0, Error::<T>::NoRedeemables  );
+		/*
+			redeem
+			index: redeemable of the sender
+		*/
+		#[weight = 5_000_000]
+		#[transactional]
+		pub fn redeem(
+			origin,
+			index: RedeemableIndex
+		) -> DispatchResult {
 
-		// 	for redeemable in &available_redeemables {
+			let sender = ensure_signed(origin)?;
+			let redeemable = Self::redeemable(index).ok_or(Error::<T>::UnknownRedeemable)?;
+			ensure!( redeemable.recipient == sender, Error::<T>::Unauthorized );
+			ensure!( <system::Module<T>>::block_number() <= redeemable.expiry, Error::<T>::RedeemableExpired );
+
+			// mint cost into the treasury of the realm body
+			let (issuer_share, recipient_share) = Self::shares(redeemable.cost, redeemable.payable);
+			if !redeemable.cost.is_zero() {
+				let treasury = T::Control::treasury( &Self::owner_for_realm(redeemable.realm) )
+					.ok_or(Error::<T>::UnknownRealm)?;
+				<balances::Module<T>>::repatriate_reserved( &redeemable.issuer, &treasury, issuer_share, BalanceStatus::Free )?;
+				<balances::Module<T> as Currency<_>>::transfer( &sender, &treasury, recipient_share, ExistenceRequirement::KeepAlive )?;
+			}
 
-		// 		let realm = redeemable.0
-		// 		let class = redeemable.1
+			Self::close_redeemable( index, &redeemable );
+			let id = Self::mint_item( redeemable.realm, redeemable.class, redeemable.name, redeemable.cid, &sender )?;
+			Redeemed::mutate( |total| *total = total.saturating_add(1) );
 
-		// 		// id: Hash,
-		// 		// name: Vec<u8>,
-		// 		// cid: Vec<u8>,
-		// 		// created: BlockNumber,
-		// 		// mutated: BlockNumber,
-		// 		// f: Vec<u8>
+			T::SenseHooks::on_action( SenseAction::CreateItem, &sender );
+			Self::deposit_event( RawEvent::Redeemed( index, id, sender ) );
+			Ok(())
+		}
 
-		// 		let config = TangramClassMetadata::<T>::get(( &realm, &class ));
-		// 		let curve =
-		// 		let rarity =
+		/*
+			cancel_redeemable
+			the issuer cancels at any time, anyone once expired,
+			the reserved issuer share is returned
+		*/
+		#[weight = 1_000_000]
+		pub fn cancel_redeemable(
+			origin,
+			index: RedeemableIndex
+		) -> DispatchResult {
 
-		// 		// mint
+			let sender = ensure_signed(origin)?;
+			let redeemable = Self::redeemable(index).ok_or(Error::<T>::UnknownRedeemable)?;
+			ensure!(
+				redeemable.issuer == sender || <system::Module<T>>::block_number() > redeemable.expiry,
+				Error::<T>::Unauthorized
+			);
 
-		// 	}
-		// 	// remove redeemable from vec
-		// 	// redeemable --
-		// 	// redeemed ++
-		// 	// Redeem event
+			let (issuer_share, _) = Self::shares(redeemable.cost, redeemable.payable);
+			<balances::Module<T>>::unreserve( &redeemable.issuer, issuer_share );
+			Self::close_redeemable( index, &redeemable );
 
-		// }
+			Self::deposit_event( RawEvent::RedeemableCancelled( index ) );
+			Ok(())
+		}
 
 
 		#[weight = 1_000_000]
@@ -326,9 +706,12 @@ decl_module! {
 			// TODO: ensure org does not have a realm yet
 			let index = NextRealmIndex::get();
 			let hash = <T as Config>::Randomness::random(b"rndrealm");
-			let realm = TangramRealm { id: hash.clone(), org: org, index: index.clone() };
+			let realm = TangramRealm { id: hash.clone(), org: org.clone(), index: index.clone() };
 			Realm::<T>::insert( hash.clone(), realm );
-			RealmByIndex::<T>::insert( index.clone(), hash );
+			RealmByIndex::<T>::insert( index.clone(), hash.clone() );
+			OwnerRealm::<T>::insert( index.clone(), org.clone() );
+			RealmsForOwner::<T>::mutate( &org, |realms| realms.push(hash) );
+			RealmsForOwnerCount::<T>::mutate( &org, |count| *count += 1 );
 			// unsafe add
 			NextRealmIndex::mutate(|i| *i += 1);
 			Self::deposit_event( RawEvent::RealmCreated( index.clone() ) );
@@ -355,6 +738,7 @@ decl_module! {
 			// valid realm
 			let realm_index = NextRealmIndex::get();
 			ensure!( realm < realm_index, Error::<T>::UnknownRealm );
+			ensure!( !Self::realm_archived(&realm), Error::<T>::RealmArchived );
 
 			// max items
 			ensure!( max > 0, Error::<T>::MaxItemsTooSmall );
@@ -373,6 +757,18 @@ decl_module! {
 			ItemClass::<T>::insert( hash.clone(), new_class );
 			// ClassByIndex::<T>::insert( index.clone(), hash );
 
+			let now = <system::Module<T>>::block_number();
+			let metadata = TangramClassMetadata {
+				id: hash.clone(),
+				name: name,
+				cid: Vec::new(),
+				created: now,
+				mutated: now,
+				f: Vec::new(),
+				rarity: RarityTable::default(),
+			};
+			ClassMetadata::<T>::insert( (&realm,&index), metadata );
+
 			NextItemIndex::insert((&realm,&index),0);
 
 			// ++ class index
@@ -394,69 +790,631 @@ decl_module! {
 		) -> DispatchResult {
 
 			let sender = ensure_signed(origin)?;
-			// TODO: get realm controller
-			// TODO: ensure origin == realm controller
-			// T::ItemAdmin::ensure_origin(origin)?;
+			ensure!( realm < NextRealmIndex::get(), Error::<T>::UnknownRealm );
+			ensure!( Self::realm_controller(realm) == Some(sender.clone()), Error::<T>::Unauthorized );
 
+			Self::mint_item( realm, class, name, cid, &who )?;
+			T::SenseHooks::on_action( SenseAction::CreateItem, &sender );
+			Ok(())
+		}
+
+		/*
+			set_rarity
+			realm, class: class to update
+			table:        odds of the rarity tiers for future mints
+		*/
+		#[weight = 1_000_000]
+		pub fn set_rarity(
+			origin,
+			realm: RealmIndex,
+			class: ClassIndex,
+			table: RarityTable
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
 			ensure!( realm < NextRealmIndex::get(), Error::<T>::UnknownRealm );
 			ensure!( class < Self::next_class_index(&realm), Error::<T>::UnknownClass );
-			ensure!( Self::next_item_index((&realm,&class)) < Self::max_items((&realm,&class) ), Error::<T>::MaxItemsReached );
-
-			// 1. lock
-			// T::Currency::set_lock(
-			// 	MODULE_ID,
-			// 	&who,
-			// 	T::CreateTokenDeposit,
-			// 	WithdrawReasons::Fee | WithdrawReasons::Reserve
-			// );
-
-			// 2. determine rarity based on time since initial invocation
-			// a bonding
-
-			// 3. generate based on rarity levels
-			// epic mega rare common
-			// 0000+0000+0000+00000000 = 24 bytes
-
-			// let epic = "0000";
-			// let rare = "1111";
-			// let high = "2222";
-			// let low  = "3333";
-
-			// let mut stream = [ epic, rare, high, low ].concat();
-			// let stream = stream.as_bytes();
-			// let id = T::Hashing::hash_of(&stream);
-
-			let dob = <T as Config>::Time::now();
-			let dna = <T as Config>::Randomness::random( &name ); // for now
-
-			let item = TangramItem {
-				dob: dob,
-				dna: dna
-			};
+			ensure!( Self::realm_controller(realm) == Some(sender), Error::<T>::Unauthorized );
+			ensure!( table.is_valid(), Error::<T>::InvalidRarity );
+
+			let now = <system::Module<T>>::block_number();
+			ClassMetadata::<T>::mutate( (&realm,&class), |metadata| {
+				metadata.rarity = table;
+				metadata.mutated = now;
+			});
+			Self::deposit_event( RawEvent::RarityUpdated( realm, class ) );
+			Ok(())
+		}
 
-			let metadata = TangramMetadata {
-				realm: realm.clone(),
-				class: class.clone(),
-				name: name.clone(),
-				cid: cid.clone(),
+		/*
+			burn
+			item: hash of an item owned by the sender
+		*/
+		#[weight = 1_000_000]
+		pub fn burn(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			Self::burn_item( &item )
+		}
+
+		/*
+			add_recipe
+			realm:     realm of ingredients and output
+			inputs:    (class, count) burned
+			output:    class minted
+			name, cid: of the crafted item
+			fee:       paid into the body treasury
+			dna:       inherited or combined
+			uses:      usage limit, none for unlimited
+		*/
+		#[weight = 1_000_000]
+		pub fn add_recipe(
+			origin,
+			realm: RealmIndex,
+			inputs: Vec<(ClassIndex, u32)>,
+			output: ClassIndex,
+			name: Vec<u8>,
+			cid: Vec<u8>,
+			fee: T::Balance,
+			dna: CraftDna,
+			uses: Option<u32>
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( realm < NextRealmIndex::get(), Error::<T>::UnknownRealm );
+			ensure!( !Self::realm_archived(&realm), Error::<T>::RealmArchived );
+			ensure!( Self::realm_controller(realm) == Some(sender), Error::<T>::Unauthorized );
+
+			let classes = Self::next_class_index(&realm);
+			ensure!( output < classes, Error::<T>::UnknownClass );
+			ensure!( !inputs.is_empty(), Error::<T>::InvalidRecipe );
+			let mut count: u32 = 0;
+			for (position, (class, amount)) in inputs.iter().enumerate() {
+				ensure!( *class < classes, Error::<T>::UnknownClass );
+				ensure!( *amount > 0, Error::<T>::InvalidRecipe );
+				ensure!( !inputs[..position].iter().any(|(c, _)| c == class), Error::<T>::InvalidRecipe );
+				count = count.saturating_add(*amount);
+			}
+			ensure!( count <= T::MaxIngredients::get(), Error::<T>::InvalidRecipe );
+
+			let index = Self::next_recipe_index(&realm);
+			let recipe = TangramRecipe { inputs, output, name, cid, fee, dna, enabled: true, uses };
+			Recipes::<T>::insert( (&realm,&index), recipe );
+			NextRecipeIndex::insert( &realm, index.checked_add(1).ok_or(Error::<T>::Overflow)? );
+
+			Self::deposit_event( RawEvent::RecipeAdded( realm, index ) );
+			Ok(())
+		}
+
+		/*
+			set_recipe
+			enabled: switch the recipe on or off
+			uses:    remaining uses, none for unlimited
+		*/
+		#[weight = 1_000_000]
+		pub fn set_recipe(
+			origin,
+			realm: RealmIndex,
+			recipe: RecipeIndex,
+			enabled: bool,
+			uses: Option<u32>
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( Self::realm_controller(realm) == Some(sender), Error::<T>::Unauthorized );
+			Recipes::<T>::try_mutate( (&realm,&recipe), |maybe| -> DispatchResult {
+				let stored = maybe.as_mut().ok_or(Error::<T>::UnknownRecipe)?;
+				stored.enabled = enabled;
+				stored.uses = uses;
+				Ok(())
+			})?;
+			Self::deposit_event( RawEvent::RecipeUpdated( realm, recipe ) );
+			Ok(())
+		}
+
+		/*
+			craft
+			realm, recipe: recipe to craft
+			items:         ingredients owned by the sender, burned
+		*/
+		#[weight = 5_000_000 + 1_000_000 * items.len() as u64]
+		#[transactional]
+		pub fn craft(
+			origin,
+			realm: RealmIndex,
+			recipe: RecipeIndex,
+			items: Vec<T::Hash>
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut stored = Self::recipe((&realm,&recipe)).ok_or(Error::<T>::UnknownRecipe)?;
+			ensure!( stored.enabled, Error::<T>::RecipeDisabled );
+			ensure!( stored.uses != Some(0), Error::<T>::RecipeExhausted );
+
+			// ingredients match the inputs exactly
+			let mut parents = Vec::with_capacity(items.len());
+			let mut needed = stored.inputs.clone();
+			for (position, item) in items.iter().enumerate() {
+				ensure!( Item::<T>::contains_key(item), Error::<T>::UnknownItem );
+				ensure!( Self::account_for_item(item) == sender, Error::<T>::Unauthorized );
+				ensure!( !items[..position].contains(item), Error::<T>::IngredientsMismatch );
+				let metadata = Self::item_metadata(item);
+				ensure!( metadata.realm == realm, Error::<T>::IngredientsMismatch );
+				let input = needed.iter_mut().find(|(class, _)| *class == metadata.class)
+					.ok_or(Error::<T>::IngredientsMismatch)?;
+				input.1 = input.1.checked_sub(1).ok_or(Error::<T>::IngredientsMismatch)?;
+				parents.push(( Self::item(item).dna, Self::item_rarity(item).unwrap_or_default() ));
+			}
+			ensure!( needed.iter().all(|(_, count)| *count == 0), Error::<T>::IngredientsMismatch );
+
+			if !stored.fee.is_zero() {
+				let treasury = T::Control::treasury( &Self::owner_for_realm(realm) )
+					.ok_or(Error::<T>::UnknownRealm)?;
+				<balances::Module<T> as Currency<_>>::transfer( &sender, &treasury, stored.fee, ExistenceRequirement::KeepAlive )?;
+			}
+
+			for item in &items {
+				Self::burn_item(item)?;
+			}
+			let id = Self::mint_with(
+				realm,
+				stored.output,
+				stored.name.clone(),
+				stored.cid.clone(),
+				&sender,
+				Some(( stored.dna, &parents[..] )),
+			)?;
+
+			if let Some(uses) = stored.uses {
+				stored.uses = Some(uses.saturating_sub(1));
+				Recipes::<T>::insert( (&realm,&recipe), stored );
+			}
+
+			T::SenseHooks::on_action( SenseAction::CreateItem, &sender );
+			Self::deposit_event( RawEvent::Crafted( realm, recipe, id, sender ) );
+			Ok(())
+		}
+
+		/*
+			transfer
+			to:   receiving account
+			item: hash of an item owned by the sender
+		*/
+		#[weight = 1_000_000 + Module::<T>::tree_transfer_weight()]
+		pub fn transfer(
+			origin,
+			to: T::AccountId,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			Self::transfer_item( &item, &to )
+		}
+
+		//
+		//	nesting
+		//
+
+		/*
+			equip
+			parent: item holding the child, owned by the sender
+			child:  unlocked root item of the sender in the realm of the parent,
+			        moves with the parent
+		*/
+		#[weight = 1_000_000]
+		pub fn equip(
+			origin,
+			parent: T::Hash,
+			child: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &parent, &sender )?;
+			Self::ensure_owner( &child, &sender )?;
+			Self::ensure_unlocked( &child )?;
+			ensure!( Self::item_lock( &Self::root_item(&parent) ).is_none(), Error::<T>::ItemLocked );
+
+			// items of a realm only hold items of the same realm
+			ensure!( Self::realm_of(&child) == Self::realm_of(&parent), Error::<T>::RealmMismatch );
+
+			// no cycles, bounded depth, width and size
+			ensure!( child != parent && !Self::ancestors(&parent).contains(&child), Error::<T>::InvalidNesting );
+			ensure!(
+				Self::ancestors(&parent).len() as u32 + 1 + Self::height(&child) <= T::MaxItemDepth::get(),
+				Error::<T>::NestingTooDeep
+			);
+			let mut children = Self::item_children(&parent);
+			ensure!( (children.len() as u32) < T::MaxItemChildren::get(), Error::<T>::TooManyChildren );
+			let size = Self::item_tree( &Self::root_item(&parent) ).len() + Self::item_tree(&child).len();
+			ensure!( size as u32 <= T::MaxTreeSize::get(), Error::<T>::TreeTooLarge );
+
+			children.push(child);
+			ItemChildren::<T>::insert( &parent, children );
+			ItemParent::<T>::insert( &child, parent );
+			Self::deposit_event( RawEvent::Equipped( parent, child ) );
+			Ok(())
+		}
+
+		/*
+			unequip
+			child: returns to the root owner as a root item
+		*/
+		#[weight = 1_000_000]
+		pub fn unequip(
+			origin,
+			child: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &child, &sender )?;
+			let parent = Self::item_parent(&child).ok_or(Error::<T>::NotEquipped)?;
+			ensure!( Self::item_lock( &Self::root_item(&parent) ).is_none(), Error::<T>::ItemLocked );
+
+			ItemChildren::<T>::mutate( &parent, |children| children.retain(|c| c != &child) );
+			ItemParent::<T>::remove(&child);
+			Self::deposit_event( RawEvent::Unequipped( parent, child ) );
+			Ok(())
+		}
+
+		//
+		//	rentals
+		//
+
+		/*
+			lend
+			item:  unlocked root item of the sender, locked until the rental ends
+			user:  account using the item
+			until: last block of use
+			fee:   paid by the user on `rent`, without a fee the rental starts right away
+		*/
+		#[weight = 1_000_000]
+		pub fn lend(
+			origin,
+			item: T::Hash,
+			user: T::AccountId,
+			until: T::BlockNumber,
+			fee: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			ensure!( user != sender, Error::<T>::Unauthorized );
+			let now = <system::Module<T>>::block_number();
+			ensure!( until > now, Error::<T>::RentalEnded );
+			ensure!( until <= now.saturating_add(T::MaxRentalDuration::get()), Error::<T>::RentalTooLong );
+
+			let end = until.saturating_add(One::one());
+			ensure!(
+				(Self::rentals_ending(&end).len() as u32) < T::MaxRentalsPerBlock::get(),
+				Error::<T>::RentalsPerBlockExceeded
+			);
+
+			Self::lock_item( &item, ItemLock::Rented )?;
+			RentalsEnding::<T>::mutate( &end, |items| items.push(item) );
+			if fee.is_zero() {
+				ItemUser::<T>::insert( &item, (user.clone(), until) );
+				Self::deposit_event( RawEvent::Rented( item, user, until ) );
+			} else {
+				PendingRentals::<T>::insert( &item, (user.clone(), until, fee) );
+				Self::deposit_event( RawEvent::Lent( item, user, until, fee ) );
+			}
+			Ok(())
+		}
+
+		/*
+			rent
+			the user of a pending rental pays the fee to the owner
+		*/
+		#[weight = 1_000_000]
+		#[transactional]
+		pub fn rent(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let (user, until, fee) = Self::pending_rental(&item).ok_or(Error::<T>::UnknownRental)?;
+			ensure!( user == sender, Error::<T>::Unauthorized );
+			ensure!( until >= <system::Module<T>>::block_number(), Error::<T>::RentalEnded );
+
+			let owner = Self::account_for_item(&item);
+			<balances::Module<T> as Currency<_>>::transfer( &sender, &owner, fee, ExistenceRequirement::KeepAlive )?;
+			PendingRentals::<T>::remove(&item);
+			ItemUser::<T>::insert( &item, (sender.clone(), until) );
+			Self::deposit_event( RawEvent::Rented( item, sender, until ) );
+			Ok(())
+		}
+
+		/*
+			cancel_lend
+			the owner withdraws a rental which was not paid yet
+		*/
+		#[weight = 1_000_000]
+		pub fn cancel_lend(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			let (_, until, _) = Self::pending_rental(&item).ok_or(Error::<T>::UnknownRental)?;
+			RentalsEnding::<T>::mutate( &until.saturating_add(One::one()), |items| items.retain(|i| i != &item) );
+			Self::end_rental(&item);
+			Ok(())
+		}
+
+		//
+		//	operators
+		//
+
+		/*
+			set_operator
+			realm:    realm of the controller
+			operator: account, e.g. a contract, minting and moving items of the realm
+			allowed:  grant or revoke
+		*/
+		#[weight = 1_000_000]
+		pub fn set_operator(
+			origin,
+			realm: RealmIndex,
+			operator: T::AccountId,
+			allowed: bool
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( Self::realm_controller(realm) == Some(sender), Error::<T>::Unauthorized );
+			if allowed {
+				RealmOperators::<T>::insert( (realm, &operator), true );
+			} else {
+				RealmOperators::<T>::remove( (realm, &operator) );
+			}
+			Self::deposit_event( RawEvent::OperatorSet( realm, operator, allowed ) );
+			Ok(())
+		}
+
+		//
+		//	market
+		//
+
+		/*
+			set_royalty
+			realm:   realm of the controller
+			royalty: share of every sale paid into the body treasury
+		*/
+		#[weight = 1_000_000]
+		pub fn set_royalty(
+			origin,
+			realm: RealmIndex,
+			royalty: Permill
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( Self::realm_controller(realm) == Some(sender), Error::<T>::Unauthorized );
+			ensure!( royalty <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh );
+			RealmRoyalty::insert( realm, royalty );
+			Self::deposit_event( RawEvent::RoyaltySet( realm, royalty ) );
+			Ok(())
+		}
+
+		/*
+			list
+			item:  owned by the sender, locked until delisted or sold
+			price: fixed price
+		*/
+		#[weight = 1_000_000]
+		pub fn list(
+			origin,
+			item: T::Hash,
+			price: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			Self::lock_item( &item, ItemLock::Listed )?;
+			Listings::<T>::insert( &item, TangramListing { seller: sender.clone(), price, sale: Sale::Fixed } );
+			Self::deposit_event( RawEvent::Listed( item, sender, price ) );
+			Ok(())
+		}
+
+		/*
+			start_auction
+			item:    owned by the sender, locked until settled
+			reserve: minimum bid
+			end:     last block to bid
+		*/
+		#[weight = 1_000_000]
+		pub fn start_auction(
+			origin,
+			item: T::Hash,
+			reserve: T::Balance,
+			end: T::BlockNumber
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner( &item, &sender )?;
+			ensure!( end > <system::Module<T>>::block_number(), Error::<T>::AuctionEnded );
+			Self::lock_item( &item, ItemLock::Listed )?;
+			Listings::<T>::insert( &item, TangramListing { seller: sender, price: reserve, sale: Sale::Auction(end, None) } );
+			Self::deposit_event( RawEvent::AuctionStarted( item, reserve, end ) );
+			Ok(())
+		}
+
+		/*
+			delist
+			fixed price listings and auctions without bids
+		*/
+		#[weight = 1_000_000]
+		pub fn delist(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let listing = Self::listing(&item).ok_or(Error::<T>::NotListed)?;
+			ensure!( listing.seller == sender, Error::<T>::Unauthorized );
+			if let Sale::Auction(_, Some(_)) = listing.sale {
+				return Err(Error::<T>::AuctionHasBids.into())
+			}
+			Self::close_listing( &item );
+			Self::deposit_event( RawEvent::Delisted( item ) );
+			Ok(())
+		}
+
+		/*
+			buy
+			item:      listed for a fixed price
+			max_price: the price the buyer agrees to
+		*/
+		#[weight = 2_000_000 + Module::<T>::tree_transfer_weight()]
+		#[transactional]
+		pub fn buy(
+			origin,
+			item: T::Hash,
+			max_price: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let listing = Self::listing(&item).ok_or(Error::<T>::NotListed)?;
+			ensure!( listing.sale == Sale::Fixed, Error::<T>::NotListed );
+			ensure!( listing.price <= max_price, Error::<T>::PriceTooLow );
+			ensure!( listing.seller != sender, Error::<T>::Unauthorized );
+
+			Self::pay_sale( &item, &sender, &listing.seller, listing.price, false )?;
+			Self::close_listing( &item );
+			Self::transfer_item( &item, &sender )?;
+			Self::deposit_event( RawEvent::Sold( item, listing.seller, sender, listing.price ) );
+			Ok(())
+		}
+
+		/*
+			bid
+			amount: reserved, at least the reserve and above the highest bid,
+			the outbid amount is returned
+		*/
+		#[weight = 2_000_000]
+		#[transactional]
+		pub fn bid(
+			origin,
+			item: T::Hash,
+			amount: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut listing = Self::listing(&item).ok_or(Error::<T>::NotListed)?;
+			ensure!( listing.seller != sender, Error::<T>::Unauthorized );
+			let (end, highest) = match listing.sale {
+				Sale::Auction(end, highest) => (end, highest),
+				Sale::Fixed => return Err(Error::<T>::NotListed.into()),
 			};
+			ensure!( <system::Module<T>>::block_number() <= end, Error::<T>::AuctionEnded );
+			ensure!( amount >= listing.price, Error::<T>::PriceTooLow );
+			if let Some((bidder, highest)) = highest {
+				ensure!( amount > highest, Error::<T>::PriceTooLow );
+				<balances::Module<T>>::unreserve( &bidder, highest );
+			}
+			<balances::Module<T>>::reserve( &sender, amount )?;
+
+			listing.sale = Sale::Auction(end, Some(( sender.clone(), amount )));
+			Listings::<T>::insert( &item, listing );
+			Self::deposit_event( RawEvent::Bid( item, sender, amount ) );
+			Ok(())
+		}
 
-			// 3. mint
-			match Self::mint(
-				&who,
-				item
-			) {
-				Ok(id) => {
-					// 4. store metadata
-					ItemMetadata::<T>::insert(id, metadata );
-
-					let itemIndex = Self::next_item_index((&realm,&class));
-					let nextItemIndex = itemIndex.checked_add(1).ok_or(Error::<T>::Overflow)?;
-					NextItemIndex::insert((&realm,&class), nextItemIndex);
-					Self::deposit_event( RawEvent::Minted( id, dna, who ) );
+		/*
+			settle_auction
+			anyone after the end: the highest bid buys the item,
+			without bids it is delisted
+		*/
+		#[weight = 2_000_000 + Module::<T>::tree_transfer_weight()]
+		#[transactional]
+		pub fn settle_auction(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let listing = Self::listing(&item).ok_or(Error::<T>::NotListed)?;
+			let (end, highest) = match listing.sale {
+				Sale::Auction(end, highest) => (end, highest),
+				Sale::Fixed => return Err(Error::<T>::NotListed.into()),
+			};
+			ensure!( <system::Module<T>>::block_number() > end, Error::<T>::AuctionRunning );
+
+			Self::close_listing( &item );
+			match highest {
+				Some((bidder, amount)) => {
+					Self::pay_sale( &item, &bidder, &listing.seller, amount, true )?;
+					Self::transfer_item( &item, &bidder )?;
+					Self::deposit_event( RawEvent::Sold( item, listing.seller, bidder, amount ) );
 				},
-				Err(err) => Err(err)?
+				None => Self::deposit_event( RawEvent::Delisted( item ) ),
+			}
+			Ok(())
+		}
+
+		/*
+			make_offer
+			amount: at least the existential deposit, reserved until
+			withdrawn or accepted, replaces a previous offer of the sender
+		*/
+		#[weight = 1_000_000]
+		#[transactional]
+		pub fn make_offer(
+			origin,
+			item: T::Hash,
+			amount: T::Balance
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( Item::<T>::contains_key(&item), Error::<T>::UnknownItem );
+			ensure!( Self::account_for_item(&item) != sender, Error::<T>::Unauthorized );
+			ensure!( amount >= <T as balances::Config>::ExistentialDeposit::get(), Error::<T>::PriceTooLow );
+
+			let mut offers = Self::offers(&item);
+			if let Some(position) = offers.iter().position(|(bidder, _)| bidder == &sender) {
+				let (_, previous) = offers.remove(position);
+				<balances::Module<T>>::unreserve( &sender, previous );
+			}
+			ensure!( offers.len() < T::MaxOffers::get() as usize, Error::<T>::TooManyOffers );
+			<balances::Module<T>>::reserve( &sender, amount )?;
+			offers.push(( sender.clone(), amount ));
+			Offers::<T>::insert( &item, offers );
+
+			Self::deposit_event( RawEvent::OfferMade( item, sender, amount ) );
+			Ok(())
+		}
+
+		#[weight = 1_000_000]
+		pub fn withdraw_offer(
+			origin,
+			item: T::Hash
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut offers = Self::offers(&item);
+			let position = offers.iter().position(|(bidder, _)| bidder == &sender)
+				.ok_or(Error::<T>::UnknownOffer)?;
+			let (_, amount) = offers.remove(position);
+			<balances::Module<T>>::unreserve( &sender, amount );
+			Offers::<T>::insert( &item, offers );
+
+			Self::deposit_event( RawEvent::OfferWithdrawn( item, sender ) );
+			Ok(())
+		}
+
+		/*
+			accept_offer
+			the owner sells to `buyer` for its offer,
+			a fixed price listing is closed, auctions can not accept offers
+		*/
+		#[weight = 2_000_000 + Module::<T>::tree_transfer_weight()]
+		#[transactional]
+		pub fn accept_offer(
+			origin,
+			item: T::Hash,
+			buyer: T::AccountId
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!( Item::<T>::contains_key(&item), Error::<T>::UnknownItem );
+			ensure!( Self::account_for_item(&item) == sender, Error::<T>::Unauthorized );
+			if let Some(listing) = Self::listing(&item) {
+				ensure!( listing.sale == Sale::Fixed, Error::<T>::AuctionRunning );
+				Self::close_listing( &item );
 			}
+
+			let mut offers = Self::offers(&item);
+			let position = offers.iter().position(|(bidder, _)| bidder == &buyer)
+				.ok_or(Error::<T>::UnknownOffer)?;
+			let (_, amount) = offers.remove(position);
+			Offers::<T>::insert( &item, offers );
+
+			Self::pay_sale( &item, &buyer, &sender, amount, true )?;
+			Self::transfer_item( &item, &buyer )?;
+			Self::deposit_event( RawEvent::Sold( item, sender, buyer, amount ) );
 			Ok(())
 		}
 
@@ -465,6 +1423,441 @@ decl_module! {
 
 impl<T: Config> Module<T> {
 
+	// layout is unchanged, only the version is recorded
+	fn migrate_to_v1() -> Weight {
+		StorageVersion::put(Releases::V1);
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	// class metadata gets a rarity table, legacy classes roll low only
+	fn migrate_to_v2() -> Weight {
+		let mut count: Weight = 0;
+		ClassMetadata::<T>::translate::<OldClassMetadata<T::Hash, T::BlockNumber>, _>(
+			|_, old| {
+				count = count.saturating_add(1);
+				Some(TangramClassMetadata {
+					id: old.id,
+					name: old.name,
+					cid: old.cid,
+					created: old.created,
+					mutated: old.mutated,
+					f: old.f,
+					rarity: RarityTable::default(),
+				})
+			}
+		);
+
+		StorageVersion::put(Releases::V2);
+		T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_add(1))
+	}
+
+	/// storage invariants, checked against state snapshots after migrations
+	#[cfg(feature = "std")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for (account, items) in ItemsForAccount::<T>::iter() {
+			if Self::total_for_account(&account) != items.len() as u64 {
+				return Err("item count does not match item list")
+			}
+			for item in &items {
+				if Self::account_for_item(item) != account {
+					return Err("listed item owned by another account")
+				}
+			}
+		}
+		if Self::storage_version() != Releases::V2 {
+			return Err("storage version not migrated")
+		}
+		Ok(())
+	}
+
+	/// mint an item of a class in a realm for `who`
+	/// also used by other pallets, e.g. to reward contributors
+	pub fn mint_item(
+		realm: RealmIndex,
+		class: ClassIndex,
+		name: Vec<u8>,
+		cid: Vec<u8>,
+		who: &T::AccountId,
+	) -> Result<T::Hash, DispatchError> {
+		Self::mint_with( realm, class, name, cid, who, None )
+	}
+
+	// mint, crafted items derive their dna from their parents' (dna, rarity)
+	fn mint_with(
+		realm: RealmIndex,
+		class: ClassIndex,
+		name: Vec<u8>,
+		cid: Vec<u8>,
+		who: &T::AccountId,
+		parents: Option<(CraftDna, &[(T::Hash, Rarity)])>,
+	) -> Result<T::Hash, DispatchError> {
+
+		ensure!( realm < NextRealmIndex::get(), Error::<T>::UnknownRealm );
+		ensure!( !Self::realm_archived(&realm), Error::<T>::RealmArchived );
+		ensure!( class < Self::next_class_index(&realm), Error::<T>::UnknownClass );
+		ensure!(
+			Self::next_item_index((&realm,&class)).saturating_add(Self::outstanding_redeemables((&realm,&class)))
+				< Self::max_items((&realm,&class)),
+			Error::<T>::MaxItemsReached
+		);
+
+		// 1. lock
+		// T::Currency::set_lock(
+		// 	MODULE_ID,
+		// 	&who,
+		// 	T::CreateTokenDeposit,
+		// 	WithdrawReasons::Fee | WithdrawReasons::Reserve
+		// );
+
+		// 2. determine rarity based on time since initial invocation
+		let now = <system::Module<T>>::block_number();
+		let first = Self::first_minted((&realm,&class)).unwrap_or(now);
+		let age = now.saturating_sub(first).saturated_into::<u32>();
+		let table = Self::rarity_table(realm, class);
+
+		// 3. generate based on rarity levels,
+		// unique per item, several items can be minted in one block
+		let dob = <T as Config>::Time::now();
+		let index = Self::next_item_index((&realm,&class));
+		let seed = <T as Config>::Randomness::random( &(realm, class, index, &name).encode() );
+		let (rarity, dna) = Self::generate_dna( &table, seed, realm, class, index, age );
+		let (rarity, dna) = match parents {
+			Some((mode, parents)) => Self::inherit_dna( mode, parents, dna ),
+			None => (rarity, dna),
+		};
+
+		let item = TangramItem {
+			dob: dob,
+			dna: dna
+		};
+
+		let metadata = TangramMetadata {
+			realm: realm.clone(),
+			class: class.clone(),
+			name: name.clone(),
+			cid: cid.clone(),
+		};
+
+		// 3. mint
+		let id = Self::mint(
+			who,
+			item
+		)?;
+
+		// 4. store metadata
+		ItemMetadata::<T>::insert(id, metadata );
+		ItemRarity::<T>::insert(id, rarity);
+		if Self::first_minted((&realm,&class)).is_none() {
+			FirstMinted::<T>::insert( (&realm,&class), now );
+		}
+		ItemByIndex::<T>::insert((&realm,&class,&index), id.clone());
+
+		let nextItemIndex = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+		NextItemIndex::insert((&realm,&class), nextItemIndex);
+		Self::deposit_event( RawEvent::Minted( id, dna, who.clone() ) );
+
+		Ok(id)
+	}
+
+	/// dna of item `index` in a class for `seed`, `age` blocks after the first mint:
+	/// rarity | epic 3 | rare 4 | high 4 | low 20 bytes,
+	/// segments above the rarity of the item stay empty
+	pub fn generate_dna(
+		table: &RarityTable,
+		seed: T::Hash,
+		realm: RealmIndex,
+		class: ClassIndex,
+		index: ItemIndex,
+		age: u32,
+	) -> (Rarity, T::Hash) {
+		let mut dna = T::Hashing::hash_of( &(seed, realm, class, index) );
+		let bytes = dna.as_mut();
+		let roll = u16::from_le_bytes([ bytes[0], bytes[1] ]) % RarityTable::PARTS;
+		let rarity = table.tier( roll, age );
+		let empty = match rarity {
+			Rarity::EPIC => 1,
+			Rarity::RARE => 4,
+			Rarity::HIGH => 8,
+			Rarity::LOW => 12,
+		};
+		let len = bytes.len();
+		for byte in &mut bytes[1..empty.min(len)] { *byte = 0; }
+		bytes[0] = rarity as u8;
+		(rarity, dna)
+	}
+
+	// crafted dna keeps the rarity segments of its parents,
+	// the low segment stays fresh so the item is unique
+	fn inherit_dna( mode: CraftDna, parents: &[(T::Hash, Rarity)], fresh: T::Hash ) -> (Rarity, T::Hash) {
+		let rarity = match mode {
+			CraftDna::Inherited => parents.first().map(|parent| parent.1).unwrap_or_default(),
+			CraftDna::Combined => parents.iter().map(|parent| parent.1).max().unwrap_or_default(),
+		};
+		let mut dna = fresh;
+		let bytes = dna.as_mut();
+		for position in 1..12.min(bytes.len()) {
+			bytes[position] = match mode {
+				CraftDna::Inherited => parents.first().map(|parent| parent.0.as_ref()[position]).unwrap_or(0),
+				CraftDna::Combined => parents.iter().fold(0, |byte, parent| byte ^ parent.0.as_ref()[position]),
+			};
+		}
+		bytes[0] = rarity as u8;
+		(rarity, dna)
+	}
+
+	/// rarity of an item
+	pub fn rarity_of( item: &T::Hash ) -> Option<Rarity> {
+		Self::item_rarity(item)
+	}
+
+	/// rarity table of a class
+	pub fn rarity_table( realm: RealmIndex, class: ClassIndex ) -> RarityTable {
+		Self::class_metadata((realm, class)).rarity
+	}
+
+	/// existing item owned by `who`
+	pub fn ensure_owner( item: &T::Hash, who: &T::AccountId ) -> DispatchResult {
+		ensure!( AccountForItem::<T>::contains_key(item), Error::<T>::UnknownItem );
+		ensure!( &Self::account_for_item(item) == who, Error::<T>::Unauthorized );
+		Ok(())
+	}
+
+	/// items are transferable and burnable while unlocked and not equipped
+	pub fn ensure_unlocked( item: &T::Hash ) -> DispatchResult {
+		ensure!( Self::item_lock(item).is_none(), Error::<T>::ItemLocked );
+		ensure!( Self::item_parent(item).is_none(), Error::<T>::ItemEquipped );
+		Ok(())
+	}
+
+	/// items holding an item, from its parent up to the root
+	pub fn ancestors( item: &T::Hash ) -> Vec<T::Hash> {
+		let mut ancestors = Vec::new();
+		let mut current = *item;
+		while let Some(parent) = Self::item_parent(&current) {
+			ancestors.push(parent);
+			current = parent;
+		}
+		ancestors
+	}
+
+	/// the item at the top of the tree of an item
+	pub fn root_item( item: &T::Hash ) -> T::Hash {
+		Self::ancestors(item).last().copied().unwrap_or(*item)
+	}
+
+	// levels of items below an item
+	fn height( item: &T::Hash ) -> u32 {
+		Self::item_children(item).iter()
+			.map(|child| Self::height(child).saturating_add(1))
+			.max()
+			.unwrap_or(0)
+	}
+
+	/// the full tree of an item, depth first with the depth below `item`
+	pub fn item_tree( item: &T::Hash ) -> Vec<(T::Hash, u32)> {
+		let mut tree = Vec::new();
+		let mut stack = vec![ (*item, 0u32) ];
+		while let Some((current, depth)) = stack.pop() {
+			tree.push(( current, depth ));
+			for child in Self::item_children(&current).iter().rev() {
+				stack.push(( *child, depth + 1 ));
+			}
+		}
+		tree
+	}
+
+	/// account using an item: the renter while its root item is rented,
+	/// otherwise the owner
+	pub fn user_of( item: &T::Hash ) -> Option<T::AccountId> {
+		if !AccountForItem::<T>::contains_key(item) { return None }
+		let root = Self::root_item(item);
+		match Self::item_user(&root) {
+			Some((user, _)) => Some(user),
+			None => Some( Self::account_for_item(&root) ),
+		}
+	}
+
+	// usage returns to the owner and the item is unlocked
+	fn end_rental( item: &T::Hash ) {
+		ItemUser::<T>::remove(item);
+		PendingRentals::<T>::remove(item);
+		if Self::item_lock(item) == Some(ItemLock::Rented) {
+			ItemLocks::<T>::remove(item);
+		}
+		Self::deposit_event( RawEvent::RentalEnded( *item ) );
+	}
+
+	fn lock_item( item: &T::Hash, lock: ItemLock ) -> DispatchResult {
+		Self::ensure_unlocked(item)?;
+		ItemLocks::<T>::insert( item, lock );
+		Ok(())
+	}
+
+	fn close_listing( item: &T::Hash ) {
+		Listings::<T>::remove(item);
+		ItemLocks::<T>::remove(item);
+	}
+
+	// pay the seller, the realm royalty goes to the treasury of its body,
+	// reserved payments are taken from the buyer's reserve
+	fn pay_sale(
+		item: &T::Hash,
+		buyer: &T::AccountId,
+		seller: &T::AccountId,
+		amount: T::Balance,
+		reserved: bool,
+	) -> DispatchResult {
+		let realm = Self::item_metadata(item).realm;
+		let treasury = T::Control::treasury( &Self::owner_for_realm(realm) );
+		let royalty = match treasury {
+			Some(_) => Self::realm_royalty(realm) * amount,
+			None => Zero::zero(),
+		};
+		let proceeds = amount.saturating_sub(royalty);
+
+		let pay = |to: &T::AccountId, value: T::Balance| -> DispatchResult {
+			if value.is_zero() { return Ok(()) }
+			if reserved {
+				let missing = <balances::Module<T>>::repatriate_reserved( buyer, to, value, BalanceStatus::Free )?;
+				ensure!( missing.is_zero(), Error::<T>::BalanceTooLow );
+			} else {
+				<balances::Module<T> as Currency<_>>::transfer( buyer, to, value, ExistenceRequirement::KeepAlive )?;
+			}
+			Ok(())
+		};
+		if let Some(treasury) = treasury {
+			pay( &treasury, royalty )?;
+		}
+		pay( seller, proceeds )?;
+
+		if !royalty.is_zero() {
+			Self::deposit_event( RawEvent::RoyaltyPaid( *item, realm, royalty ) );
+		}
+		Ok(())
+	}
+
+	/// move an item and the items it holds to `to`
+	pub fn transfer_item( item: &T::Hash, to: &T::AccountId ) -> DispatchResult {
+
+		Self::ensure_unlocked(item)?;
+		let owner = Self::account_for_item(item);
+		if &owner == to { return Ok(()) }
+
+		for (moved, _) in Self::item_tree(item) {
+			Self::move_item( &moved, &owner, to );
+		}
+
+		Self::deposit_event( RawEvent::Transferred( *item, to.clone() ) );
+		Ok(())
+
+	}
+
+	/// weight of moving the largest tree, `MaxTreeSize` items
+	pub fn tree_transfer_weight() -> Weight {
+		// per item: children, both item lists and totals, the owner
+		T::DbWeight::get().reads_writes(5, 5).saturating_mul(T::MaxTreeSize::get() as Weight)
+	}
+
+	fn move_item( item: &T::Hash, from: &T::AccountId, to: &T::AccountId ) {
+		ItemsForAccount::<T>::mutate( from, |items| items.retain(|i| i != item) );
+		ItemsForAccount::<T>::mutate( to, |items| {
+			match items.binary_search(item) {
+				Ok(_pos) => {} // should never happen
+				Err(pos) => items.insert(pos, *item),
+			}
+		});
+		TotalForAccount::<T>::mutate( from, |total| *total = total.saturating_sub(1) );
+		TotalForAccount::<T>::mutate( to, |total| *total += 1 );
+		AccountForItem::<T>::insert( item, to.clone() );
+	}
+
+	/// owner of an item, none if unknown
+	pub fn owner_of( item: &T::Hash ) -> Option<T::AccountId> {
+		if !AccountForItem::<T>::contains_key(item) { return None }
+		Some( Self::account_for_item(item) )
+	}
+
+	/// realm of an item
+	pub fn realm_of( item: &T::Hash ) -> RealmIndex {
+		Self::item_metadata(item).realm
+	}
+
+	/// mint an item of an operated realm for `who`
+	#[transactional]
+	pub fn operator_mint(
+		operator: &T::AccountId,
+		realm: RealmIndex,
+		class: ClassIndex,
+		name: Vec<u8>,
+		cid: Vec<u8>,
+		who: &T::AccountId,
+	) -> Result<T::Hash, DispatchError> {
+		ensure!( Self::is_operator((realm, operator)), Error::<T>::NotOperator );
+		Self::mint_item( realm, class, name, cid, who )
+	}
+
+	/// move an item owned by the operator, or by `on_behalf` when
+	/// the operator operates the realm of the item
+	#[transactional]
+	pub fn operator_transfer(
+		operator: &T::AccountId,
+		on_behalf: &T::AccountId,
+		item: &T::Hash,
+		to: &T::AccountId,
+	) -> DispatchResult {
+		ensure!( AccountForItem::<T>::contains_key(item), Error::<T>::UnknownItem );
+		let owner = Self::account_for_item(item);
+		if &owner != operator {
+			ensure!( &owner == on_behalf, Error::<T>::Unauthorized );
+			ensure!( Self::is_operator((Self::realm_of(item), operator)), Error::<T>::NotOperator );
+		}
+		Self::transfer_item( item, to )
+	}
+
+	/// controller of the body owning a realm
+	pub fn realm_controller( realm: RealmIndex ) -> Option<T::AccountId> {
+		if !OwnerRealm::<T>::contains_key(realm) { return None }
+		T::Control::controller( &Self::owner_for_realm(realm) )
+	}
+
+	// split the cost of a redeemable into (issuer, recipient) share
+	fn shares( cost: T::Balance, payable: Payable ) -> (T::Balance, T::Balance) {
+		let issuer = match payable {
+			Payable::SENDER => cost,
+			Payable::RECIPIENT => Zero::zero(),
+			Payable::SPLIT => cost / T::Balance::from(2u32),
+		};
+		(issuer, cost.saturating_sub(issuer))
+	}
+
+	// remove a redeemable and release its reserved supply
+	fn close_redeemable( index: RedeemableIndex, redeemable: &TangramRedeemable<T::AccountId, T::Balance, T::BlockNumber> ) {
+		Redeemables::<T>::remove(index);
+		RedeemablesForAccount::<T>::mutate( &redeemable.recipient, |redeemables| redeemables.retain(|i| *i != index) );
+		OutstandingRedeemables::mutate( (&redeemable.realm,&redeemable.class), |count| *count = count.saturating_sub(1) );
+		Redeemable::mutate( |total| *total = total.saturating_sub(1) );
+	}
+
+	/// realm of an item
+	pub fn realm_of_item( item: &T::Hash ) -> Option<RealmIndex> {
+		if !Item::<T>::contains_key(item) { return None }
+		Some( Self::item_metadata(item).realm )
+	}
+
+	/// archive all realms of an organisation, existing items remain
+	pub fn archive_realms( org: &T::Hash ) {
+		for hash in Self::realms_for_owner(org) {
+			let index = Self::realm_by_hash(&hash).index;
+			RealmArchived::insert( index, true );
+			Self::deposit_event( RawEvent::RealmArchived( index ) );
+		}
+	}
+
+	/// the first realm created for an organisation
+	pub fn realm_for_owner( org: &T::Hash ) -> Option<RealmIndex> {
+		Self::realms_for_owner(org).first().map( |hash| Self::realm_by_hash(hash).index )
+	}
+
 	// type ItemId;
 
 	// / return the total number of items per class
@@ -497,11 +1890,7 @@ impl<T: Config> Module<T> {
 	) -> dispatch::result::Result< T::Hash, dispatch::DispatchError> {
 
 		let id = T::Hashing::hash_of(&item);
-
-		//     ensure!(
-		//         !AccountForItem::<T, I>::contains_key(&item_id),
-		//         Error::<T, I>::ItemExists
-		//     );
+		ensure!( !Item::<T>::contains_key(&id), Error::<T>::ItemExists );
 
 		//     ensure!(
 		//         Self::total_for_account(owner_account) < T::UserItemLimit::get(),
@@ -536,28 +1925,27 @@ impl<T: Config> Module<T> {
 
 	}
 
-	// fn burn(item_id: &Item<T>) -> dispatch::DispatchResult {
-	//     let owner = Self::owner_of(item_id);
-	//     ensure!(
-	//         owner != T::AccountId::default(),
-	//         Error::<T, I>::NonexistentItem
-	//     );
-
-	//     let burn_item = (*item_id, <T as Config<T>>::ItemInfo::default());
-
-	//     Total::<T>::mutate(|total| *total -= 1);
-	//     Burned::<T>::mutate(|total| *total += 1);
-	//     TotalForAccount::<T, T>::mutate(&owner, |total| *total -= 1);
-	//     ItemsForAccount::<T, T>::mutate(owner, |items| {
-	//         let pos = items
-	//             .binary_search(&burn_item)
-	//             .expect("We already checked that we have the correct owner; qed");
-	//         items.remove(pos);
-	//     });
-	//     AccountForItem::<T, T>::remove(&item_id);
-
-	//     Ok(())
-	// }
+	/// remove an item, its class supply is not released
+	pub fn burn_item( item: &T::Hash ) -> DispatchResult {
+		ensure!( Item::<T>::contains_key(item), Error::<T>::UnknownItem );
+		Self::ensure_unlocked(item)?;
+		ensure!( Self::item_children(item).is_empty(), Error::<T>::ItemHasChildren );
+		let owner = Self::account_for_item(item);
+
+		Total::mutate( |total| *total = total.saturating_sub(1) );
+		Burned::mutate( |total| *total = total.saturating_add(1) );
+		TotalForAccount::<T>::mutate( &owner, |total| *total = total.saturating_sub(1) );
+		ItemsForAccount::<T>::mutate( &owner, |items| items.retain(|i| i != item) );
+		AccountForItem::<T>::remove(item);
+		ItemRarity::<T>::remove(item);
+		Item::<T>::remove(item);
+
+		// metadata goes, the index keeps pointing to the burned item
+		ItemMetadata::<T>::remove(item);
+
+		Self::deposit_event( RawEvent::Burned( *item ) );
+		Ok(())
+	}
 
 	// fn transfer(
 	//     dest_account: &T::AccountId,
@@ -598,17 +1986,61 @@ impl<T: Config> Module<T> {
 
 }
 
+impl<T: Config> RealmProvider<T::AccountId> for Module<T> {
+	fn is_participant( realm: RealmIndex, who: &T::AccountId ) -> bool {
+		Self::items_for_account(who).iter().any(|item| Self::realm_of(item) == realm)
+	}
+}
+
 decl_event!(
 	pub enum Event<T>
 	where
 		Hash = <T as frame_system::Config>::Hash,
 		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = <T as balances::Config>::Balance,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
 	{
 		RealmCreated( u64 ),
+		RealmArchived( u64 ),
 		ClassCreated( u64, u64, u64 ),
 		Minted( Hash, Hash, AccountId ),
 		Burned( Hash ),
 		Transferred( Hash, AccountId ),
+		/// redeemable index, realm, class, recipient
+		RedeemableAdded( u64, u64, u64, AccountId ),
+		/// redeemable index, minted item, recipient
+		Redeemed( u64, Hash, AccountId ),
+		RedeemableCancelled( u64 ),
+		/// realm, class
+		RarityUpdated( u64, u64 ),
+		/// realm, recipe
+		RecipeAdded( u64, u64 ),
+		RecipeUpdated( u64, u64 ),
+		/// realm, recipe, crafted item, crafter
+		Crafted( u64, u64, Hash, AccountId ),
+		RoyaltySet( u64, Permill ),
+		/// item, seller, price
+		Listed( Hash, AccountId, Balance ),
+		/// item, reserve, end
+		AuctionStarted( Hash, Balance, BlockNumber ),
+		Delisted( Hash ),
+		Bid( Hash, AccountId, Balance ),
+		OfferMade( Hash, AccountId, Balance ),
+		OfferWithdrawn( Hash, AccountId ),
+		/// item, seller, buyer, price
+		Sold( Hash, AccountId, AccountId, Balance ),
+		/// item, realm, royalty
+		RoyaltyPaid( Hash, u64, Balance ),
+		/// parent, child
+		Equipped( Hash, Hash ),
+		Unequipped( Hash, Hash ),
+		/// item, user, until, fee
+		Lent( Hash, AccountId, BlockNumber, Balance ),
+		/// item, user, until
+		Rented( Hash, AccountId, BlockNumber ),
+		RentalEnded( Hash ),
+		/// realm, operator, allowed
+		OperatorSet( u64, AccountId, bool ),
 	}
 );
 
@@ -616,6 +2048,8 @@ decl_error! {
 	pub enum Error for Module<T: Config> {
 		/// Unknown Realm
 		UnknownRealm,
+		/// Realm Archived
+		RealmArchived,
 		/// Unknown Class
 		UnknownClass,
 		/// Unknown Item
@@ -639,6 +2073,72 @@ decl_error! {
 		/// Not enough funds to complete transaction.
 		BalanceTooLow,
 		/// Authorization Error
-		Unauthorized
+		Unauthorized,
+		/// Unknown Redeemable
+		UnknownRedeemable,
+		/// Redeemable Expired
+		RedeemableExpired,
+		/// No Recipients
+		NoRecipients,
+		/// Too many Redeemables in one call
+		TooManyRedeemables,
+		/// Too many outstanding Redeemables for an Account
+		TooManyRedeemablesForAccount,
+		/// Rarity odds exceed the parts
+		InvalidRarity,
+		/// Unknown Recipe
+		UnknownRecipe,
+		/// Recipe Disabled
+		RecipeDisabled,
+		/// Recipe has no uses left
+		RecipeExhausted,
+		/// Recipe inputs invalid
+		InvalidRecipe,
+		/// Items do not match the recipe
+		IngredientsMismatch,
+		/// Item is locked
+		ItemLocked,
+		/// Royalty above the maximum
+		RoyaltyTooHigh,
+		/// Item not listed
+		NotListed,
+		/// Price or bid too low
+		PriceTooLow,
+		/// Auction Ended
+		AuctionEnded,
+		/// Auction still running
+		AuctionRunning,
+		/// Auction has bids
+		AuctionHasBids,
+		/// Too many Offers on the Item
+		TooManyOffers,
+		/// Unknown Offer
+		UnknownOffer,
+		/// Item is held by another Item
+		ItemEquipped,
+		/// Item holds other Items
+		ItemHasChildren,
+		/// Item is not held by another Item
+		NotEquipped,
+		/// Item can not hold itself or an ancestor
+		InvalidNesting,
+		/// Nesting too deep
+		NestingTooDeep,
+		/// Item holds too many Items
+		TooManyChildren,
+		/// Tree of Items too large
+		TreeTooLarge,
+		/// Items of different Realms
+		RealmMismatch,
+		/// Unknown Rental
+		UnknownRental,
+		/// Rental Ended
+		RentalEnded,
+		/// Rental longer than allowed
+		RentalTooLong,
+		/// Max rentals ending per block exceeded
+		RentalsPerBlockExceeded,
+		/// Not an Operator of the Realm
+		NotOperator,
 	}
 }
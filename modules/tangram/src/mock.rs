@@ -1 +1,189 @@
-//
\ No newline at end of file
+// mock runtime for the tangram tests
+
+#![cfg(test)]
+
+use crate as tangram;
+use frame_support::{ parameter_types, traits::{ OnFinalize, OnInitialize, Randomness, Time } };
+use primitives::BodyProvider;
+use sp_core::H256;
+use sp_runtime::{ testing::Header, traits::{ BlakeTwo256, Hash, IdentityLookup }, Permill };
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Tangram: tangram::{Module, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const TREASURY: u64 = 100;
+
+// alice controls the body owning realm 0, bob the body owning realm 1
+pub fn org() -> H256 { H256::repeat_byte(1) }
+pub fn other_org() -> H256 { H256::repeat_byte(2) }
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 50;
+}
+impl balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = MaxLocks;
+	type WeightInfo = ();
+}
+
+pub struct MockTime;
+impl Time for MockTime {
+	type Moment = u64;
+	fn now() -> u64 { 0 }
+}
+
+thread_local! {
+	static NONCE: RefCell<u64> = const { RefCell::new(0) };
+}
+
+// every call returns a fresh value
+pub struct MockRandomness;
+impl Randomness<H256> for MockRandomness {
+	fn random(subject: &[u8]) -> H256 {
+		let nonce = NONCE.with(|n| { *n.borrow_mut() += 1; *n.borrow() });
+		BlakeTwo256::hash_of(&(subject, nonce))
+	}
+}
+
+pub struct Bodies;
+impl BodyProvider<u64, H256> for Bodies {
+	fn controller(body: &H256) -> Option<u64> {
+		if *body == org() { Some(ALICE) }
+		else if *body == other_org() { Some(BOB) }
+		else { None }
+	}
+	fn treasury(body: &H256) -> Option<u64> {
+		if *body == org() { Some(TREASURY) } else { None }
+	}
+	fn is_member(_: &H256, _: &u64) -> bool { false }
+}
+
+parameter_types! {
+	pub const CreateRealmDeposit: u64 = 0;
+	pub const CreateClassDeposit: u64 = 0;
+	pub const CreateItemDeposit: u64 = 0;
+	pub const MaxRealmsPerOrg: u64 = 1;
+	pub const MaxClassesPerRealm: u64 = 16;
+	pub const MaxTokenPerClass: u128 = 1_000;
+	pub const MaxTotalToken: u128 = 1_000_000;
+	pub const MaxRedeemables: u32 = 4;
+	pub const MaxRedeemablesPerAccount: u32 = 2;
+	pub const MaxIngredients: u32 = 4;
+	pub const MaxRoyalty: Permill = Permill::from_percent(20);
+	pub const MaxOffers: u32 = 2;
+	pub const MaxItemChildren: u32 = 2;
+	pub const MaxItemDepth: u32 = 2;
+	pub const MaxTreeSize: u32 = 4;
+	pub const MaxRentalDuration: u64 = 100;
+	pub const MaxRentalExpiries: u32 = 2;
+	pub const MaxRentalsPerBlock: u32 = 3;
+}
+impl tangram::Config for Test {
+	type Time = MockTime;
+	type Randomness = MockRandomness;
+	type Currency = Balances;
+	type Event = Event;
+	type CreateRealmDeposit = CreateRealmDeposit;
+	type CreateClassDeposit = CreateClassDeposit;
+	type CreateItemDeposit = CreateItemDeposit;
+	type MaxRealmsPerOrg = MaxRealmsPerOrg;
+	type MaxClassesPerRealm = MaxClassesPerRealm;
+	type MaxTokenPerClass = MaxTokenPerClass;
+	type MaxTotalToken = MaxTotalToken;
+	type NextRealmIndex = ();
+	type NextClassIndex = ();
+	type NextItemIndex = ();
+	type TotalIndex = ();
+	type BurnedIndex = ();
+	type SenseHooks = ();
+	type Control = Bodies;
+	type MaxRedeemables = MaxRedeemables;
+	type MaxRedeemablesPerAccount = MaxRedeemablesPerAccount;
+	type MaxIngredients = MaxIngredients;
+	type MaxRoyalty = MaxRoyalty;
+	type MaxOffers = MaxOffers;
+	type MaxItemChildren = MaxItemChildren;
+	type MaxItemDepth = MaxItemDepth;
+	type MaxTreeSize = MaxTreeSize;
+	type MaxRentalDuration = MaxRentalDuration;
+	type MaxRentalExpiries = MaxRentalExpiries;
+	type MaxRentalsPerBlock = MaxRentalsPerBlock;
+}
+
+// realm 0 of `org` with classes 0 and 1, realm 1 of `other_org` with class 0
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> {
+		balances: vec![ (ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000), (TREASURY, 1) ],
+	}.assimilate_storage(&mut t).unwrap();
+	tangram::GenesisConfig::<Test> {
+		realms: vec![ org(), other_org() ],
+		classes: vec![ (0, b"sword".to_vec(), 10), (0, b"gem".to_vec(), 10), (1, b"wand".to_vec(), 10) ],
+		items: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		Tangram::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		Tangram::on_initialize(System::block_number());
+	}
+}
+
+// mint an item of realm 0 for `who`
+pub fn mint(class: u64, who: u64) -> H256 {
+	Tangram::mint_item( 0, class, b"item".to_vec(), vec![], &who ).unwrap()
+}
@@ -1,35 +1,746 @@
-// Tests to be written here
+use crate::{ ClassMetadata, CraftDna, Error, ItemLock, OldClassMetadata, Payable, Releases, TangramClassMetadata, mock::* };
+use sp_core::H256;
+use sp_runtime::Permill;
+use primitives::{ Rarity, RarityTable };
+use frame_support::{ assert_noop, assert_ok, migration::put_storage_value, storage::unhashed, traits::OnRuntimeUpgrade, StorageMap };
 
-// use crate::{Error, mock::*};
-// use frame_support::{assert_ok, assert_noop};
+fn tangram_realm_setup() {
+	assert_eq!( Tangram::next_realm_index(), 2 );
+	assert_eq!( Tangram::next_class_index(0), 2 );
+	assert_eq!( Tangram::realm_controller(0), Some(ALICE) );
+	assert_eq!( Tangram::realm_controller(1), Some(BOB) );
+}
 
-// #[test]
-// fn it_works_for_default_value() {
-// 	new_test_ext().execute_with(|| {
-// 		// Just a dummy test for the dummy function `do_something`
-// 		// calling the `do_something` function with a value 42
-// 		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-// 		// asserting that the stored value is equal to what we stored
-// 		assert_eq!(TemplateModule::something(), Some(42));
-// 	});
-// }
+//
+//	redeemables
+//
 
-// TODO:
+#[test]
+fn only_realm_controllers_create_items() {
+	new_test_ext().execute_with(|| {
+		tangram_realm_setup();
+		assert_noop!(
+			Tangram::create_item( Origin::signed(BOB), 0, 0, vec![], vec![], BOB ),
+			Error::<Test>::Unauthorized
+		);
+		assert_noop!(
+			Tangram::create_item( Origin::signed(ALICE), 2, 0, vec![], vec![], BOB ),
+			Error::<Test>::UnknownRealm
+		);
+		assert_ok!( Tangram::create_item( Origin::signed(ALICE), 0, 0, vec![], vec![], BOB ) );
+		assert_eq!( Tangram::total_for_account(BOB), 1 );
+		assert_ok!( Tangram::check_invariants() );
+	});
+}
 
-// 1 create a realm for an organisation, next index should be 1
+#[test]
+fn redeem_splits_the_cost() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::add_redeemable(
+			Origin::signed(ALICE), 0, 0, b"voucher".to_vec(), vec![], vec![BOB], 100, Payable::SPLIT, 10
+		) );
+		assert_eq!( Balances::reserved_balance(ALICE), 50 );
+		assert_eq!( Tangram::redeemables_for_account(BOB), vec![0] );
+		assert_eq!( Tangram::outstanding_redeemables((0, 0)), 1 );
 
-// 2 create another realm for an organisation, should throw
+		assert_noop!( Tangram::redeem( Origin::signed(CHARLIE), 0 ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::redeem( Origin::signed(BOB), 0 ) );
 
-// 3 create a class for a realm, next index should be 1
+		assert_eq!( Balances::reserved_balance(ALICE), 0 );
+		assert_eq!( Balances::free_balance(ALICE), 950 );
+		assert_eq!( Balances::free_balance(BOB), 950 );
+		assert_eq!( Balances::free_balance(TREASURY), 101 );
+		assert_eq!( Tangram::total_for_account(BOB), 1 );
+		assert!( Tangram::redeemable(0).is_none() );
+		assert!( Tangram::redeemables_for_account(BOB).is_empty() );
+		assert_eq!( Tangram::outstanding_redeemables((0, 0)), 0 );
+		assert_eq!( Tangram::total_redeemed(), 1 );
+	});
+}
 
-// 4 create an item in a class in a realm, totalminted should be 1
+#[test]
+fn redeem_charges_the_payable_party() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::add_redeemable(
+			Origin::signed(ALICE), 0, 0, vec![], vec![], vec![BOB], 100, Payable::SENDER, 10
+		) );
+		assert_ok!( Tangram::add_redeemable(
+			Origin::signed(ALICE), 0, 0, vec![], vec![], vec![CHARLIE], 100, Payable::RECIPIENT, 10
+		) );
+		assert_eq!( Balances::reserved_balance(ALICE), 100 );
 
-// 5 burn an item by hash, totalburned should be 1, total should be 0
+		assert_ok!( Tangram::redeem( Origin::signed(BOB), 0 ) );
+		assert_ok!( Tangram::redeem( Origin::signed(CHARLIE), 1 ) );
+		assert_eq!( Balances::free_balance(ALICE), 900 );
+		assert_eq!( Balances::free_balance(BOB), 1_000 );
+		assert_eq!( Balances::free_balance(CHARLIE), 900 );
+		assert_eq!( Balances::free_balance(TREASURY), 201 );
+	});
+}
 
-// 6 bootstrap an org, realm index should be +1
+#[test]
+fn expired_redeemables_are_cancelled_by_anyone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::add_redeemable(
+			Origin::signed(ALICE), 0, 0, vec![], vec![], vec![BOB], 100, Payable::SENDER, 5
+		) );
+		assert_noop!( Tangram::cancel_redeemable( Origin::signed(CHARLIE), 0 ), Error::<Test>::Unauthorized );
 
-// - total items per class 0 should be max members of org
+		run_to_block(6);
+		assert_noop!( Tangram::redeem( Origin::signed(BOB), 0 ), Error::<Test>::RedeemableExpired );
+		assert_ok!( Tangram::cancel_redeemable( Origin::signed(CHARLIE), 0 ) );
+		assert_eq!( Balances::reserved_balance(ALICE), 0 );
+		assert_eq!( Balances::free_balance(ALICE), 1_000 );
+		assert_eq!( Tangram::outstanding_redeemables((0, 0)), 0 );
+	});
+}
 
-// - each member should have 1 tangram
+#[test]
+fn redeemables_reserve_supply_and_are_capped_per_account() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Tangram::add_redeemable( Origin::signed(BOB), 0, 0, vec![], vec![], vec![BOB], 0, Payable::SENDER, 5 ),
+			Error::<Test>::Unauthorized
+		);
+		assert_noop!(
+			Tangram::add_redeemable( Origin::signed(ALICE), 0, 0, vec![], vec![], vec![BOB; 5], 0, Payable::SENDER, 5 ),
+			Error::<Test>::TooManyRedeemables
+		);
+		assert_noop!(
+			Tangram::add_redeemable( Origin::signed(ALICE), 0, 0, vec![], vec![], vec![BOB; 3], 0, Payable::SENDER, 5 ),
+			Error::<Test>::TooManyRedeemablesForAccount
+		);
+		assert_eq!( Tangram::next_redeemable_index(), 0 );
 
-// 7 bootstrap an org, with same org hash should throw
+		for _ in 0..9 { mint(0, CHARLIE); }
+		assert_ok!( Tangram::add_redeemable( Origin::signed(ALICE), 0, 0, vec![], vec![], vec![BOB], 0, Payable::SENDER, 5 ) );
+		assert_noop!(
+			Tangram::add_redeemable( Origin::signed(ALICE), 0, 0, vec![], vec![], vec![CHARLIE; 2], 0, Payable::SENDER, 5 ),
+			Error::<Test>::MaxItemsReached
+		);
+		// the last item of the class is reserved for bob
+		assert_noop!(
+			Tangram::create_item( Origin::signed(ALICE), 0, 0, vec![], vec![], CHARLIE ),
+			Error::<Test>::MaxItemsReached
+		);
+		assert_ok!( Tangram::redeem( Origin::signed(BOB), 0 ) );
+	});
+}
+
+//
+//	rarity
+//
+
+#[test]
+fn rarity_tiers_follow_the_odds() {
+	let table = RarityTable { epic: 100, rare: 200, high: 300, halving: 0 };
+	assert_eq!( table.tier(99, 0), Rarity::EPIC );
+	assert_eq!( table.tier(100, 0), Rarity::RARE );
+	assert_eq!( table.tier(299, 0), Rarity::RARE );
+	assert_eq!( table.tier(300, 0), Rarity::HIGH );
+	assert_eq!( table.tier(599, 0), Rarity::HIGH );
+	assert_eq!( table.tier(600, 0), Rarity::LOW );
+	// without halving, odds stay constant
+	assert_eq!( table.tier(99, 1_000_000), Rarity::EPIC );
+}
+
+#[test]
+fn rarity_odds_halve_with_age() {
+	let table = RarityTable { epic: 100, rare: 200, high: 300, halving: 10 };
+	assert_eq!( table.tier(99, 9), Rarity::EPIC );
+	// epic 50, rare 100, high 150
+	assert_eq!( table.tier(49, 10), Rarity::EPIC );
+	assert_eq!( table.tier(50, 10), Rarity::RARE );
+	assert_eq!( table.tier(150, 10), Rarity::HIGH );
+	assert_eq!( table.tier(300, 10), Rarity::LOW );
+	// eventually everything is low
+	assert_eq!( table.tier(0, 1_000), Rarity::LOW );
+}
+
+#[test]
+fn only_controllers_set_valid_rarity_tables() {
+	new_test_ext().execute_with(|| {
+		let table = RarityTable { epic: 10_000, rare: 0, high: 0, halving: 0 };
+		assert_noop!( Tangram::set_rarity( Origin::signed(BOB), 0, 0, table ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::set_rarity( Origin::signed(ALICE), 0, 2, table ), Error::<Test>::UnknownClass );
+		assert_noop!(
+			Tangram::set_rarity( Origin::signed(ALICE), 0, 0, RarityTable { epic: 5_000, rare: 5_000, high: 1, halving: 0 } ),
+			Error::<Test>::InvalidRarity
+		);
+		assert_ok!( Tangram::set_rarity( Origin::signed(ALICE), 0, 0, table ) );
+		assert_eq!( Tangram::rarity_table(0, 0), table );
+	});
+}
+
+#[test]
+fn dna_encodes_the_rarity() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_rarity( Origin::signed(ALICE), 0, 0, RarityTable { epic: 10_000, rare: 0, high: 0, halving: 0 } ) );
+
+		let epic = mint(0, BOB);
+		assert_eq!( Tangram::rarity_of(&epic), Some(Rarity::EPIC) );
+		assert_eq!( Tangram::item(epic).dna.as_ref()[0], Rarity::EPIC as u8 );
+
+		// the default table only mints low items, their rarity segments stay empty
+		let low = mint(1, BOB);
+		let dna = Tangram::item(low).dna;
+		assert_eq!( Tangram::rarity_of(&low), Some(Rarity::LOW) );
+		assert_eq!( dna.as_ref()[..12], [0u8; 12] );
+
+		// items minted in one block are unique
+		assert_ne!( mint(1, BOB), low );
+		assert_eq!( Tangram::first_minted((0, 1)), Some(1) );
+	});
+}
+
+//
+//	crafting
+//
+
+#[test]
+fn recipes_are_validated() {
+	new_test_ext().execute_with(|| {
+		let add = |who: u64, inputs: Vec<(u64, u32)>, output: u64| Tangram::add_recipe(
+			Origin::signed(who), 0, inputs, output, vec![], vec![], 0, CraftDna::Inherited, None
+		);
+		assert_noop!( add( BOB, vec![(0, 1)], 1 ), Error::<Test>::Unauthorized );
+		assert_noop!( add( ALICE, vec![(0, 1)], 2 ), Error::<Test>::UnknownClass );
+		assert_noop!( add( ALICE, vec![(2, 1)], 1 ), Error::<Test>::UnknownClass );
+		assert_noop!( add( ALICE, vec![], 1 ), Error::<Test>::InvalidRecipe );
+		assert_noop!( add( ALICE, vec![(0, 0)], 1 ), Error::<Test>::InvalidRecipe );
+		assert_noop!( add( ALICE, vec![(0, 1), (0, 1)], 1 ), Error::<Test>::InvalidRecipe );
+		assert_noop!( add( ALICE, vec![(0, 3), (1, 2)], 1 ), Error::<Test>::InvalidRecipe );
+		assert_ok!( add( ALICE, vec![(0, 2), (1, 2)], 1 ) );
+		assert_eq!( Tangram::next_recipe_index(0), 1 );
+	});
+}
+
+#[test]
+fn crafting_burns_ingredients_and_mints_the_output() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::add_recipe(
+			Origin::signed(ALICE), 0, vec![(0, 2), (1, 1)], 1, b"crafted".to_vec(), vec![], 10, CraftDna::Combined, Some(1)
+		) );
+		let swords = [ mint(0, BOB), mint(0, BOB) ];
+		let gem = mint(1, BOB);
+		let other = mint(1, CHARLIE);
+
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, vec![ swords[0], gem ] ),
+			Error::<Test>::IngredientsMismatch
+		);
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, vec![ swords[0], swords[0], gem ] ),
+			Error::<Test>::IngredientsMismatch
+		);
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, vec![ swords[0], swords[1], other ] ),
+			Error::<Test>::Unauthorized
+		);
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 1, vec![ swords[0], swords[1], gem ] ),
+			Error::<Test>::UnknownRecipe
+		);
+
+		assert_ok!( Tangram::craft( Origin::signed(BOB), 0, 0, vec![ swords[0], swords[1], gem ] ) );
+		for item in swords.iter().chain(&[gem]) {
+			assert!( Tangram::owner_of(item).is_none() );
+		}
+		assert_eq!( Tangram::total_for_account(BOB), 1 );
+		let crafted = Tangram::items_for_account(BOB)[0];
+		assert_eq!( Tangram::item_metadata(crafted).class, 1 );
+		assert_eq!( Tangram::item_metadata(crafted).name, b"crafted".to_vec() );
+		assert_eq!( Balances::free_balance(TREASURY), 11 );
+		assert_eq!( Tangram::burend_items(), 3 );
+		assert_ok!( Tangram::check_invariants() );
+
+		// the recipe had a single use
+		let more = [ mint(0, BOB), mint(0, BOB), mint(1, BOB) ];
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, more.to_vec() ),
+			Error::<Test>::RecipeExhausted
+		);
+		assert_ok!( Tangram::set_recipe( Origin::signed(ALICE), 0, 0, false, None ) );
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, more.to_vec() ),
+			Error::<Test>::RecipeDisabled
+		);
+		assert_ok!( Tangram::set_recipe( Origin::signed(ALICE), 0, 0, true, None ) );
+		assert_ok!( Tangram::craft( Origin::signed(BOB), 0, 0, more.to_vec() ) );
+	});
+}
+
+#[test]
+fn failed_crafts_keep_the_ingredients() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::add_recipe(
+			Origin::signed(ALICE), 0, vec![(0, 1)], 1, vec![], vec![], 5_000, CraftDna::Inherited, None
+		) );
+		let sword = mint(0, BOB);
+		assert_noop!(
+			Tangram::craft( Origin::signed(BOB), 0, 0, vec![ sword ] ),
+			balances::Error::<Test, _>::InsufficientBalance
+		);
+		assert_eq!( Tangram::owner_of(&sword), Some(BOB) );
+	});
+}
+
+#[test]
+fn crafted_dna_inherits_from_the_ingredients() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_rarity( Origin::signed(ALICE), 0, 0, RarityTable { epic: 10_000, rare: 0, high: 0, halving: 0 } ) );
+		assert_ok!( Tangram::add_recipe(
+			Origin::signed(ALICE), 0, vec![(0, 1)], 1, vec![], vec![], 0, CraftDna::Inherited, None
+		) );
+		assert_ok!( Tangram::add_recipe(
+			Origin::signed(ALICE), 0, vec![(0, 1), (1, 1)], 1, vec![], vec![], 0, CraftDna::Combined, None
+		) );
+
+		// inherited: segments and rarity of the first ingredient
+		let sword = mint(0, BOB);
+		let parent = Tangram::item(sword).dna;
+		assert_ok!( Tangram::craft( Origin::signed(BOB), 0, 0, vec![ sword ] ) );
+		let crafted = Tangram::items_for_account(BOB)[0];
+		let dna = Tangram::item(crafted).dna;
+		assert_eq!( Tangram::rarity_of(&crafted), Some(Rarity::EPIC) );
+		assert_eq!( dna.as_ref()[..12], parent.as_ref()[..12] );
+		assert_ne!( dna, parent );
+
+		// combined: segments xor-ed, the highest rarity
+		let sword = mint(0, CHARLIE);
+		let gem = mint(1, CHARLIE);
+		let (a, b) = ( Tangram::item(sword).dna, Tangram::item(gem).dna );
+		assert_ok!( Tangram::craft( Origin::signed(CHARLIE), 0, 1, vec![ sword, gem ] ) );
+		let crafted = Tangram::items_for_account(CHARLIE)[0];
+		let dna = Tangram::item(crafted).dna;
+		assert_eq!( Tangram::rarity_of(&crafted), Some(Rarity::EPIC) );
+		assert_eq!( dna.as_ref()[0], Rarity::EPIC as u8 );
+		for position in 1..12 {
+			assert_eq!( dna.as_ref()[position], a.as_ref()[position] ^ b.as_ref()[position] );
+		}
+	});
+}
+
+//
+//	market
+//
+
+#[test]
+fn royalties_are_capped_and_set_by_controllers() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Tangram::set_royalty( Origin::signed(BOB), 0, Permill::from_percent(10) ),
+			Error::<Test>::Unauthorized
+		);
+		assert_noop!(
+			Tangram::set_royalty( Origin::signed(ALICE), 0, Permill::from_percent(21) ),
+			Error::<Test>::RoyaltyTooHigh
+		);
+		assert_ok!( Tangram::set_royalty( Origin::signed(ALICE), 0, Permill::from_percent(10) ) );
+		assert_eq!( Tangram::realm_royalty(0), Permill::from_percent(10) );
+	});
+}
+
+#[test]
+fn fixed_price_sales_pay_the_royalty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_royalty( Origin::signed(ALICE), 0, Permill::from_percent(10) ) );
+		let item = mint(0, BOB);
+
+		assert_noop!( Tangram::list( Origin::signed(CHARLIE), item, 100 ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::list( Origin::signed(BOB), item, 100 ) );
+
+		// listed items are locked
+		assert_noop!( Tangram::transfer( Origin::signed(BOB), CHARLIE, item ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::burn( Origin::signed(BOB), item ), Error::<Test>::ItemLocked );
+
+		assert_noop!( Tangram::buy( Origin::signed(BOB), item, 100 ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::buy( Origin::signed(CHARLIE), item, 99 ), Error::<Test>::PriceTooLow );
+		assert_ok!( Tangram::buy( Origin::signed(CHARLIE), item, 100 ) );
+
+		assert_eq!( Tangram::owner_of(&item), Some(CHARLIE) );
+		assert_eq!( Balances::free_balance(CHARLIE), 900 );
+		assert_eq!( Balances::free_balance(BOB), 1_090 );
+		assert_eq!( Balances::free_balance(TREASURY), 11 );
+		assert!( Tangram::listing(&item).is_none() );
+		assert!( Tangram::item_lock(&item).is_none() );
+		assert_ok!( Tangram::check_invariants() );
+	});
+}
+
+#[test]
+fn realms_without_a_treasury_take_no_royalty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_royalty( Origin::signed(BOB), 1, Permill::from_percent(10) ) );
+		let wand = Tangram::mint_item( 1, 0, vec![], vec![], &ALICE ).unwrap();
+		assert_ok!( Tangram::list( Origin::signed(ALICE), wand, 100 ) );
+		assert_ok!( Tangram::buy( Origin::signed(CHARLIE), wand, 100 ) );
+		assert_eq!( Balances::free_balance(ALICE), 1_100 );
+	});
+}
+
+#[test]
+fn delisting_unlocks() {
+	new_test_ext().execute_with(|| {
+		let item = mint(0, BOB);
+		assert_ok!( Tangram::list( Origin::signed(BOB), item, 100 ) );
+		assert_noop!( Tangram::delist( Origin::signed(CHARLIE), item ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::delist( Origin::signed(BOB), item ) );
+		assert_ok!( Tangram::transfer( Origin::signed(BOB), CHARLIE, item ) );
+		assert_noop!( Tangram::buy( Origin::signed(ALICE), item, 100 ), Error::<Test>::NotListed );
+	});
+}
+
+#[test]
+fn auctions_settle_to_the_highest_bid() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_royalty( Origin::signed(ALICE), 0, Permill::from_percent(20) ) );
+		let item = mint(0, BOB);
+		assert_noop!( Tangram::start_auction( Origin::signed(BOB), item, 50, 1 ), Error::<Test>::AuctionEnded );
+		assert_ok!( Tangram::start_auction( Origin::signed(BOB), item, 50, 5 ) );
+		assert_noop!( Tangram::buy( Origin::signed(CHARLIE), item, 1_000 ), Error::<Test>::NotListed );
+
+		assert_noop!( Tangram::bid( Origin::signed(CHARLIE), item, 49 ), Error::<Test>::PriceTooLow );
+		assert_ok!( Tangram::bid( Origin::signed(CHARLIE), item, 50 ) );
+		assert_noop!( Tangram::bid( Origin::signed(ALICE), item, 50 ), Error::<Test>::PriceTooLow );
+		assert_ok!( Tangram::bid( Origin::signed(ALICE), item, 100 ) );
+		// the outbid amount is returned
+		assert_eq!( Balances::reserved_balance(CHARLIE), 0 );
+		assert_eq!( Balances::reserved_balance(ALICE), 100 );
+		assert_noop!( Tangram::delist( Origin::signed(BOB), item ), Error::<Test>::AuctionHasBids );
+
+		assert_noop!( Tangram::settle_auction( Origin::signed(CHARLIE), item ), Error::<Test>::AuctionRunning );
+		run_to_block(6);
+		assert_noop!( Tangram::bid( Origin::signed(CHARLIE), item, 200 ), Error::<Test>::AuctionEnded );
+		assert_ok!( Tangram::settle_auction( Origin::signed(CHARLIE), item ) );
+
+		assert_eq!( Tangram::owner_of(&item), Some(ALICE) );
+		assert_eq!( Balances::reserved_balance(ALICE), 0 );
+		assert_eq!( Balances::free_balance(ALICE), 900 );
+		assert_eq!( Balances::free_balance(BOB), 1_080 );
+		assert_eq!( Balances::free_balance(TREASURY), 21 );
+		assert!( Tangram::item_lock(&item).is_none() );
+	});
+}
+
+#[test]
+fn auctions_without_bids_are_delisted() {
+	new_test_ext().execute_with(|| {
+		let item = mint(0, BOB);
+		assert_ok!( Tangram::start_auction( Origin::signed(BOB), item, 50, 5 ) );
+		run_to_block(6);
+		assert_ok!( Tangram::settle_auction( Origin::signed(CHARLIE), item ) );
+		assert_eq!( Tangram::owner_of(&item), Some(BOB) );
+		assert!( Tangram::listing(&item).is_none() );
+		assert!( Tangram::item_lock(&item).is_none() );
+	});
+}
+
+#[test]
+fn offers_are_reserved_and_accepted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!( Tangram::set_royalty( Origin::signed(ALICE), 0, Permill::from_percent(10) ) );
+		let item = mint(0, BOB);
+
+		assert_noop!( Tangram::make_offer( Origin::signed(BOB), item, 10 ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::make_offer( Origin::signed(CHARLIE), item, 0 ), Error::<Test>::PriceTooLow );
+		assert_ok!( Tangram::make_offer( Origin::signed(CHARLIE), item, 10 ) );
+		// a new offer replaces the previous one
+		assert_ok!( Tangram::make_offer( Origin::signed(CHARLIE), item, 200 ) );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 200 );
+		assert_ok!( Tangram::make_offer( Origin::signed(ALICE), item, 100 ) );
+		assert_noop!( Tangram::make_offer( Origin::signed(TREASURY), item, 1 ), Error::<Test>::TooManyOffers );
+
+		assert_ok!( Tangram::withdraw_offer( Origin::signed(ALICE), item ) );
+		assert_eq!( Balances::reserved_balance(ALICE), 0 );
+		assert_noop!( Tangram::accept_offer( Origin::signed(BOB), item, ALICE ), Error::<Test>::UnknownOffer );
+
+		// accepting closes a fixed price listing
+		assert_ok!( Tangram::list( Origin::signed(BOB), item, 1_000 ) );
+		assert_noop!( Tangram::accept_offer( Origin::signed(CHARLIE), item, CHARLIE ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::accept_offer( Origin::signed(BOB), item, CHARLIE ) );
+
+		assert_eq!( Tangram::owner_of(&item), Some(CHARLIE) );
+		assert_eq!( Balances::reserved_balance(CHARLIE), 0 );
+		assert_eq!( Balances::free_balance(CHARLIE), 800 );
+		assert_eq!( Balances::free_balance(BOB), 1_180 );
+		assert_eq!( Balances::free_balance(TREASURY), 21 );
+		assert!( Tangram::listing(&item).is_none() );
+		assert!( Tangram::offers(&item).is_empty() );
+	});
+}
+
+#[test]
+fn auctions_do_not_accept_offers() {
+	new_test_ext().execute_with(|| {
+		let item = mint(0, BOB);
+		assert_ok!( Tangram::make_offer( Origin::signed(CHARLIE), item, 100 ) );
+		assert_ok!( Tangram::start_auction( Origin::signed(BOB), item, 50, 5 ) );
+		assert_noop!( Tangram::accept_offer( Origin::signed(BOB), item, CHARLIE ), Error::<Test>::AuctionRunning );
+	});
+}
+
+//
+//	nesting
+//
+
+#[test]
+fn equipped_items_move_with_their_parent() {
+	new_test_ext().execute_with(|| {
+		let (parent, child, grandchild) = ( mint(0, BOB), mint(1, BOB), mint(1, BOB) );
+		assert_noop!( Tangram::equip( Origin::signed(CHARLIE), parent, child ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), parent, child ) );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), child, grandchild ) );
+		assert_eq!( Tangram::root_item(&grandchild), parent );
+		assert_eq!( Tangram::ancestors(&grandchild), vec![ child, parent ] );
+		assert_eq!( Tangram::item_tree(&parent), vec![ (parent, 0), (child, 1), (grandchild, 2) ] );
+
+		// children only move with their parent
+		assert_noop!( Tangram::transfer( Origin::signed(BOB), CHARLIE, child ), Error::<Test>::ItemEquipped );
+		assert_noop!( Tangram::burn( Origin::signed(BOB), grandchild ), Error::<Test>::ItemEquipped );
+		assert_noop!( Tangram::burn( Origin::signed(BOB), parent ), Error::<Test>::ItemHasChildren );
+
+		assert_ok!( Tangram::transfer( Origin::signed(BOB), CHARLIE, parent ) );
+		for item in &[ parent, child, grandchild ] {
+			assert_eq!( Tangram::owner_of(item), Some(CHARLIE) );
+		}
+		assert_eq!( Tangram::total_for_account(BOB), 0 );
+		assert_eq!( Tangram::total_for_account(CHARLIE), 3 );
+		assert_ok!( Tangram::check_invariants() );
+
+		assert_noop!( Tangram::unequip( Origin::signed(BOB), child ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::unequip( Origin::signed(CHARLIE), parent ), Error::<Test>::NotEquipped );
+		assert_ok!( Tangram::unequip( Origin::signed(CHARLIE), child ) );
+		assert_eq!( Tangram::root_item(&grandchild), child );
+		assert!( Tangram::item_children(&parent).is_empty() );
+		assert_ok!( Tangram::transfer( Origin::signed(CHARLIE), BOB, child ) );
+		assert_eq!( Tangram::owner_of(&grandchild), Some(BOB) );
+	});
+}
+
+#[test]
+fn nesting_is_bounded_and_acyclic() {
+	new_test_ext().execute_with(|| {
+		let items: Vec<_> = (0..5).map(|_| mint(0, BOB)).collect();
+		let (a, b, c, d, e) = ( items[0], items[1], items[2], items[3], items[4] );
+
+		assert_noop!( Tangram::equip( Origin::signed(BOB), a, a ), Error::<Test>::InvalidNesting );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), a, b ) );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), b, c ) );
+		// c is already equipped, and a holds c
+		assert_noop!( Tangram::equip( Origin::signed(BOB), c, a ), Error::<Test>::InvalidNesting );
+		assert_noop!( Tangram::equip( Origin::signed(BOB), c, d ), Error::<Test>::NestingTooDeep );
+
+		assert_ok!( Tangram::equip( Origin::signed(BOB), a, d ) );
+		// a holds b, c, d
+		assert_noop!( Tangram::equip( Origin::signed(BOB), d, e ), Error::<Test>::TreeTooLarge );
+		assert_ok!( Tangram::unequip( Origin::signed(BOB), c ) );
+		assert_noop!( Tangram::equip( Origin::signed(BOB), a, e ), Error::<Test>::TooManyChildren );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), d, e ) );
+	});
+}
+
+#[test]
+fn items_only_hold_items_of_their_realm() {
+	new_test_ext().execute_with(|| {
+		let sword = mint(0, BOB);
+		let wand = Tangram::mint_item( 1, 0, vec![], vec![], &BOB ).unwrap();
+		assert_noop!( Tangram::equip( Origin::signed(BOB), sword, wand ), Error::<Test>::RealmMismatch );
+	});
+}
+
+#[test]
+fn locked_trees_can_not_change() {
+	new_test_ext().execute_with(|| {
+		let (parent, child, other) = ( mint(0, BOB), mint(0, BOB), mint(0, BOB) );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), parent, child ) );
+		assert_ok!( Tangram::list( Origin::signed(BOB), parent, 100 ) );
+		assert_noop!( Tangram::equip( Origin::signed(BOB), parent, other ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::equip( Origin::signed(BOB), child, other ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::unequip( Origin::signed(BOB), child ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::list( Origin::signed(BOB), child, 100 ), Error::<Test>::ItemEquipped );
+
+		// the buyer gets the whole tree
+		assert_ok!( Tangram::buy( Origin::signed(CHARLIE), parent, 100 ) );
+		assert_eq!( Tangram::owner_of(&child), Some(CHARLIE) );
+	});
+}
+
+//
+//	rentals
+//
+
+#[test]
+fn lending_is_validated() {
+	new_test_ext().execute_with(|| {
+		let item = mint(0, BOB);
+		assert_noop!( Tangram::lend( Origin::signed(CHARLIE), item, ALICE, 5, 0 ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::lend( Origin::signed(BOB), item, BOB, 5, 0 ), Error::<Test>::Unauthorized );
+		assert_noop!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 1, 0 ), Error::<Test>::RentalEnded );
+		assert_noop!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 102, 0 ), Error::<Test>::RentalTooLong );
+		assert_ok!( Tangram::list( Origin::signed(BOB), item, 100 ) );
+		assert_noop!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 5, 0 ), Error::<Test>::ItemLocked );
+	});
+}
+
+#[test]
+fn free_rentals_start_right_away_and_expire() {
+	new_test_ext().execute_with(|| {
+		let (item, child) = ( mint(0, BOB), mint(0, BOB) );
+		assert_ok!( Tangram::equip( Origin::signed(BOB), item, child ) );
+		assert_ok!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 5, 0 ) );
+
+		assert_eq!( Tangram::user_of(&item), Some(CHARLIE) );
+		assert_eq!( Tangram::user_of(&child), Some(CHARLIE) );
+		assert_eq!( Tangram::owner_of(&item), Some(BOB) );
+		assert_noop!( Tangram::transfer( Origin::signed(BOB), ALICE, item ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::unequip( Origin::signed(BOB), child ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::list( Origin::signed(BOB), item, 100 ), Error::<Test>::ItemLocked );
+		assert_noop!( Tangram::cancel_lend( Origin::signed(BOB), item ), Error::<Test>::UnknownRental );
+
+		// block 5 is the last block of use
+		run_to_block(5);
+		assert_eq!( Tangram::user_of(&item), Some(CHARLIE) );
+		run_to_block(6);
+		assert_eq!( Tangram::user_of(&item), Some(BOB) );
+		assert!( Tangram::item_lock(&item).is_none() );
+		assert_ok!( Tangram::transfer( Origin::signed(BOB), ALICE, item ) );
+	});
+}
+
+#[test]
+fn paid_rentals_start_on_payment() {
+	new_test_ext().execute_with(|| {
+		let item = mint(0, BOB);
+		assert_ok!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 5, 30 ) );
+		assert_eq!( Tangram::user_of(&item), Some(BOB) );
+		assert_eq!( Tangram::item_lock(&item), Some(ItemLock::Rented) );
+
+		assert_noop!( Tangram::rent( Origin::signed(ALICE), item ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::rent( Origin::signed(CHARLIE), item ) );
+		assert_eq!( Tangram::user_of(&item), Some(CHARLIE) );
+		assert_eq!( Balances::free_balance(CHARLIE), 970 );
+		assert_eq!( Balances::free_balance(BOB), 1_030 );
+		assert_noop!( Tangram::rent( Origin::signed(CHARLIE), item ), Error::<Test>::UnknownRental );
+	});
+}
+
+#[test]
+fn unpaid_rentals_are_cancelled_or_expire() {
+	new_test_ext().execute_with(|| {
+		let (item, other) = ( mint(0, BOB), mint(0, BOB) );
+		assert_ok!( Tangram::lend( Origin::signed(BOB), item, CHARLIE, 5, 30 ) );
+		assert_noop!( Tangram::cancel_lend( Origin::signed(CHARLIE), item ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::cancel_lend( Origin::signed(BOB), item ) );
+		assert!( Tangram::item_lock(&item).is_none() );
+		assert!( Tangram::rentals_ending(6).is_empty() );
+
+		assert_ok!( Tangram::lend( Origin::signed(BOB), other, CHARLIE, 5, 30 ) );
+		run_to_block(6);
+		assert_noop!( Tangram::rent( Origin::signed(CHARLIE), other ), Error::<Test>::UnknownRental );
+		assert!( Tangram::item_lock(&other).is_none() );
+	});
+}
+
+#[test]
+fn rental_expiries_are_carried_over() {
+	new_test_ext().execute_with(|| {
+		let items: Vec<_> = (0..3).map(|_| mint(0, BOB)).collect();
+		for item in &items {
+			assert_ok!( Tangram::lend( Origin::signed(BOB), *item, CHARLIE, 5, 0 ) );
+		}
+		let rented = || items.iter().filter(|item| Tangram::item_lock(item).is_some()).count();
+
+		// the block and one rental fit into the budget of a block
+		run_to_block(6);
+		assert_eq!( rented(), 2 );
+		run_to_block(7);
+		assert_eq!( rented(), 1 );
+		run_to_block(8);
+		assert_eq!( rented(), 0 );
+	});
+}
+
+#[test]
+fn rentals_ending_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		let items: Vec<_> = (0..4).map(|_| mint(0, BOB)).collect();
+		for item in &items[..3] {
+			assert_ok!( Tangram::lend( Origin::signed(BOB), *item, CHARLIE, 5, 0 ) );
+		}
+		assert_noop!(
+			Tangram::lend( Origin::signed(BOB), items[3], CHARLIE, 5, 0 ),
+			Error::<Test>::RentalsPerBlockExceeded
+		);
+		assert_ok!( Tangram::lend( Origin::signed(BOB), items[3], CHARLIE, 6, 0 ) );
+	});
+}
+
+//
+//	operators
+//
+
+#[test]
+fn operators_mint_and_move_items_of_their_realm() {
+	new_test_ext().execute_with(|| {
+		let operator = 42;
+		assert_noop!(
+			Tangram::operator_mint( &operator, 0, 0, vec![], vec![], &BOB ),
+			Error::<Test>::NotOperator
+		);
+		assert_noop!( Tangram::set_operator( Origin::signed(BOB), 0, operator, true ), Error::<Test>::Unauthorized );
+		assert_ok!( Tangram::set_operator( Origin::signed(ALICE), 0, operator, true ) );
+
+		let item = Tangram::operator_mint( &operator, 0, 0, vec![], vec![], &BOB ).unwrap();
+		assert_noop!(
+			Tangram::operator_mint( &operator, 1, 0, vec![], vec![], &BOB ),
+			Error::<Test>::NotOperator
+		);
+
+		// on behalf of the owner only
+		assert_noop!(
+			Tangram::operator_transfer( &operator, &CHARLIE, &item, &CHARLIE ),
+			Error::<Test>::Unauthorized
+		);
+		assert_ok!( Tangram::operator_transfer( &operator, &BOB, &item, &CHARLIE ) );
+		assert_eq!( Tangram::owner_of(&item), Some(CHARLIE) );
+
+		// items of other realms only when owned by the operator
+		let wand = Tangram::mint_item( 1, 0, vec![], vec![], &CHARLIE ).unwrap();
+		assert_noop!(
+			Tangram::operator_transfer( &operator, &CHARLIE, &wand, &BOB ),
+			Error::<Test>::NotOperator
+		);
+		let own = Tangram::mint_item( 1, 0, vec![], vec![], &operator ).unwrap();
+		assert_ok!( Tangram::operator_transfer( &operator, &CHARLIE, &own, &BOB ) );
+
+		assert_ok!( Tangram::set_operator( Origin::signed(ALICE), 0, operator, false ) );
+		assert!( !Tangram::is_operator((0, operator)) );
+	});
+}
+
+#[test]
+fn v2_migration_adds_rarity_to_class_metadata() {
+	new_test_ext().execute_with(|| {
+		// class metadata as a V1 chain stored it
+		let old = OldClassMetadata {
+			id: H256::repeat_byte(1), name: b"sword".to_vec(), cid: b"cid".to_vec(), created: 3u64, mutated: 4u64, f: vec![],
+		};
+		unhashed::put( &ClassMetadata::<Test>::hashed_key_for((0, 0)), &old );
+		put_storage_value( b"Tangram50", b"StorageVersion", &[], Releases::V1 );
+
+		Tangram::on_runtime_upgrade();
+		assert_eq!( Tangram::storage_version(), Releases::V2 );
+		assert_eq!( Tangram::class_metadata((0, 0)), TangramClassMetadata {
+			id: H256::repeat_byte(1), name: b"sword".to_vec(), cid: b"cid".to_vec(), created: 3, mutated: 4, f: vec![],
+			rarity: RarityTable::default(),
+		});
+		assert_ok!( Tangram::check_invariants() );
+
+		// the rarity is set on top of the migrated metadata
+		let table = RarityTable { epic: 100, rare: 0, high: 0, halving: 0 };
+		assert_ok!( Tangram::set_rarity( Origin::signed(ALICE), 0, 0, table ) );
+		assert_eq!( Tangram::class_metadata((0, 0)).name, b"sword".to_vec() );
+		assert_eq!( Tangram::rarity_table(0, 0), table );
+	});
+}